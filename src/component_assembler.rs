@@ -1,26 +1,117 @@
-use crate::components::logger::TokioLogger;
+use std::path::Path;
+use crate::components::logger::{LogLevel, TokioLogger, level_from_flags};
 use crate::components::engine::EngineImpl;
 use crate::components::init::InitImpl;
 use crate::components::load::LoadImpl;
 use crate::components::file_system::DiskFileSystem;
-use crate::components::project_serialization::YamlProjectSerialization;
-use crate::components::project_io::YamlProjectIO;
+use crate::components::project_serialization::{JsonProjectSerialization, YamlProjectSerialization};
+use crate::components::project_io::{FormatProjectIO, YamlProjectIO};
 use crate::components::csv_parser::CsvParserImpl;
-use crate::components::table_reader::CsvTableReader;
-use crate::components::table_reader::CmdCsvTableReader;
+use crate::components::table_reader::{
+    CsvTableReader, HttpCsvTableReader, JsonTableReader, ParquetTableReader, StdinCsvTableReader, XlsxTableReader,
+};
+use crate::components::table_reader::{CmdCsvTableReader, DEFAULT_MAX_CMD_PROCESSES};
+use crate::components::freeze::FreezeImpl;
+use crate::components::table_writer::{CsvTableWriter, PostgresTableWriter, SqliteTableWriter};
+use crate::components::validate::ValidateImpl;
+use crate::components::fmt::FmtImpl;
+use crate::components::ddl::DdlImpl;
+use crate::models::{ConnectionString, Dialect};
 use crate::traits::{
     Engine, ProjectIO, ProjectSerialization, Init, Load, Logger, FileSystem, CsvParser, TableReader,
+    Freeze, TableWriter, Validate, Fmt, Ddl,
 };
 
-pub struct ComponentAssembler;
+fn short_type_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn env_var_line(name: &str) -> String {
+    format!("  {name}={}\n", std::env::var(name).unwrap_or_else(|_| "(unset)".to_string()))
+}
+
+pub struct ComponentAssembler {
+    max_cmd_processes: usize,
+    log_level: Option<LogLevel>,
+    allow_outside_project: bool,
+}
+
+impl Default for ComponentAssembler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl ComponentAssembler {
     pub fn new() -> Self {
-        ComponentAssembler
+        ComponentAssembler {
+            max_cmd_processes: DEFAULT_MAX_CMD_PROCESSES,
+            log_level: None,
+            allow_outside_project: false,
+        }
+    }
+
+    pub fn with_max_cmd_processes(mut self, max_cmd_processes: usize) -> Self {
+        self.max_cmd_processes = max_cmd_processes;
+        self
+    }
+
+    /// Let file sources read paths that resolve outside the project directory.
+    pub fn with_allow_outside_project(mut self, allow_outside_project: bool) -> Self {
+        self.allow_outside_project = allow_outside_project;
+        self
+    }
+
+    /// Log at the level picked by `-v`/`-q` style flags; without either the level comes from `RUST_LOG`.
+    pub fn with_log_flags(mut self, verbose: u8, quiet: bool) -> Self {
+        self.log_level = level_from_flags(verbose, quiet);
+        self
+    }
+
+    fn tokio_logger(&self) -> TokioLogger {
+        match self.log_level {
+            Some(level) => TokioLogger::with_level(level),
+            None => TokioLogger::new(),
+        }
     }
 
     pub fn logger(&self) -> Box<dyn Logger> {
-        Box::new(TokioLogger::new())
+        Box::new(self.tokio_logger())
+    }
+
+    /// Describes the concrete components this assembler wires together, for `dbloada doctor`.
+    pub fn doctor_report(&self) -> String {
+        let logger = self.tokio_logger();
+        let mut out = String::new();
+        out.push_str(&format!(
+            "logger: {} (level: {:?}, format: {:?})\n",
+            short_type_name::<TokioLogger>(),
+            logger.level(),
+            logger.format()
+        ));
+        out.push_str(&format!("file system: {}\n", short_type_name::<DiskFileSystem>()));
+        out.push_str(&format!(
+            "project serialization: {} (.yaml), {} (.json)\n",
+            short_type_name::<YamlProjectSerialization>(),
+            short_type_name::<JsonProjectSerialization>()
+        ));
+        out.push_str(&format!("project io: {}\n", short_type_name::<FormatProjectIO>()));
+        out.push_str(&format!("csv parser: {}\n", short_type_name::<CsvParserImpl>()));
+        out.push_str(&format!("max cmd processes: {}\n", self.max_cmd_processes));
+        out.push_str(&format!("allow paths outside project: {}\n", self.allow_outside_project));
+        out.push_str("table readers:\n");
+        for reader in self.table_readers() {
+            let extensions = match reader.extensions() {
+                [] => "(by source type)".to_string(),
+                extensions => extensions.join(", "),
+            };
+            out.push_str(&format!("  {}: {}\n", reader.name(), extensions));
+        }
+        out.push_str("environment:\n");
+        out.push_str(&env_var_line("RUST_LOG"));
+        out.push_str(&env_var_line("DBLOADA_LOG_FORMAT"));
+        out
     }
 
     pub fn init(&self) -> Box<dyn Init> {
@@ -37,13 +128,78 @@ impl ComponentAssembler {
 
     pub fn table_readers(&self) -> Vec<Box<dyn TableReader>> {
         vec![
-            Box::new(CsvTableReader::new(self.logger(), self.file_system(), self.csv_parser())),
-            Box::new(CmdCsvTableReader::new(self.logger(), self.csv_parser())),
+            Box::new(CsvTableReader::new(
+                self.logger(),
+                self.file_system(),
+                self.csv_parser(),
+                self.allow_outside_project,
+            )),
+            Box::new(JsonTableReader::new(self.logger(), self.file_system(), self.allow_outside_project)),
+            Box::new(ParquetTableReader::new(self.logger(), self.file_system(), self.allow_outside_project)),
+            Box::new(XlsxTableReader::new(self.logger(), self.file_system(), self.allow_outside_project)),
+            Box::new(CmdCsvTableReader::new(self.logger(), self.csv_parser(), self.max_cmd_processes)),
+            Box::new(HttpCsvTableReader::new(self.logger(), self.csv_parser())),
+            Box::new(StdinCsvTableReader::new(self.logger(), self.csv_parser())),
         ]
     }
 
+    pub fn sqlite_table_writer(&self, path: &Path) -> Box<dyn TableWriter> {
+        Box::new(SqliteTableWriter::new(self.logger(), path))
+    }
+
+    pub fn postgres_table_writer(&self, url: &str) -> Box<dyn TableWriter> {
+        Box::new(PostgresTableWriter::new(self.logger(), url))
+    }
+
+    /// The writer for the database a connection string points at, if there is one for its dialect.
+    pub fn database_table_writer(&self, connection: &ConnectionString) -> Option<Box<dyn TableWriter>> {
+        match (connection.dialect, &connection.database) {
+            (Dialect::Postgres, _) => Some(self.postgres_table_writer(&connection.url)),
+            (Dialect::Sqlite, Some(path)) => Some(self.sqlite_table_writer(Path::new(path))),
+            (Dialect::Sqlite, None) | (Dialect::Mysql, _) => None,
+        }
+    }
+
+    pub fn csv_table_writer(&self, out_dir: &Path, header: bool) -> Box<dyn TableWriter> {
+        Box::new(CsvTableWriter::new(self.logger(), self.file_system(), out_dir, header))
+    }
+
     pub fn engine(&self) -> Box<dyn Engine> {
-        Box::new(EngineImpl::new(self.logger(), self.init(), self.load()))
+        Box::new(EngineImpl::new(
+            self.logger(),
+            self.init(),
+            self.load(),
+            self.freeze(),
+            self.validate(),
+            self.fmt(),
+            self.ddl(),
+        ))
+    }
+
+    pub fn validate(&self) -> Box<dyn Validate> {
+        Box::new(ValidateImpl::new(self.logger(), self.project_io()))
+    }
+
+    pub fn fmt(&self) -> Box<dyn Fmt> {
+        Box::new(FmtImpl::new(
+            self.logger(),
+            self.file_system(),
+            self.project_serialization(),
+            self.json_project_serialization(),
+        ))
+    }
+
+    pub fn ddl(&self) -> Box<dyn Ddl> {
+        Box::new(DdlImpl::new(self.logger(), self.project_io(), self.file_system()))
+    }
+
+    pub fn freeze(&self) -> Box<dyn Freeze> {
+        Box::new(FreezeImpl::new(
+            self.logger(),
+            self.project_io(),
+            self.file_system(),
+            self.table_readers(),
+        ))
     }
 
     pub fn file_system(&self) -> Box<dyn FileSystem> {
@@ -54,11 +210,38 @@ impl ComponentAssembler {
         Box::new(YamlProjectSerialization::new(self.logger()))
     }
 
+    pub fn json_project_serialization(&self) -> Box<dyn ProjectSerialization> {
+        Box::new(JsonProjectSerialization::new(self.logger()))
+    }
+
+    /// Reads and writes `.json` project files as JSON and all others as YAML.
     pub fn project_io(&self) -> Box<dyn ProjectIO> {
-        Box::new(YamlProjectIO::new(
-            self.logger(),
-            self.file_system(),
-            self.project_serialization(),
+        Box::new(FormatProjectIO::new(
+            Box::new(YamlProjectIO::new(self.logger(), self.file_system(), self.project_serialization())),
+            Box::new(YamlProjectIO::new(self.logger(), self.file_system(), self.json_project_serialization())),
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn doctor_report_lists_table_readers() {
+        let report = ComponentAssembler::new().with_max_cmd_processes(3).doctor_report();
+        assert!(report.contains("  csv: .csv, .csv.gz\n"), "report was:\n{report}");
+        assert!(report.contains("  cmd_csv: (by source type)\n"), "report was:\n{report}");
+        assert!(
+            report.contains("project serialization: YamlProjectSerialization (.yaml), JsonProjectSerialization (.json)\n"),
+            "report was:\n{report}"
+        );
+        assert!(report.contains("max cmd processes: 3\n"), "report was:\n{report}");
+    }
+
+    #[test]
+    fn doctor_report_shows_log_level_override() {
+        let report = ComponentAssembler::new().with_log_flags(1, false).doctor_report();
+        assert!(report.starts_with("logger: TokioLogger (level: Debug, "), "report was:\n{report}");
+    }
+}
@@ -1,7 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
 use async_trait::async_trait;
-use crate::models::{ColumnIdentifier, Table, TableSpec};
-use crate::traits::{Logger, CsvParser, CsvParserError};
+use regex::Regex;
+use crate::models::{
+    ColumnIdentifier, ColumnSpec, ColumnType, CsvOptions, RAW_LINE_COLUMN, RaggedRowMode, RowFilter, Table, TableSpec,
+    TrimMode,
+};
+use crate::traits::{Logger, CsvParser, CsvParserError, RowCallback};
 
 pub struct CsvParserImpl {
     logger: Box<dyn Logger>,
@@ -13,70 +18,383 @@ impl CsvParserImpl {
     }
 }
 
-pub fn strip_csv_field(field: &str) -> String {
+const COMMON_DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+
+/// When a row parsed into a single field, returns the most frequent other
+/// common delimiter found in it, which is most likely the one the file uses.
+pub fn suggest_delimiter(record: &csv::StringRecord, delimiter: u8) -> Option<char> {
+    if record.len() != 1 {
+        return None;
+    }
+    let line = record.get(0).unwrap_or("");
+    COMMON_DELIMITERS
+        .iter()
+        .filter(|&&c| c as u8 != delimiter)
+        .map(|&c| (c, line.matches(c).count()))
+        .filter(|&(_, count)| count > 0)
+        .max_by_key(|&(_, count)| count)
+        .map(|(c, _)| c)
+}
+
+pub fn csv_trim(mode: TrimMode) -> csv::Trim {
+    match mode {
+        TrimMode::None => csv::Trim::None,
+        TrimMode::Headers => csv::Trim::Headers,
+        TrimMode::Fields => csv::Trim::Fields,
+        TrimMode::All => csv::Trim::All,
+    }
+}
+
+/// Trims `field` when `trim` is set. Unless `keep_quotes`, a field whose opening quote follows
+/// whitespace (`"a", "b"`) loses that pair of quotes, which the CSV reader kept as part of the value.
+pub fn clean_field(field: &str, trim: bool, keep_quotes: bool) -> String {
     let trimmed = field.trim();
-    trimmed
-        .strip_prefix('"')
-        .and_then(|s| s.strip_suffix('"'))
-        .unwrap_or(trimmed)
-        .to_string()
+    if !keep_quotes
+        && field.starts_with(char::is_whitespace)
+        && let Some(unquoted) = trimmed.strip_prefix('"').and_then(|s| s.strip_suffix('"'))
+    {
+        return unquoted.to_string();
+    }
+    if trim { trimmed } else { field }.to_string()
+}
+
+/// The trimming left to [`clean_field`]: without `keep_quotes` the reader must see the whitespace
+/// in front of an opening quote, so it trims nothing itself.
+fn reader_trim(csv_options: &CsvOptions) -> csv::Trim {
+    if csv_options.keep_quotes { csv_trim(csv_options.trim) } else { csv::Trim::None }
+}
+
+/// Whether [`clean_field`] still has to trim headers and fields, after [`reader_trim`].
+fn field_trims(csv_options: &CsvOptions) -> (bool, bool) {
+    if csv_options.keep_quotes {
+        return (false, false);
+    }
+    match csv_options.trim {
+        TrimMode::None => (false, false),
+        TrimMode::Headers => (true, false),
+        TrimMode::Fields => (false, true),
+        TrimMode::All => (true, true),
+    }
+}
+
+fn delimiter_display(c: char) -> String {
+    match c {
+        '\t' => "\\t".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn resolve_column_index(
+    table: &TableSpec,
+    col: &ColumnSpec,
+    header_map: &Option<HashMap<String, usize>>,
+) -> Result<usize, CsvParserError> {
+    let parse_error = |message: String| CsvParserError::ParseError { table_name: table.name.clone(), message };
+    match &col.column_identifier {
+        ColumnIdentifier::Index(i) => Ok(*i as usize),
+        ColumnIdentifier::Name(name) => {
+            let map = header_map.as_ref().ok_or_else(|| parse_error(format!(
+                "column '{}' uses name identifier '{}' but has_header is false",
+                col.name, name
+            )))?;
+            match matching_header_name(col, name, map).map_err(parse_error)? {
+                Some((_, index)) => Ok(index),
+                None if col.aliases.is_empty() => Err(parse_error(format!(
+                    "column '{}' references header '{}' which was not found in CSV headers",
+                    col.name, name
+                ))),
+                None => Err(parse_error(format!(
+                    "column '{}' references header '{}' or its aliases '{}', none of which were found in CSV headers",
+                    col.name,
+                    name,
+                    col.aliases.join("', '")
+                ))),
+            }
+        }
+        ColumnIdentifier::Capture { source, .. } => {
+            let source_col = table
+                .columns
+                .iter()
+                .find(|c| &c.name == source && !matches!(c.column_identifier, ColumnIdentifier::Capture { .. }))
+                .ok_or_else(|| parse_error(format!(
+                    "column '{}' captures from '{}', which is not a name or index column of this table",
+                    col.name, source
+                )))?;
+            resolve_column_index(table, source_col, header_map)
+        }
+    }
+}
+
+/// Maps each header name to its index, failing when a name occurs more than once.
+pub fn build_header_map(table: &TableSpec, headers: &csv::StringRecord) -> Result<HashMap<String, usize>, CsvParserError> {
+    let mut map = HashMap::new();
+    let mut duplicates: Vec<String> = Vec::new();
+    for (i, h) in headers.iter().enumerate() {
+        if map.insert(h.to_string(), i).is_some() && !duplicates.iter().any(|d| d == h) {
+            duplicates.push(h.to_string());
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(CsvParserError::ParseError {
+            table_name: table.name.clone(),
+            message: format!("duplicate CSV header(s): {}", duplicates.join(", ")),
+        });
+    }
+    Ok(map)
+}
+
+/// The index of the header matching `name`, honoring the column's `ignore_header_case`.
+fn find_header(col: &ColumnSpec, name: &str, map: &HashMap<String, usize>) -> Result<Option<usize>, String> {
+    if !col.ignore_header_case {
+        return Ok(map.get(name).copied());
+    }
+    let lowercase = name.to_lowercase();
+    let mut matches: Vec<(&String, &usize)> =
+        map.iter().filter(|(header, _)| header.to_lowercase() == lowercase).collect();
+    matches.sort_by_key(|(_, i)| **i);
+    match matches.as_slice() {
+        [] => Ok(None),
+        [(_, i)] => Ok(Some(**i)),
+        _ => Err(format!(
+            "column '{}' references header '{}', which matches several headers when ignoring case: {}",
+            col.name,
+            name,
+            matches.iter().map(|(h, _)| h.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// The first of `name` and the column's aliases found in the headers, with its index.
+fn matching_header_name<'a>(
+    col: &'a ColumnSpec,
+    name: &'a str,
+    map: &HashMap<String, usize>,
+) -> Result<Option<(&'a str, usize)>, String> {
+    for candidate in std::iter::once(name).chain(col.aliases.iter().map(String::as_str)) {
+        if let Some(index) = find_header(col, candidate, map)? {
+            return Ok(Some((candidate, index)));
+        }
+    }
+    Ok(None)
+}
+
+/// Columns whose header was found through an alias rather than the name identifier,
+/// as `(column name, alias)` pairs.
+pub fn matched_aliases<'a>(
+    table: &'a TableSpec,
+    header_map: &Option<HashMap<String, usize>>,
+) -> Vec<(&'a str, &'a str)> {
+    let Some(map) = header_map else {
+        return vec![];
+    };
+    table
+        .columns
+        .iter()
+        .filter_map(|col| match &col.column_identifier {
+            ColumnIdentifier::Name(name) => match matching_header_name(col, name, map) {
+                Ok(Some((candidate, _))) if candidate != name => Some((col.name.as_str(), candidate)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
 }
 
+/// Maps every column to the field it reads. Capture columns read the field of their source column.
 pub fn resolve_column_indices(
     table: &TableSpec,
     header_map: &Option<HashMap<String, usize>>,
 ) -> Result<Vec<usize>, CsvParserError> {
-    let mut indices = Vec::with_capacity(table.columns.len());
-    for col in &table.columns {
-        let idx = match &col.column_identifier {
-            ColumnIdentifier::Index(i) => *i as usize,
-            ColumnIdentifier::Name(name) => {
-                let map = header_map.as_ref().ok_or_else(|| CsvParserError::ParseError {
-                    table_name: table.name.clone(),
-                    message: format!(
-                        "column '{}' uses name identifier '{}' but has_header is false",
-                        col.name, name
-                    ),
-                })?;
-                *map.get(name).ok_or_else(|| CsvParserError::ParseError {
+    table.columns.iter().map(|col| resolve_column_index(table, col, header_map)).collect()
+}
+
+/// Compiles the pattern of each capture column; `None` for all other columns.
+pub fn compile_captures(table: &TableSpec) -> Result<Vec<Option<(Regex, usize)>>, CsvParserError> {
+    table
+        .columns
+        .iter()
+        .map(|col| match &col.column_identifier {
+            ColumnIdentifier::Capture { pattern, group, .. } => Regex::new(pattern)
+                .map(|re| Some((re, *group)))
+                .map_err(|e| CsvParserError::ParseError {
                     table_name: table.name.clone(),
-                    message: format!(
-                        "column '{}' references header '{}' which was not found in CSV headers",
-                        col.name, name
-                    ),
-                })?
-            }
-        };
-        indices.push(idx);
+                    message: format!("column '{}' has an invalid pattern: {}", col.name, e),
+                }),
+            _ => Ok(None),
+        })
+        .collect()
+}
+
+/// Replaces the value of each capture column with its capture group, or empty if it does not match.
+pub fn apply_captures(row: &mut [String], captures: &[Option<(Regex, usize)>]) {
+    for (value, capture) in row.iter_mut().zip(captures) {
+        if let Some((re, group)) = capture {
+            *value = re
+                .captures(value)
+                .and_then(|c| c.get(*group))
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+        }
+    }
+}
+
+/// The table's filter with the position of its column in a row; `None` without a filter.
+pub fn compile_filter(table: &TableSpec) -> Result<Option<(usize, &RowFilter)>, CsvParserError> {
+    let Some(filter) = &table.filter else {
+        return Ok(None);
+    };
+    let index = table.columns.iter().position(|c| c.name == filter.column).ok_or_else(|| CsvParserError::ParseError {
+        table_name: table.name.clone(),
+        message: format!("filter references unknown column '{}'", filter.column),
+    })?;
+    Ok(Some((index, filter)))
+}
+
+pub fn passes_filter(row: &[String], filter: Option<(usize, &RowFilter)>) -> bool {
+    filter.is_none_or(|(index, filter)| filter.matches(row.get(index).map_or("", String::as_str)))
+}
+
+/// Drops the rows that don't match the table's filter.
+pub fn filter_rows(table: &TableSpec, rows: &mut Vec<Vec<String>>) -> Result<(), CsvParserError> {
+    let filter = compile_filter(table)?;
+    rows.retain(|row| passes_filter(row, filter));
+    Ok(())
+}
+
+/// The positions in a row of the table's `dedup_by` columns.
+pub fn compile_dedup(table: &TableSpec) -> Result<Vec<usize>, CsvParserError> {
+    table
+        .dedup_by
+        .iter()
+        .map(|name| {
+            table.columns.iter().position(|c| &c.name == name).ok_or_else(|| CsvParserError::ParseError {
+                table_name: table.name.clone(),
+                message: format!("dedupBy references unknown column '{name}'"),
+            })
+        })
+        .collect()
+}
+
+/// Whether `row` is the first with its values in the `key` columns. Always true without key columns.
+pub fn first_occurrence(row: &[String], key: &[usize], seen: &mut HashSet<Vec<String>>) -> bool {
+    key.is_empty() || seen.insert(key.iter().map(|&i| row.get(i).cloned().unwrap_or_default()).collect())
+}
+
+/// Drops rows repeating an earlier row's `dedup_by` values and returns how many were dropped.
+pub fn dedup_rows(table: &TableSpec, rows: &mut Vec<Vec<String>>) -> Result<usize, CsvParserError> {
+    let key = compile_dedup(table)?;
+    let mut seen = HashSet::new();
+    let before = rows.len();
+    rows.retain(|row| first_occurrence(row, &key, &mut seen));
+    Ok(before - rows.len())
+}
+
+pub fn dedup_message(table: &TableSpec, duplicates: usize) -> String {
+    format!("table '{}': dropped {} duplicate row(s) by {}", table.name, duplicates, table.dedup_by.join(", "))
+}
+
+/// Returns the expected field count when a row of `actual` fields breaks the
+/// `mode`. `width` is the header's field count and `required` one past the
+/// highest referenced column index.
+pub fn ragged_row_violation(mode: RaggedRowMode, actual: usize, width: usize, required: usize) -> Option<usize> {
+    let expected = width.max(required);
+    let violated = match mode {
+        RaggedRowMode::Pad => false,
+        RaggedRowMode::Truncate => actual < expected,
+        RaggedRowMode::Error => actual != expected,
+    };
+    violated.then_some(expected)
+}
+
+/// Lists the collected row errors, noting how many more occurred beyond them.
+pub fn summarize_row_errors(errors: &[String], total: usize) -> String {
+    let mut summary = format!("{} row error(s): {}", total, errors.join("; "));
+    if total > errors.len() {
+        summary.push_str(&format!("; and {} more", total - errors.len()));
+    }
+    summary
+}
+
+/// `content` without its first `n` lines.
+pub fn skip_lines(content: &str, n: usize) -> &str {
+    let mut rest = content;
+    for _ in 0..n {
+        rest = rest.split_once('\n').map_or("", |(_, after)| after);
+    }
+    rest
+}
+
+/// Consumes the first `n` lines of `input`.
+fn skip_input_lines(input: Box<dyn Read + Send>, n: usize) -> std::io::Result<Box<dyn Read + Send>> {
+    if n == 0 {
+        return Ok(input);
+    }
+    let mut reader = BufReader::new(input);
+    let mut line = Vec::new();
+    for _ in 0..n {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
     }
-    Ok(indices)
+    Ok(Box::new(reader))
 }
 
-pub fn extract_row(record: &csv::StringRecord, indices: &[usize]) -> Vec<String> {
+pub fn extract_row(record: &csv::StringRecord, indices: &[usize], csv_options: &CsvOptions) -> Vec<String> {
+    let (_, trim) = field_trims(csv_options);
     indices
         .iter()
-        .map(|&i| strip_csv_field(record.get(i).unwrap_or("")))
+        .map(|&i| clean_field(record.get(i).unwrap_or(""), trim, csv_options.keep_quotes))
         .collect()
 }
 
-#[async_trait]
-impl CsvParser for CsvParserImpl {
-    async fn parse(&self, content: &str, table: &TableSpec) -> Result<Table, CsvParserError> {
+/// The text of a record spanning `start..end` in `content`, without line terminators
+/// (after a CRLF the reader may place the next record start on the `\n`).
+pub fn raw_line(content: &str, start: u64, end: u64) -> String {
+    content
+        .get(start as usize..end as usize)
+        .unwrap_or("")
+        .trim_matches(['\r', '\n'])
+        .to_string()
+}
+
+/// The spec's columns, plus the raw line column when the spec keeps raw lines.
+fn output_table(table: &TableSpec, rows: Vec<Vec<String>>) -> Table {
+    let mut result = Table::from_spec(table, rows);
+    if table.keep_raw_line {
+        result.columns.push(RAW_LINE_COLUMN.to_string());
+        result.column_types.push(ColumnType::String { max_length: None });
+        result.nullable.push(false);
+    }
+    result
+}
+
+impl CsvParserImpl {
+    async fn resolve_header<R: Read + Send>(
+        &self,
+        input: R,
+        table: &TableSpec,
+    ) -> Result<(csv::Reader<R>, Vec<usize>), CsvParserError> {
+        let csv_options = table.source.csv_options();
+        let delimiter = csv_options.delimiter_byte();
         let mut reader = csv::ReaderBuilder::new()
             .has_headers(table.has_header)
-            .trim(csv::Trim::All)
-            .from_reader(content.as_bytes());
+            .flexible(true)
+            .delimiter(delimiter)
+            .comment(csv_options.comment_byte())
+            .trim(reader_trim(csv_options))
+            .from_reader(input);
 
         let header_map = if table.has_header {
             let headers = reader.headers().map_err(|e| CsvParserError::ParseError {
                 table_name: table.name.clone(),
                 message: format!("failed to parse CSV headers: {}", e),
             })?;
-            let map: HashMap<String, usize> = headers
-                .iter()
-                .enumerate()
-                .map(|(i, h)| (strip_csv_field(h), i))
-                .collect();
+            self.warn_on_suspicious_delimiter(table, headers, delimiter).await;
+            let (trim, _) = field_trims(csv_options);
+            let headers: csv::StringRecord =
+                headers.iter().map(|h| clean_field(h, trim, csv_options.keep_quotes)).collect();
+            let map = build_header_map(table, &headers)?;
             self.logger.debug(&format!("CSV headers: {:?}", map)).await;
             Some(map)
         } else {
@@ -84,23 +402,183 @@ impl CsvParser for CsvParserImpl {
         };
 
         let indices = resolve_column_indices(table, &header_map)?;
+        for (column, alias) in matched_aliases(table, &header_map) {
+            self.logger.debug(&format!("column '{column}' matched header alias '{alias}'")).await;
+        }
         self.logger.debug(&format!(
             "column mapping: {:?}",
             table.columns.iter().map(|c| &c.name).zip(indices.iter()).collect::<Vec<_>>()
         )).await;
+        Ok((reader, indices))
+    }
+
+    /// Parses records from `input`. `content` is the same CSV as a string, needed for
+    /// raw lines; without it, raw line cells are empty.
+    async fn parse_rows_from<R: Read + Send>(
+        &self,
+        input: R,
+        content: Option<&str>,
+        table: &TableSpec,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, CsvParserError> {
+        let csv_options = table.source.csv_options();
+        let delimiter = csv_options.delimiter_byte();
+        let (mut reader, indices) = self.resolve_header(input, table).await?;
+        let captures = compile_captures(table)?;
+        let filter = compile_filter(table)?;
+        let dedup_key = compile_dedup(table)?;
+        let mut seen = HashSet::new();
+        let mut duplicates = 0;
+        let required = indices.iter().max().map_or(0, |&i| i + 1);
+        let mut width = if table.has_header {
+            reader.headers().map(|h| h.len()).ok()
+        } else {
+            None
+        };
+
+        let parse_error = |message: String| CsvParserError::ParseError { table_name: table.name.clone(), message };
+        let tolerated = csv_options.max_errors.filter(|&max| max > 0);
+        let mut errors = Vec::new();
+        let mut error_count = 0;
+        let mut record_count = 0;
+        let mut row_count = 0;
+        let mut record = csv::StringRecord::new();
+        while csv_options.limit.is_none_or(|limit| row_count < limit) {
+            let before = reader.position().byte();
+            let problem = match reader.read_record(&mut record) {
+                Ok(false) => break,
+                Ok(true) => {
+                    record_count += 1;
+                    if record_count == 1 && !table.has_header {
+                        self.warn_on_suspicious_delimiter(table, &record, delimiter).await;
+                    }
+                    let expected_width = *width.get_or_insert(record.len());
+                    ragged_row_violation(csv_options.on_ragged_row, record.len(), expected_width, required).map(|expected| {
+                        format!(
+                            "row {} (line {}) has {} field(s), expected {}",
+                            record_count,
+                            record.position().map_or(0, |p| p.line()),
+                            record.len(),
+                            expected,
+                        )
+                    })
+                }
+                Err(e) => {
+                    record_count += 1;
+                    let message = format!("failed to parse CSV record: {}", e);
+                    if reader.position().byte() == before {
+                        // the reader cannot move past this error
+                        return Err(parse_error(message));
+                    }
+                    Some(message)
+                }
+            };
+            if let Some(message) = problem {
+                let Some(max) = tolerated else {
+                    return Err(parse_error(message));
+                };
+                error_count += 1;
+                if errors.len() < max {
+                    errors.push(message);
+                }
+                continue;
+            }
+            let mut row = extract_row(&record, &indices, csv_options);
+            apply_captures(&mut row, &captures);
+            if !passes_filter(&row, filter) {
+                continue;
+            }
+            if !first_occurrence(&row, &dedup_key, &mut seen) {
+                duplicates += 1;
+                continue;
+            }
+            if table.keep_raw_line {
+                let start = record.position().map(|p| p.byte()).unwrap_or(0);
+                row.push(content.map_or(String::new(), |c| raw_line(c, start, reader.position().byte())));
+            }
+            on_row(row);
+            row_count += 1;
+        }
+        if error_count > 0 {
+            return Err(parse_error(summarize_row_errors(&errors, error_count)));
+        }
+        if duplicates > 0 {
+            self.logger.info(&dedup_message(table, duplicates)).await;
+        }
+        Ok(row_count)
+    }
+
+    fn skip_input_lines(&self, input: Box<dyn Read + Send>, table: &TableSpec) -> Result<Box<dyn Read + Send>, CsvParserError> {
+        skip_input_lines(input, table.source.csv_options().skip_rows).map_err(|e| CsvParserError::ParseError {
+            table_name: table.name.clone(),
+            message: format!("failed to skip leading rows: {}", e),
+        })
+    }
+
+    async fn warn_on_suspicious_delimiter(&self, table: &TableSpec, record: &csv::StringRecord, delimiter: u8) {
+        if let Some(suggested) = suggest_delimiter(record, delimiter) {
+            self.logger.warn(&format!(
+                "table '{}': the first row has a single field but contains '{}'; did you mean delimiter: \"{}\"?",
+                table.name,
+                delimiter_display(suggested),
+                delimiter_display(suggested),
+            )).await;
+        }
+    }
+}
 
+#[async_trait]
+impl CsvParser for CsvParserImpl {
+    async fn parse(&self, content: &str, table: &TableSpec) -> Result<Table, CsvParserError> {
         let mut rows = Vec::new();
-        for result in reader.records() {
-            let record = result.map_err(|e| CsvParserError::ParseError {
+        self.parse_rows(content, table, &mut |row| rows.push(row)).await?;
+        Ok(output_table(table, rows))
+    }
+
+    async fn parse_rows(
+        &self,
+        content: &str,
+        table: &TableSpec,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, CsvParserError> {
+        let content = skip_lines(content, table.source.csv_options().skip_rows);
+        self.parse_rows_from(content.as_bytes(), Some(content), table, on_row).await
+    }
+
+    async fn parse_header(&self, content: &str, table: &TableSpec) -> Result<Table, CsvParserError> {
+        let content = skip_lines(content, table.source.csv_options().skip_rows);
+        self.resolve_header(content.as_bytes(), table).await?;
+        compile_captures(table)?;
+        Ok(output_table(table, vec![]))
+    }
+
+    async fn column_indices(&self, content: &str, table: &TableSpec) -> Result<Vec<usize>, CsvParserError> {
+        let content = skip_lines(content, table.source.csv_options().skip_rows);
+        let (_, indices) = self.resolve_header(content.as_bytes(), table).await?;
+        Ok(indices)
+    }
+
+    async fn parse_rows_from_reader(
+        &self,
+        input: Box<dyn Read + Send>,
+        table: &TableSpec,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, CsvParserError> {
+        if table.keep_raw_line {
+            return Err(CsvParserError::ParseError {
                 table_name: table.name.clone(),
-                message: format!("failed to parse CSV record: {}", e),
-            })?;
-            rows.push(extract_row(&record, &indices));
+                message: "keepRawLine needs the whole CSV in memory and cannot be read incrementally".to_string(),
+            });
         }
+        let input = self.skip_input_lines(input, table)?;
+        self.parse_rows_from(input, None, table, on_row).await
+    }
 
-        let column_names: Vec<String> = table.columns.iter().map(|c| c.name.clone()).collect();
-
-        Ok(Table::new(table.name.clone(), column_names, rows))
+    async fn parse_header_from_reader(&self, input: Box<dyn Read + Send>, table: &TableSpec) -> Result<Table, CsvParserError> {
+        let input = self.skip_input_lines(input, table)?;
+        self.resolve_header(input, table).await?;
+        compile_captures(table)?;
+        Ok(output_table(table, vec![]))
     }
 }
 
@@ -114,6 +592,10 @@ mod tests {
         SourceSpec::File(FileSourceSpec {
             filename: "test.csv".to_string(),
             character_encoding: "utf-8".to_string(),
+            allow_special_files: false,
+            glob: false,
+            sheet: None,
+            csv: Default::default(),
         })
     }
 
@@ -122,9 +604,13 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             has_header: true,
+            keep_raw_line: false,
             source: file_source(),
             columns,
             relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
         }
     }
 
@@ -133,9 +619,13 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             has_header: false,
+            keep_raw_line: false,
             source: file_source(),
             columns,
             relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
         }
     }
 
@@ -144,7 +634,12 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             column_identifier: ColumnIdentifier::Name(header.to_string()),
-            column_type: ColumnType::String,
+            column_type: ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
         }
     }
 
@@ -153,25 +648,15 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             column_identifier: ColumnIdentifier::Index(index),
-            column_type: ColumnType::String,
+            column_type: ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
         }
     }
 
-    #[test]
-    fn strip_csv_field_removes_quotes() {
-        assert_eq!(strip_csv_field("\"hello\""), "hello");
-    }
-
-    #[test]
-    fn strip_csv_field_trims_whitespace() {
-        assert_eq!(strip_csv_field("  hello  "), "hello");
-    }
-
-    #[test]
-    fn strip_csv_field_no_quotes() {
-        assert_eq!(strip_csv_field("hello"), "hello");
-    }
-
     #[test]
     fn resolve_column_indices_by_index() {
         let spec = table_spec_no_header("t", vec![
@@ -219,6 +704,89 @@ mod tests {
         assert_eq!(table.cell(1, 1), Some("Germany"));
     }
 
+    #[tokio::test]
+    async fn parse_rejects_duplicate_headers() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Name,Country,Name,Code,Code\nLondon,UK,L,1,2\n";
+        let spec = table_spec_with_header("city", vec![col_by_name("country", "Country")]);
+        let err = parser.parse(content, &spec).await.unwrap_err();
+        assert!(
+            matches!(err, CsvParserError::ParseError { ref table_name, ref message }
+                if table_name == "city" && message.contains("duplicate CSV header(s): Name, Code")),
+            "error was: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn parse_without_headers_allows_repeated_first_row_values() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let spec = table_spec_no_header("pair", vec![col_by_index("a", 0), col_by_index("b", 1)]);
+        let table = parser.parse("x,x\n", &spec).await.unwrap();
+        assert_eq!(table.num_rows(), 1);
+    }
+
+    fn col_capture(name: &str, source: &str, pattern: &str, group: usize) -> ColumnSpec {
+        ColumnSpec {
+            column_identifier: ColumnIdentifier::Capture {
+                source: source.to_string(),
+                pattern: pattern.to_string(),
+                group,
+            },
+            ..col_by_name(name, "")
+        }
+    }
+
+    #[tokio::test]
+    async fn parse_capture_extracts_group_from_source_column() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "File,Size\nreport_2023_q1.pdf,10\nnotes.txt,2\n";
+        let spec = table_spec_with_header("files", vec![
+            col_by_name("file", "File"),
+            col_capture("year", "file", r"_(\d{4})_q(\d)", 1),
+            col_capture("quarter", "file", r"_(\d{4})_q(\d)", 2),
+        ]);
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.row(0), Some(vec!["report_2023_q1.pdf".to_string(), "2023".to_string(), "1".to_string()].as_slice()));
+        // the second row does not match, so its captured values are empty
+        assert_eq!(table.row(1), Some(vec!["notes.txt".to_string(), String::new(), String::new()].as_slice()));
+    }
+
+    #[tokio::test]
+    async fn parse_capture_from_unknown_column_errors() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let spec = table_spec_with_header("files", vec![col_capture("year", "path", r"(\d{4})", 1)]);
+        let err = parser.parse("File\na_2023\n", &spec).await.unwrap_err();
+        assert!(err.to_string().contains("captures from 'path'"), "error was: {err}");
+    }
+
+    #[tokio::test]
+    async fn parse_keep_raw_line_appends_original_line() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Name,Country\nLondon, UK\r\n\"Berlin\",Germany";
+        let mut spec = table_spec_with_header("city", vec![
+            col_by_name("country", "Country"),
+            col_by_name("name", "Name"),
+        ]);
+        spec.keep_raw_line = true;
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.headers(), &["country", "name", RAW_LINE_COLUMN]);
+        assert_eq!(table.get(0, RAW_LINE_COLUMN), Some("London, UK"));
+        assert_eq!(table.get(0, "country"), Some("UK"));
+        assert_eq!(table.get(1, RAW_LINE_COLUMN), Some("\"Berlin\",Germany"));
+    }
+
+    #[tokio::test]
+    async fn parse_carries_column_types_from_spec() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let mut population = col_by_name("population", "Population");
+        population.column_type = ColumnType::Int64;
+        let spec = table_spec_with_header("city", vec![col_by_name("name", "Name"), population]);
+        let table = parser.parse("Name,Population\nLondon,8982000\n", &spec).await.unwrap();
+        assert_eq!(table.column_types, vec![ColumnType::String { max_length: None }, ColumnType::Int64]);
+        assert_eq!(table.column_type(1), Some(&ColumnType::Int64));
+        assert_eq!(table.column_type(2), None);
+    }
+
     #[tokio::test]
     async fn parse_without_headers() {
         let parser = CsvParserImpl::new(Box::new(TestLogger));
@@ -245,4 +813,399 @@ mod tests {
         assert_eq!(table.cell(0, 0), Some("3"));
         assert_eq!(table.cell(0, 1), Some("1"));
     }
+
+    #[tokio::test]
+    async fn parse_keeps_rfc4180_quoted_values_intact() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "\"Name\",\"Note\"\n\"a, b\",\"she said \"\"hi\"\"\"\n\"\"\"quoted\"\"\",plain\n";
+        let spec = table_spec_with_header("t", vec![
+            col_by_name("name", "Name"),
+            col_by_name("note", "Note"),
+        ]);
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("a, b"));
+        assert_eq!(table.cell(0, 1), Some("she said \"hi\""));
+        assert_eq!(table.cell(1, 0), Some("\"quoted\""));
+        assert_eq!(table.cell(1, 1), Some("plain"));
+    }
+
+    #[tokio::test]
+    async fn parse_unquotes_fields_quoted_after_a_space_unless_keep_quotes() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "\"Name\", \"Country\"\n\"London\", \"United Kingdom\"\n";
+        let mut spec = table_spec_with_header("t", vec![
+            col_by_name("name", "Name"),
+            col_by_name("country", "Country"),
+        ]);
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("London"));
+        assert_eq!(table.cell(0, 1), Some("United Kingdom"));
+
+        spec.source.csv_options_mut().keep_quotes = true;
+        spec.columns[1].column_identifier = ColumnIdentifier::Name("\"Country\"".to_string());
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.cell(0, 1), Some("\"United Kingdom\""));
+    }
+
+    #[tokio::test]
+    async fn parse_with_custom_delimiter() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Name|Note\nLondon|a, b\n";
+        let mut spec = table_spec_with_header("t", vec![
+            col_by_name("name", "Name"),
+            col_by_name("note", "Note"),
+        ]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.csv.delimiter = Some('|');
+        }
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("London"));
+        assert_eq!(table.cell(0, 1), Some("a, b"));
+    }
+
+    #[tokio::test]
+    async fn parse_skips_comment_lines_before_and_after_header() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "# exported 2024-05-01\n# by the BI tool\nName,Country\nLondon,UK\n# Berlin,DE\nParis,FR\n";
+        let mut spec = table_spec_with_header("t", vec![col_by_name("name", "Name")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.csv.comment = Some('#');
+        }
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.rows, vec![vec!["London"], vec!["Paris"]]);
+    }
+
+    #[test]
+    fn skip_lines_drops_leading_lines() {
+        assert_eq!(skip_lines("a\nb\r\nc\n", 0), "a\nb\r\nc\n");
+        assert_eq!(skip_lines("a\nb\r\nc\n", 2), "c\n");
+        assert_eq!(skip_lines("a\nb", 5), "");
+    }
+
+    fn with_skip_rows(mut spec: TableSpec, skip_rows: usize) -> TableSpec {
+        spec.source.csv_options_mut().skip_rows = skip_rows;
+        spec
+    }
+
+    #[tokio::test]
+    async fn parse_skip_rows_then_header() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Sales report\nGenerated: 2024-05-01,,\nName,Country\nLondon,UK\n";
+        let spec = with_skip_rows(table_spec_with_header("t", vec![col_by_name("country", "Country")]), 2);
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.rows, vec![vec!["UK"]]);
+
+        let input: Box<dyn Read + Send> = Box::new(std::io::Cursor::new(content.as_bytes().to_vec()));
+        let mut rows = Vec::new();
+        parser.parse_rows_from_reader(input, &spec, &mut |row| rows.push(row)).await.unwrap();
+        assert_eq!(rows, vec![vec!["UK"]]);
+    }
+
+    #[tokio::test]
+    async fn parse_skip_rows_then_indexed_columns() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "junk\nLondon,UK\nParis,FR\n";
+        let spec = with_skip_rows(table_spec_no_header("t", vec![col_by_index("country", 1)]), 1);
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.rows, vec![vec!["UK"], vec!["FR"]]);
+    }
+
+    #[tokio::test]
+    async fn parse_header_resolves_columns_without_rows() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Name,Age\nAlice,30\nBob,25\n";
+        let spec = table_spec_with_header("people", vec![col_by_name("name", "Name"), col_by_name("age", "Age")]);
+        let table = parser.parse_header(content, &spec).await.unwrap();
+        assert_eq!(table.columns, vec!["name", "age"]);
+        assert_eq!(table.num_rows(), 0);
+
+        let spec = table_spec_with_header("people", vec![col_by_name("email", "Email")]);
+        let err = parser.parse_header(content, &spec).await.unwrap_err();
+        assert!(err.to_string().contains("Email"), "error was: {err}");
+    }
+
+    fn ignoring_case(mut column: ColumnSpec) -> ColumnSpec {
+        column.ignore_header_case = true;
+        column
+    }
+
+    #[tokio::test]
+    async fn parse_matches_headers_ignoring_case_when_enabled() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "NAME,country\nLondon,UK\n";
+        let spec = table_spec_with_header("t", vec![ignoring_case(col_by_name("country", "Country"))]);
+        assert_eq!(parser.parse(content, &spec).await.unwrap().rows, vec![vec!["UK"]]);
+
+        let spec = table_spec_with_header("t", vec![col_by_name("country", "Country")]);
+        let err = parser.parse(content, &spec).await.unwrap_err();
+        assert!(err.to_string().contains("header 'Country' which was not found"), "error was: {err}");
+    }
+
+    #[tokio::test]
+    async fn parse_rejects_headers_that_collide_ignoring_case() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Country,COUNTRY\nUK,FR\n";
+        let spec = table_spec_with_header("t", vec![ignoring_case(col_by_name("country", "country"))]);
+        let err = parser.parse(content, &spec).await.unwrap_err();
+        assert!(
+            err.to_string().contains("matches several headers when ignoring case: Country, COUNTRY"),
+            "error was: {err}"
+        );
+    }
+
+    fn with_aliases(mut column: ColumnSpec, aliases: &[&str]) -> ColumnSpec {
+        column.aliases = aliases.iter().map(|a| a.to_string()).collect();
+        column
+    }
+
+    #[tokio::test]
+    async fn parse_resolves_column_through_alias() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "BuildingName,Floors\nStar Tower,40\n";
+        let column = with_aliases(col_by_name("building", "Building Name"), &["Building", "BuildingName"]);
+        let spec = table_spec_with_header("t", vec![column]);
+        assert_eq!(parser.parse(content, &spec).await.unwrap().rows, vec![vec!["Star Tower"]]);
+    }
+
+    #[test]
+    fn primary_name_wins_over_aliases_and_first_alias_over_later_ones() {
+        let map: HashMap<String, usize> =
+            [("Building Name", 0), ("Building", 1), ("BuildingName", 2)].map(|(h, i)| (h.to_string(), i)).into();
+        let spec = table_spec_with_header("t", vec![
+            with_aliases(col_by_name("a", "Building Name"), &["Building"]),
+            with_aliases(col_by_name("b", "Name"), &["BuildingName", "Building"]),
+        ]);
+        let map = Some(map);
+        assert_eq!(resolve_column_indices(&spec, &map).unwrap(), vec![0, 2]);
+        assert_eq!(matched_aliases(&spec, &map), vec![("b", "BuildingName")]);
+    }
+
+    #[tokio::test]
+    async fn parse_reports_name_and_aliases_when_none_match() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let column = with_aliases(col_by_name("building", "Building Name"), &["BuildingName"]);
+        let spec = table_spec_with_header("t", vec![column]);
+        let err = parser.parse("Site,Floors\nStar Tower,40\n", &spec).await.unwrap_err();
+        assert!(
+            err.to_string().contains("header 'Building Name' or its aliases 'BuildingName', none of which were found"),
+            "error was: {err}"
+        );
+    }
+
+    async fn parse_filtered(filter: &str) -> Result<Table, CsvParserError> {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let mut spec = table_spec_with_header("t", vec![col_by_name("name", "Name"), col_by_name("status", "Status")]);
+        spec.filter = Some(RowFilter::parse(filter).unwrap());
+        parser.parse("Name,Status\nAlice,active\nBob,retired\nCarol,\n", &spec).await
+    }
+
+    #[tokio::test]
+    async fn parse_keeps_rows_equal_to_filter_value() {
+        let table = parse_filtered(r#"status == "active""#).await.unwrap();
+        assert_eq!(table.rows, vec![vec!["Alice", "active"]]);
+    }
+
+    #[tokio::test]
+    async fn parse_drops_rows_equal_to_filter_value_with_not_equal() {
+        let table = parse_filtered("status != retired").await.unwrap();
+        assert_eq!(table.rows, vec![vec!["Alice", "active"], vec!["Carol", ""]]);
+    }
+
+    #[tokio::test]
+    async fn parse_rejects_filter_on_unknown_column() {
+        let err = parse_filtered("Status == active").await.unwrap_err();
+        assert!(err.to_string().contains("filter references unknown column 'Status'"), "error was: {err}");
+    }
+
+    #[tokio::test]
+    async fn parse_keeps_first_row_for_each_dedup_key() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Id,Region,Amount\n1,north,10\n2,north,20\n1,north,30\n1,south,40\n";
+        // the spec lists columns in a different order than the file
+        let mut spec = table_spec_with_header("t", vec![
+            col_by_name("amount", "Amount"),
+            col_by_name("region", "Region"),
+            col_by_name("id", "Id"),
+        ]);
+        spec.dedup_by = vec!["id".to_string(), "region".to_string()];
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.rows, vec![vec!["10", "north", "1"], vec!["20", "north", "2"], vec!["40", "south", "1"]]);
+
+        spec.dedup_by = vec![];
+        assert_eq!(parser.parse(content, &spec).await.unwrap().num_rows(), 4);
+    }
+
+    #[test]
+    fn dedup_rows_reports_dropped_count() {
+        let mut spec = table_spec_with_header("t", vec![col_by_name("id", "Id"), col_by_name("name", "Name")]);
+        spec.dedup_by = vec!["id".to_string()];
+        let mut rows = vec![vec!["1".to_string(), "a".to_string()], vec!["1".to_string(), "b".to_string()]];
+        assert_eq!(dedup_rows(&spec, &mut rows).unwrap(), 1);
+        assert_eq!(rows, vec![vec!["1", "a"]]);
+
+        spec.dedup_by = vec!["code".to_string()];
+        let err = dedup_rows(&spec, &mut rows).unwrap_err();
+        assert!(err.to_string().contains("dedupBy references unknown column 'code'"), "error was: {err}");
+    }
+
+    async fn parse_ragged(mode: RaggedRowMode, content: &str) -> Result<Table, CsvParserError> {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let mut spec = table_spec_with_header("city", vec![col_by_name("name", "Name"), col_by_name("code", "Code")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.csv.on_ragged_row = mode;
+        }
+        parser.parse(content, &spec).await
+    }
+
+    #[tokio::test]
+    async fn parse_ragged_row_error_names_row_and_field_counts() {
+        let err = parse_ragged(RaggedRowMode::Error, "Name,Code\nLondon,GB\nBerlin,D,E\n").await.unwrap_err();
+        assert!(err.to_string().contains("row 2 (line 3) has 3 field(s), expected 2"), "error was: {err}");
+
+        let err = parse_ragged(RaggedRowMode::Error, "Name,Code\nLondon\n").await.unwrap_err();
+        assert!(err.to_string().contains("row 1 (line 2) has 1 field(s), expected 2"), "error was: {err}");
+    }
+
+    async fn parse_with_max_errors(max_errors: Option<usize>, content: &str) -> Result<Table, CsvParserError> {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let mut spec = table_spec_with_header("city", vec![col_by_name("name", "Name"), col_by_name("code", "Code")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.csv.on_ragged_row = RaggedRowMode::Error;
+            fs.csv.max_errors = max_errors;
+        }
+        parser.parse(content, &spec).await
+    }
+
+    #[tokio::test]
+    async fn parse_max_errors_collects_bad_rows() {
+        let content = "Name,Code\nLondon\nBerlin,DE\nParis,FR,x\nRome\nOslo,NO\n";
+        let err = parse_with_max_errors(Some(2), content).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("3 row error(s)"), "error was: {message}");
+        assert!(message.contains("row 1 (line 2) has 1 field(s)"), "error was: {message}");
+        assert!(message.contains("row 3 (line 4) has 3 field(s)"), "error was: {message}");
+        assert!(!message.contains("row 4"), "error was: {message}");
+        assert!(message.ends_with("and 1 more"), "error was: {message}");
+    }
+
+    #[tokio::test]
+    async fn parse_max_errors_zero_fails_on_first_bad_row() {
+        let content = "Name,Code\nLondon\nParis,FR,x\n";
+        for max_errors in [None, Some(0)] {
+            let err = parse_with_max_errors(max_errors, content).await.unwrap_err();
+            let message = err.to_string();
+            assert!(message.contains("row 1 (line 2)") && !message.contains("row error(s)"), "error was: {message}");
+        }
+        assert_eq!(parse_with_max_errors(Some(5), "Name,Code\nOslo,NO\n").await.unwrap().num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn parse_ragged_row_pad_fills_missing_fields() {
+        let table = parse_ragged(RaggedRowMode::Pad, "Name,Code\nLondon\nBerlin,DE,extra\n").await.unwrap();
+        assert_eq!(table.row(0), Some(vec!["London".to_string(), String::new()].as_slice()));
+        assert_eq!(table.row(1), Some(vec!["Berlin".to_string(), "DE".to_string()].as_slice()));
+    }
+
+    #[tokio::test]
+    async fn parse_ragged_row_truncate_drops_extra_fields_only() {
+        let table = parse_ragged(RaggedRowMode::Truncate, "Name,Code\nBerlin,DE,extra\n").await.unwrap();
+        assert_eq!(table.row(0), Some(vec!["Berlin".to_string(), "DE".to_string()].as_slice()));
+        assert!(parse_ragged(RaggedRowMode::Truncate, "Name,Code\nLondon\n").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn parse_stops_after_limit_rows() {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Name,Code\nLondon,GB\nBerlin,DE\nParis,FR\n";
+        let mut spec = table_spec_with_header("city", vec![col_by_name("code", "Code")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.csv.limit = Some(2);
+        }
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.num_rows(), 2);
+        assert_eq!(table.cell(0, 0), Some("GB"));
+        assert_eq!(table.cell(1, 0), Some("DE"));
+
+        let mut spec = table_spec_no_header("city", vec![col_by_index("name", 0)]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.csv.limit = Some(1);
+        }
+        let table = parser.parse(content, &spec).await.unwrap();
+        assert_eq!(table.rows, vec![vec!["Name".to_string()]]);
+    }
+
+    async fn parse_with_trim(mode: TrimMode, header: &str) -> Result<Table, CsvParserError> {
+        let parser = CsvParserImpl::new(Box::new(TestLogger));
+        let content = "Name ,Code\n London , GB \n";
+        let mut spec = table_spec_with_header("t", vec![col_by_name("name", header), col_by_name("code", "Code")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.csv.trim = mode;
+        }
+        parser.parse(content, &spec).await
+    }
+
+    #[tokio::test]
+    async fn parse_trim_none_keeps_headers_and_fields() {
+        assert!(parse_with_trim(TrimMode::None, "Name").await.is_err());
+        let table = parse_with_trim(TrimMode::None, "Name ").await.unwrap();
+        assert_eq!(table.cell(0, 0), Some(" London "));
+        assert_eq!(table.cell(0, 1), Some(" GB "));
+    }
+
+    #[tokio::test]
+    async fn parse_trim_headers_only() {
+        assert!(parse_with_trim(TrimMode::Headers, "Name ").await.is_err());
+        let table = parse_with_trim(TrimMode::Headers, "Name").await.unwrap();
+        assert_eq!(table.cell(0, 0), Some(" London "));
+    }
+
+    #[tokio::test]
+    async fn parse_trim_fields_only() {
+        assert!(parse_with_trim(TrimMode::Fields, "Name").await.is_err());
+        let table = parse_with_trim(TrimMode::Fields, "Name ").await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("London"));
+        assert_eq!(table.cell(0, 1), Some("GB"));
+    }
+
+    #[tokio::test]
+    async fn parse_trim_all_is_the_default() {
+        assert_eq!(TrimMode::default(), TrimMode::All);
+        let table = parse_with_trim(TrimMode::All, "Name").await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("London"));
+    }
+
+    #[test]
+    fn suggest_delimiter_finds_tab_in_single_field() {
+        let record = csv::StringRecord::from(vec!["London\tUnited Kingdom"]);
+        assert_eq!(suggest_delimiter(&record, b','), Some('\t'));
+        assert_eq!(suggest_delimiter(&csv::StringRecord::from(vec!["a;b;c"]), b','), Some(';'));
+        assert_eq!(suggest_delimiter(&csv::StringRecord::from(vec!["London"]), b','), None);
+        assert_eq!(suggest_delimiter(&csv::StringRecord::from(vec!["a;b", "c"]), b','), None);
+    }
+
+    #[tokio::test]
+    async fn parse_tab_file_as_csv_warns_with_suggestion() {
+        use crate::components::test_helpers::RecordingLogger;
+
+        let logger = RecordingLogger::default();
+        let parser = CsvParserImpl::new(Box::new(logger.clone()));
+        let spec = table_spec_no_header("city", vec![col_by_index("name", 0)]);
+        parser.parse("London\tUnited Kingdom\nBerlin\tGermany\n", &spec).await.unwrap();
+
+        assert_eq!(logger.messages_at("warn"), vec![
+            "table 'city': the first row has a single field but contains '\\t'; did you mean delimiter: \"\\t\"?",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn parse_regular_csv_does_not_warn() {
+        use crate::components::test_helpers::RecordingLogger;
+
+        let logger = RecordingLogger::default();
+        let parser = CsvParserImpl::new(Box::new(logger.clone()));
+        let spec = table_spec_with_header("city", vec![col_by_name("name", "Name")]);
+        parser.parse("Name,Country\nLondon,UK\n", &spec).await.unwrap();
+
+        assert!(logger.messages_at("warn").is_empty());
+    }
 }
@@ -0,0 +1,670 @@
+use std::path::Path;
+use async_trait::async_trait;
+use crate::components::load::{dependency_order, project_file_path};
+use crate::components::validate::validate_impl::unkeyed_relationship_targets;
+use crate::models::{ColumnType, Dialect, OnDelete, Project, RelationshipSpec, Table, TableSpec, parse_bool};
+use crate::traits::{Ddl, DdlError, FileSystem, LoadError, Logger, ProjectIO};
+
+pub fn column_type_to_sql(column_type: &ColumnType, dialect: Dialect) -> String {
+    match (dialect, column_type) {
+        (Dialect::Postgres | Dialect::Mysql, ColumnType::Int64) => "BIGINT".to_string(),
+        (Dialect::Postgres | Dialect::Mysql, ColumnType::String { max_length: Some(n) }) => format!("VARCHAR({n})"),
+        (Dialect::Postgres | Dialect::Mysql, ColumnType::String { max_length: None }) => "TEXT".to_string(),
+        (Dialect::Postgres | Dialect::Mysql, ColumnType::Bool) => "BOOLEAN".to_string(),
+        (Dialect::Sqlite, ColumnType::Int64 | ColumnType::Bool) => "INTEGER".to_string(),
+        (Dialect::Sqlite, ColumnType::String { .. }) => "TEXT".to_string(),
+    }
+}
+
+pub fn table_to_ddl(table: &TableSpec, dialect: Dialect) -> String {
+    let mut columns: Vec<String> = table
+        .columns
+        .iter()
+        .map(|col| {
+            format!(
+                "    {} {}{}",
+                dialect.quote_identifier(&col.name),
+                column_type_to_sql(&col.column_type, dialect),
+                if col.unique { " UNIQUE" } else { "" },
+            )
+        })
+        .collect();
+    let key: Vec<String> = table
+        .columns
+        .iter()
+        .filter(|col| col.primary_key)
+        .map(|col| dialect.quote_identifier(&col.name))
+        .collect();
+    if !key.is_empty() {
+        columns.push(format!("    PRIMARY KEY ({})", key.join(", ")));
+    }
+    format!(
+        "CREATE TABLE {} (\n{}\n);\n",
+        dialect.quote_identifier(&table.name),
+        columns.join(",\n"),
+    )
+}
+
+/// A quoted string literal. MySQL also reads backslashes as escapes, so they are doubled.
+fn sql_string(value: &str, dialect: Dialect) -> String {
+    let value = value.replace('\'', "''");
+    match dialect {
+        Dialect::Mysql => format!("'{}'", value.replace('\\', "\\\\")),
+        Dialect::Postgres | Dialect::Sqlite => format!("'{value}'"),
+    }
+}
+
+/// A cell read as its column type: the one mapping behind SQL literals and the database writers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellValue {
+    Null,
+    Text(String),
+    Int64(i64),
+    Bool(bool),
+}
+
+/// Reads `cell` as `column_type`; an empty cell is NULL when `nullable`. Untyped cells are text.
+pub fn cell_value(cell: &str, column_type: Option<&ColumnType>, nullable: bool) -> Result<CellValue, String> {
+    if cell.is_empty() && nullable {
+        return Ok(CellValue::Null);
+    }
+    match column_type {
+        Some(ColumnType::Int64) => cell
+            .parse::<i64>()
+            .map(CellValue::Int64)
+            .map_err(|_| format!("value '{cell}' is not a valid int64")),
+        Some(ColumnType::Bool) => parse_bool(cell)
+            .map(CellValue::Bool)
+            .ok_or_else(|| format!("value '{cell}' is not a valid bool")),
+        Some(ColumnType::String { .. }) | None => Ok(CellValue::Text(cell.to_string())),
+    }
+}
+
+/// A cell as an SQL literal. Values that do not parse as their column type stay quoted
+/// strings, so the database rejects them instead of reading them as SQL.
+pub fn cell_to_sql_literal(cell: &str, column_type: Option<&ColumnType>, nullable: bool, dialect: Dialect) -> String {
+    match (cell_value(cell, column_type, nullable), dialect) {
+        (Ok(CellValue::Null), _) => "NULL".to_string(),
+        (Ok(CellValue::Int64(i)), _) => i.to_string(),
+        (Ok(CellValue::Bool(b)), Dialect::Postgres | Dialect::Mysql) => if b { "TRUE" } else { "FALSE" }.to_string(),
+        (Ok(CellValue::Bool(b)), Dialect::Sqlite) => (b as i64).to_string(),
+        (Ok(CellValue::Text(_)) | Err(_), _) => sql_string(cell, dialect),
+    }
+}
+
+/// Multi-row `INSERT` statements holding at most `batch_size` rows each.
+pub fn table_to_inserts(table: &Table, dialect: Dialect, batch_size: usize) -> Vec<String> {
+    let columns: Vec<String> = table.columns.iter().map(|c| dialect.quote_identifier(c)).collect();
+    table
+        .rows
+        .chunks(batch_size.max(1))
+        .map(|batch| {
+            let values: Vec<String> = batch
+                .iter()
+                .map(|row| {
+                    let cells: Vec<String> = (0..table.num_columns())
+                        .map(|i| {
+                            let cell = row.get(i).map(|s| s.as_str()).unwrap_or("");
+                            let nullable = table.nullable.get(i).copied().unwrap_or(true);
+                            cell_to_sql_literal(cell, table.column_type(i), nullable, dialect)
+                        })
+                        .collect();
+                    format!("({})", cells.join(", "))
+                })
+                .collect();
+            format!(
+                "INSERT INTO {} ({}) VALUES {};\n",
+                dialect.quote_identifier(&table.name),
+                columns.join(", "),
+                values.join(", "),
+            )
+        })
+        .collect()
+}
+
+/// Table and column descriptions as `COMMENT ON` statements, or as SQL comments
+/// for SQLite and MySQL, which have no such statement.
+pub fn table_comments_to_ddl(table: &TableSpec, dialect: Dialect) -> String {
+    let table_name = dialect.quote_identifier(&table.name);
+    let mut comments = Vec::new();
+    if !table.description.trim().is_empty() {
+        comments.push((table_name.clone(), "TABLE", table.description.trim()));
+    }
+    for col in table.columns.iter().filter(|c| !c.description.trim().is_empty()) {
+        let target = format!("{}.{}", table_name, dialect.quote_identifier(&col.name));
+        comments.push((target, "COLUMN", col.description.trim()));
+    }
+    comments
+        .into_iter()
+        .map(|(target, kind, description)| match dialect {
+            Dialect::Postgres => format!("COMMENT ON {} {} IS {};\n", kind, target, sql_string(description, dialect)),
+            Dialect::Sqlite | Dialect::Mysql => format!("-- {}: {}\n", target, description.replace('\n', " ")),
+        })
+        .collect()
+}
+
+pub fn validate_relationship(
+    project: &Project,
+    table: &TableSpec,
+    rel: &RelationshipSpec,
+) -> Result<(), DdlError> {
+    let source = table.columns.iter().find(|c| c.name == rel.source_column).ok_or_else(|| DdlError::UnknownColumn {
+        relationship: rel.name.clone(),
+        table: table.name.clone(),
+        column: rel.source_column.clone(),
+    })?;
+    if rel.on_delete == OnDelete::SetNull && !source.empty_is_null {
+        return Err(DdlError::SetNullOnNotNullColumn {
+            relationship: rel.name.clone(),
+            table: table.name.clone(),
+            column: rel.source_column.clone(),
+        });
+    }
+    let target = project
+        .spec
+        .tables
+        .iter()
+        .find(|t| t.name == rel.target_table)
+        .ok_or_else(|| DdlError::UnknownTargetTable {
+            relationship: rel.name.clone(),
+            table: table.name.clone(),
+            target_table: rel.target_table.clone(),
+        })?;
+    if !target.columns.iter().any(|c| c.name == rel.target_column) {
+        return Err(DdlError::UnknownColumn {
+            relationship: rel.name.clone(),
+            table: target.name.clone(),
+            column: rel.target_column.clone(),
+        });
+    }
+    Ok(())
+}
+
+pub fn relationship_to_ddl(table: &TableSpec, rel: &RelationshipSpec, dialect: Dialect) -> String {
+    format!(
+        "ALTER TABLE {} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {};\n",
+        dialect.quote_identifier(&table.name),
+        dialect.quote_identifier(&rel.name),
+        dialect.quote_identifier(&rel.source_column),
+        dialect.quote_identifier(&rel.target_table),
+        dialect.quote_identifier(&rel.target_column),
+        rel.on_delete.as_str().to_uppercase(),
+    )
+}
+
+/// Emits one `CREATE TABLE` per table, targets of relationships first, followed by
+/// the foreign key constraints, so that every referenced table exists before a
+/// constraint points at it.
+pub fn project_to_ddl(project: &Project, dialect: Dialect) -> Result<String, DdlError> {
+    let mut foreign_keys = Vec::new();
+    for table in &project.spec.tables {
+        for rel in &table.relationships {
+            validate_relationship(project, table, rel)?;
+            foreign_keys.push(relationship_to_ddl(table, rel, dialect));
+        }
+    }
+
+    let ordered = dependency_order(&project.spec.tables, &[]).map_err(|e| match e {
+        LoadError::RelationshipCycle { tables } => DdlError::RelationshipCycle { tables },
+        other => DdlError::TableOrder(other.to_string()),
+    })?;
+    let mut ddl = ordered
+        .into_iter()
+        .map(|table| table_to_ddl(table, dialect) + &table_comments_to_ddl(table, dialect))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !foreign_keys.is_empty() {
+        ddl.push('\n');
+        ddl.push_str(&foreign_keys.concat());
+    }
+    Ok(ddl)
+}
+
+fn column_type_to_mermaid(column_type: &ColumnType) -> &'static str {
+    match column_type {
+        ColumnType::String { .. } => "string",
+        ColumnType::Int64 => "int64",
+        ColumnType::Bool => "bool",
+    }
+}
+
+/// A Mermaid `erDiagram` with an entity per table and a many-to-one line per relationship.
+pub fn project_to_mermaid(project: &Project) -> Result<String, DdlError> {
+    let mut out = String::from("erDiagram\n");
+    for table in &project.spec.tables {
+        out.push_str(&format!("    {} {{\n", table.name));
+        for col in &table.columns {
+            out.push_str(&format!("        {} {}\n", column_type_to_mermaid(&col.column_type), col.name));
+        }
+        out.push_str("    }\n");
+    }
+    for table in &project.spec.tables {
+        for rel in &table.relationships {
+            validate_relationship(project, table, rel)?;
+            out.push_str(&format!(
+                "    {} }}o--|| {} : \"{} ({} -> {})\"\n",
+                table.name, rel.target_table, rel.name, rel.source_column, rel.target_column
+            ));
+        }
+    }
+    Ok(out)
+}
+
+pub struct DdlImpl {
+    logger: Box<dyn Logger>,
+    project_io: Box<dyn ProjectIO>,
+    file_system: Box<dyn FileSystem>,
+}
+
+impl DdlImpl {
+    pub fn new(logger: Box<dyn Logger>, project_io: Box<dyn ProjectIO>, file_system: Box<dyn FileSystem>) -> Self {
+        DdlImpl { logger, project_io, file_system }
+    }
+
+    async fn load_project(&self, path: &Path) -> Result<Project, DdlError> {
+        let metadata = tokio::fs::metadata(path).await;
+        if metadata.is_err() || !metadata.unwrap().is_dir() {
+            return Err(DdlError::DirectoryNotFound(path.display().to_string()));
+        }
+
+        let file_path = project_file_path(path).await;
+        if tokio::fs::metadata(&file_path).await.is_err() {
+            return Err(DdlError::ProjectFileNotFound(file_path.display().to_string()));
+        }
+
+        Ok(self.project_io.load(&file_path).await?)
+    }
+}
+
+#[async_trait]
+impl Ddl for DdlImpl {
+    async fn write_ddl(&self, path: &Path, out: &Path, dialect: Dialect) -> Result<usize, DdlError> {
+        let project = self.load_project(path).await?;
+        let ddl = project_to_ddl(&project, dialect)?;
+        for warning in unkeyed_relationship_targets(&project) {
+            self.logger.warn(&warning).await;
+        }
+        self.file_system.save(&ddl, out).await?;
+        self.logger.info(&format!(
+            "wrote DDL for {} table(s) of project '{}' to: {}",
+            project.spec.tables.len(),
+            project.name,
+            out.display()
+        )).await;
+        Ok(project.spec.tables.len())
+    }
+
+    async fn mermaid(&self, path: &Path) -> Result<String, DdlError> {
+        let project = self.load_project(path).await?;
+        project_to_mermaid(&project)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::test_helpers::column_spec;
+    use crate::models::{
+        ColumnIdentifier, ColumnSpec, FileSourceSpec, ProjectSpec, SourceSpec, PROJECT_API_VERSION,
+    };
+
+    fn column(name: &str, column_type: ColumnType) -> ColumnSpec {
+        column_spec(name, ColumnIdentifier::Name(name.to_string()), column_type)
+    }
+
+    fn table(name: &str, columns: Vec<ColumnSpec>) -> TableSpec {
+        TableSpec {
+            name: name.to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: format!("data/{name}.csv"),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns,
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    fn relationship(name: &str, source_column: &str, target_table: &str, target_column: &str) -> RelationshipSpec {
+        RelationshipSpec {
+            name: name.to_string(),
+            description: String::new(),
+            source_column: source_column.to_string(),
+            target_table: target_table.to_string(),
+            target_column: target_column.to_string(),
+            on_delete: Default::default(),
+        }
+    }
+
+    fn city_with_relationship(rel: RelationshipSpec) -> TableSpec {
+        let mut city = table("city", vec![
+            column("name", ColumnType::String { max_length: None }),
+            column("country", ColumnType::String { max_length: None }),
+        ]);
+        city.relationships.push(rel);
+        city
+    }
+
+    fn project(tables: Vec<TableSpec>) -> Project {
+        Project {
+            name: "test".to_string(),
+            api_version: PROJECT_API_VERSION.to_string(),
+            spec: ProjectSpec { tables, includes: vec![], defaults: Default::default() },
+        }
+    }
+
+    #[test]
+    fn column_type_to_sql_postgres() {
+        assert_eq!(column_type_to_sql(&ColumnType::Int64, Dialect::Postgres), "BIGINT");
+        assert_eq!(column_type_to_sql(&ColumnType::Bool, Dialect::Postgres), "BOOLEAN");
+        assert_eq!(
+            column_type_to_sql(&ColumnType::String { max_length: Some(64) }, Dialect::Postgres),
+            "VARCHAR(64)"
+        );
+        assert_eq!(
+            column_type_to_sql(&ColumnType::String { max_length: None }, Dialect::Postgres),
+            "TEXT"
+        );
+    }
+
+    #[test]
+    fn column_type_to_sql_sqlite() {
+        assert_eq!(column_type_to_sql(&ColumnType::Int64, Dialect::Sqlite), "INTEGER");
+        assert_eq!(column_type_to_sql(&ColumnType::Bool, Dialect::Sqlite), "INTEGER");
+        assert_eq!(
+            column_type_to_sql(&ColumnType::String { max_length: Some(64) }, Dialect::Sqlite),
+            "TEXT"
+        );
+    }
+
+    #[test]
+    fn quote_identifier_escapes_embedded_quotes() {
+        assert_eq!(Dialect::Postgres.quote_identifier("my \"col\""), "\"my \"\"col\"\"\"");
+    }
+
+    #[test]
+    fn table_to_ddl_postgres() {
+        let spec = table("city", vec![
+            column("name", ColumnType::String { max_length: Some(100) }),
+            column("population", ColumnType::Int64),
+            column("notes", ColumnType::String { max_length: None }),
+        ]);
+        assert_eq!(
+            table_to_ddl(&spec, Dialect::Postgres),
+            "CREATE TABLE \"city\" (\n    \"name\" VARCHAR(100),\n    \"population\" BIGINT,\n    \"notes\" TEXT\n);\n"
+        );
+    }
+
+    #[test]
+    fn table_to_ddl_emits_composite_primary_key() {
+        let mut spec = table("office_floor", vec![
+            column("building", ColumnType::String { max_length: None }),
+            column("floor", ColumnType::Int64),
+            column("notes", ColumnType::String { max_length: None }),
+        ]);
+        spec.columns[0].primary_key = true;
+        spec.columns[1].primary_key = true;
+        assert_eq!(
+            table_to_ddl(&spec, Dialect::Sqlite),
+            "CREATE TABLE \"office_floor\" (\n    \"building\" TEXT,\n    \"floor\" INTEGER,\n    \"notes\" TEXT,\n    \
+             PRIMARY KEY (\"building\", \"floor\")\n);\n"
+        );
+    }
+
+    #[test]
+    fn table_to_ddl_marks_unique_columns() {
+        let mut spec = table("country", vec![
+            column("code", ColumnType::String { max_length: Some(2) }),
+            column("name", ColumnType::String { max_length: None }),
+        ]);
+        spec.columns[0].unique = true;
+        assert_eq!(
+            table_to_ddl(&spec, Dialect::Postgres),
+            "CREATE TABLE \"country\" (\n    \"code\" VARCHAR(2) UNIQUE,\n    \"name\" TEXT\n);\n"
+        );
+    }
+
+    #[test]
+    fn table_to_inserts_batches_rows_and_escapes_quotes() {
+        let mut spec = table("city", vec![
+            column("name", ColumnType::String { max_length: None }),
+            column("population", ColumnType::Int64),
+            column("capital", ColumnType::Bool),
+        ]);
+        spec.columns[0].empty_is_null = false;
+        let row = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let city = Table::from_spec(&spec, vec![
+            row(&["L'Aquila", "69000", "no"]),
+            row(&["", "", "yes"]),
+            row(&["Oslo", "n/a", ""]),
+        ]);
+        assert_eq!(table_to_inserts(&city, Dialect::Postgres, 2), vec![
+            "INSERT INTO \"city\" (\"name\", \"population\", \"capital\") \
+             VALUES ('L''Aquila', 69000, FALSE), ('', NULL, TRUE);\n",
+            "INSERT INTO \"city\" (\"name\", \"population\", \"capital\") VALUES ('Oslo', 'n/a', NULL);\n",
+        ]);
+        assert!(table_to_inserts(&city, Dialect::Sqlite, 10)[0].contains("('L''Aquila', 69000, 0), ('', NULL, 1), "));
+    }
+
+    #[test]
+    fn mysql_quotes_identifiers_with_backticks_and_escapes_backslashes() {
+        let spec = table("my`city", vec![
+            column("name", ColumnType::String { max_length: Some(80) }),
+            column("capital", ColumnType::Bool),
+        ]);
+        assert_eq!(
+            table_to_ddl(&spec, Dialect::Mysql),
+            "CREATE TABLE `my``city` (\n    `name` VARCHAR(80),\n    `capital` BOOLEAN\n);\n"
+        );
+        let city = Table::from_spec(&spec, vec![vec!["C:\\data's".to_string(), "true".to_string()]]);
+        assert_eq!(
+            table_to_inserts(&city, Dialect::Mysql, 10),
+            vec!["INSERT INTO `my``city` (`name`, `capital`) VALUES ('C:\\\\data''s', TRUE);\n"]
+        );
+    }
+
+    #[test]
+    fn table_to_inserts_of_empty_table_is_empty() {
+        let spec = table("city", vec![column("name", ColumnType::String { max_length: None })]);
+        let city = Table::from_spec(&spec, vec![]);
+        assert!(table_to_inserts(&city, Dialect::Postgres, 100).is_empty());
+    }
+
+    #[test]
+    fn project_to_ddl_postgres_emits_one_statement_per_table() {
+        let project = project(vec![
+            table("country", vec![column("name", ColumnType::String { max_length: None })]),
+            table("city", vec![
+                column("name", ColumnType::String { max_length: None }),
+                column("country", ColumnType::String { max_length: None }),
+            ]),
+        ]);
+        assert_eq!(
+            project_to_ddl(&project, Dialect::Postgres).unwrap(),
+            "CREATE TABLE \"country\" (\n    \"name\" TEXT\n);\n\
+             \n\
+             CREATE TABLE \"city\" (\n    \"name\" TEXT,\n    \"country\" TEXT\n);\n"
+        );
+    }
+
+    #[test]
+    fn project_to_ddl_empty_project() {
+        assert_eq!(project_to_ddl(&project(vec![]), Dialect::Postgres).unwrap(), "");
+    }
+
+    #[test]
+    fn project_to_ddl_emits_foreign_keys_after_tables() {
+        let project = project(vec![
+            table("country", vec![column("name", ColumnType::String { max_length: None })]),
+            city_with_relationship(relationship("located_in_country", "country", "country", "name")),
+        ]);
+        assert_eq!(
+            project_to_ddl(&project, Dialect::Postgres).unwrap(),
+            "CREATE TABLE \"country\" (\n    \"name\" TEXT\n);\n\
+             \n\
+             CREATE TABLE \"city\" (\n    \"name\" TEXT,\n    \"country\" TEXT\n);\n\
+             \n\
+             ALTER TABLE \"city\" ADD CONSTRAINT \"located_in_country\" FOREIGN KEY (\"country\") REFERENCES \"country\" (\"name\") ON DELETE NO ACTION;\n"
+        );
+    }
+
+    #[test]
+    fn relationship_to_ddl_emits_on_delete_action() {
+        let mut rel = relationship("located_in_country", "country", "country", "name");
+        rel.on_delete = OnDelete::SetNull;
+        let city = city_with_relationship(rel.clone());
+        assert!(relationship_to_ddl(&city, &rel, Dialect::Sqlite).ends_with(" ON DELETE SET NULL;\n"));
+    }
+
+    #[test]
+    fn project_to_ddl_rejects_set_null_on_not_null_column() {
+        let mut rel = relationship("located_in_country", "country", "country", "name");
+        rel.on_delete = OnDelete::SetNull;
+        let mut city = city_with_relationship(rel);
+        city.columns[1].empty_is_null = false;
+        let project = project(vec![
+            table("country", vec![column("name", ColumnType::String { max_length: None })]),
+            city,
+        ]);
+        let err = project_to_ddl(&project, Dialect::Postgres).unwrap_err();
+        assert!(matches!(err, DdlError::SetNullOnNotNullColumn { ref column, .. } if column == "country"));
+    }
+
+    #[test]
+    fn project_to_mermaid_lists_entities_and_relationships() {
+        let mut country = table("country", vec![column("name", ColumnType::String { max_length: Some(80) })]);
+        country.columns.push(column("population", ColumnType::Int64));
+        let project = project(vec![
+            country,
+            city_with_relationship(relationship("located_in_country", "country", "country", "name")),
+        ]);
+        assert_eq!(
+            project_to_mermaid(&project).unwrap(),
+            "erDiagram\n\
+             \x20   country {\n        string name\n        int64 population\n    }\n\
+             \x20   city {\n        string name\n        string country\n    }\n\
+             \x20   city }o--|| country : \"located_in_country (country -> name)\"\n"
+        );
+    }
+
+    #[test]
+    fn project_to_mermaid_rejects_unknown_target_table() {
+        let project = project(vec![
+            city_with_relationship(relationship("located_in_country", "country", "country", "name")),
+        ]);
+        let err = project_to_mermaid(&project).unwrap_err();
+        assert!(matches!(err, DdlError::UnknownTargetTable { ref target_table, .. } if target_table == "country"));
+    }
+
+    #[test]
+    fn project_to_ddl_rejects_unknown_target_table() {
+        let project = project(vec![
+            city_with_relationship(relationship("located_in_country", "country", "country", "name")),
+        ]);
+        let err = project_to_ddl(&project, Dialect::Postgres).unwrap_err();
+        assert!(matches!(err, DdlError::UnknownTargetTable { ref target_table, .. } if target_table == "country"));
+    }
+
+    #[test]
+    fn project_to_ddl_rejects_unknown_target_column() {
+        let project = project(vec![
+            table("country", vec![column("name", ColumnType::String { max_length: None })]),
+            city_with_relationship(relationship("located_in_country", "country", "country", "code")),
+        ]);
+        let err = project_to_ddl(&project, Dialect::Postgres).unwrap_err();
+        assert!(matches!(err, DdlError::UnknownColumn { ref table, ref column, .. } if table == "country" && column == "code"));
+    }
+
+    #[test]
+    fn project_to_ddl_rejects_unknown_source_column() {
+        let project = project(vec![
+            table("country", vec![column("name", ColumnType::String { max_length: None })]),
+            city_with_relationship(relationship("located_in_country", "country_name", "country", "name")),
+        ]);
+        let err = project_to_ddl(&project, Dialect::Postgres).unwrap_err();
+        assert!(matches!(err, DdlError::UnknownColumn { ref table, ref column, .. } if table == "city" && column == "country_name"));
+    }
+
+    #[test]
+    fn project_to_ddl_orders_relationship_targets_first() {
+        let project = project(vec![
+            city_with_relationship(relationship("located_in_country", "country", "country", "name")),
+            table("country", vec![column("name", ColumnType::String { max_length: None })]),
+        ]);
+        let ddl = project_to_ddl(&project, Dialect::Postgres).unwrap();
+        let country = ddl.find("CREATE TABLE \"country\"").unwrap();
+        let city = ddl.find("CREATE TABLE \"city\"").unwrap();
+        assert!(country < city, "ddl was:\n{ddl}");
+    }
+
+    #[test]
+    fn project_to_ddl_rejects_relationship_cycle() {
+        let mut country = table("country", vec![column("capital", ColumnType::String { max_length: None })]);
+        country.relationships.push(relationship("has_capital", "capital", "city", "name"));
+        let project = project(vec![
+            country,
+            city_with_relationship(relationship("located_in_country", "country", "country", "capital")),
+        ]);
+        let err = project_to_ddl(&project, Dialect::Postgres).unwrap_err();
+        assert!(matches!(err, DdlError::RelationshipCycle { .. }));
+    }
+
+    #[test]
+    fn table_comments_to_ddl_escapes_descriptions() {
+        let mut spec = table("city", vec![column("name", ColumnType::String { max_length: None })]);
+        spec.description = "Cities we're in".to_string();
+        spec.columns[0].description = "Official name".to_string();
+        assert_eq!(
+            table_comments_to_ddl(&spec, Dialect::Postgres),
+            "COMMENT ON TABLE \"city\" IS 'Cities we''re in';\n\
+             COMMENT ON COLUMN \"city\".\"name\" IS 'Official name';\n"
+        );
+        assert_eq!(
+            table_comments_to_ddl(&spec, Dialect::Sqlite),
+            "-- \"city\": Cities we're in\n-- \"city\".\"name\": Official name\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_ddl_writes_all_tables_in_dependency_order() {
+        use crate::components::file_system::DiskFileSystem;
+        use crate::components::init::init_impl::example_project;
+        use crate::components::project_io::YamlProjectIO;
+        use crate::components::project_serialization::YamlProjectSerialization;
+        use crate::components::test_helpers::TestLogger;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let project_io = || YamlProjectIO::new(
+            Box::new(TestLogger),
+            Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+            Box::new(YamlProjectSerialization::new(Box::new(TestLogger))),
+        );
+        let mut example = example_project("test");
+        example.spec.tables.reverse();
+        project_io().save(&example, &project_file_path(tmp.path()).await).await.unwrap();
+
+        let ddl = DdlImpl::new(
+            Box::new(TestLogger),
+            Box::new(project_io()),
+            Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+        );
+        let out = tmp.path().join("schema.sql");
+        assert_eq!(ddl.write_ddl(tmp.path(), &out, Dialect::Postgres).await.unwrap(), 5);
+
+        let sql = tokio::fs::read_to_string(&out).await.unwrap();
+        let country = sql.find("CREATE TABLE \"country\"").unwrap();
+        let city = sql.find("CREATE TABLE \"city\"").unwrap();
+        assert!(country < city, "sql was:\n{sql}");
+        assert!(sql.contains("COMMENT ON TABLE \"city\""), "sql was:\n{sql}");
+        assert!(sql.contains("FOREIGN KEY (\"country\") REFERENCES \"country\""), "sql was:\n{sql}");
+    }
+}
@@ -0,0 +1,3 @@
+pub mod ddl_impl;
+
+pub use ddl_impl::DdlImpl;
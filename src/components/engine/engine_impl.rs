@@ -1,12 +1,22 @@
 use std::path::Path;
 use async_trait::async_trait;
-use crate::models::LoadedProject;
-use crate::traits::{Engine, Init, InitError, Load, LoadError, Logger};
+use crate::components::load::dependency_order;
+use crate::models::{
+    Dialect, InitTemplate, LoadOptions, LoadedProject, Project, ProjectFormat, Table, TableMapping,
+};
+use crate::traits::{
+    Ddl, DdlError, Engine, Fmt, FmtError, Freeze, FreezeError, Init, InitError, Load, LoadError, Logger, TableWriter,
+    TableWriterError, Validate, ValidateError,
+};
 
 pub struct EngineImpl {
     logger: Box<dyn Logger>,
     init: Box<dyn Init>,
     load: Box<dyn Load>,
+    freeze: Box<dyn Freeze>,
+    validate: Box<dyn Validate>,
+    fmt: Box<dyn Fmt>,
+    ddl: Box<dyn Ddl>,
 }
 
 impl EngineImpl {
@@ -14,29 +24,135 @@ impl EngineImpl {
         logger: Box<dyn Logger>,
         init: Box<dyn Init>,
         load: Box<dyn Load>,
+        freeze: Box<dyn Freeze>,
+        validate: Box<dyn Validate>,
+        fmt: Box<dyn Fmt>,
+        ddl: Box<dyn Ddl>,
     ) -> Self {
-        EngineImpl { logger, init, load }
+        EngineImpl { logger, init, load, freeze, validate, fmt, ddl }
     }
 }
 
+/// The loaded tables with relationship targets first, ignoring the relationships skipped on load.
+/// Tables without a spec, which the writer rejects, keep their load order at the end.
+pub fn tables_in_dependency_order(loaded_project: &LoadedProject) -> Vec<&Table> {
+    let skipped: Vec<String> = loaded_project
+        .report
+        .skipped_relationships
+        .iter()
+        .filter_map(|s| s.split_once('.').map(|(_, relationship)| relationship.to_string()))
+        .collect();
+    let order: Vec<&str> = dependency_order(&loaded_project.project.spec.tables, &skipped)
+        .map(|specs| specs.iter().map(|t| t.name.as_str()).collect())
+        .unwrap_or_default();
+    let mut tables: Vec<&Table> = loaded_project.tables.iter().collect();
+    tables.sort_by_key(|t| order.iter().position(|name| *name == t.name).unwrap_or(usize::MAX));
+    tables
+}
+
 #[async_trait]
 impl Engine for EngineImpl {
     async fn init(&self) {
         self.logger.info("hello").await;
     }
 
-    async fn init_project_dir(&self, path: &Path, name: Option<&str>, force: bool) -> Result<(), InitError> {
-        self.init.init(path, name, force).await
+    async fn init_project_dir(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        force: bool,
+        overwrite_project: bool,
+        template: InitTemplate,
+        format: ProjectFormat,
+    ) -> Result<(), InitError> {
+        self.init.init(path, name, force, overwrite_project, template, format).await
+    }
+
+    async fn add_table(
+        &self,
+        path: &Path,
+        name: &str,
+        filename: &str,
+        has_header: bool,
+        columns: &[String],
+        force: bool,
+    ) -> Result<(), InitError> {
+        self.init.add_table(path, name, filename, has_header, columns, force).await
+    }
+
+    fn example_project_yaml(&self) -> Result<String, InitError> {
+        self.init.example_project_yaml()
+    }
+
+    async fn load_project(&self, path: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError> {
+        self.load.load(path, options).await
+    }
+
+    async fn load_bundle(&self, bundle: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError> {
+        self.load.load_bundle(bundle, options).await
+    }
+
+    async fn stream_table(
+        &self,
+        path: &Path,
+        table_name: &str,
+        on_row: &mut (dyn for<'r> FnMut(&'r [String]) + Send),
+    ) -> Result<usize, LoadError> {
+        self.load.stream_table(path, table_name, &mut |row| on_row(&row)).await
+    }
+
+    async fn explain_project(&self, path: &Path) -> Result<Vec<TableMapping>, LoadError> {
+        self.load.column_mappings(path).await
+    }
+
+    async fn project_info(&self, path: &Path) -> Result<Project, LoadError> {
+        self.load.read_project(path).await
+    }
+
+    async fn write_tables(&self, loaded_project: &LoadedProject, writer: &dyn TableWriter) -> Result<(), TableWriterError> {
+        writer.start(loaded_project).await?;
+        for table in tables_in_dependency_order(loaded_project) {
+            let spec = loaded_project
+                .project
+                .spec
+                .tables
+                .iter()
+                .find(|t| t.name == table.name)
+                .ok_or_else(|| TableWriterError::WriteError {
+                    table_name: table.name.clone(),
+                    message: "no table spec found in project".to_string(),
+                })?;
+            self.logger.debug(&format!("writing table '{}' using writer '{}'", table.name, writer.name())).await;
+            writer.write(table, spec).await?;
+        }
+        writer.finish(loaded_project).await
     }
 
-    async fn load_project(&self, path: &Path) -> Result<LoadedProject, LoadError> {
-        self.load.load(path).await
+    async fn validate_project(&self, path: &Path) -> Result<Vec<String>, ValidateError> {
+        self.validate.validate(path).await
+    }
+
+    async fn freeze_project(&self, path: &Path) -> Result<Vec<String>, FreezeError> {
+        self.freeze.freeze(path).await
+    }
+
+    async fn format_project(&self, path: &Path, verify: bool) -> Result<bool, FmtError> {
+        self.fmt.fmt(path, verify).await
+    }
+
+    async fn write_ddl(&self, path: &Path, out: &Path, dialect: Dialect) -> Result<usize, DdlError> {
+        self.ddl.write_ddl(path, out, dialect).await
+    }
+
+    async fn mermaid_diagram(&self, path: &Path) -> Result<String, DdlError> {
+        self.ddl.mermaid(path).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::component_assembler::ComponentAssembler;
+    use crate::models::{InitTemplate, LoadOptions, ProjectFormat};
 
     #[tokio::test]
     async fn init_then_load_project_from_temp_directory() {
@@ -45,13 +161,39 @@ mod tests {
         let engine = assembler.engine();
 
         engine
-            .init_project_dir(tmp.path(), Some("real-world-test"), false)
+            .init_project_dir(
+                tmp.path(),
+                Some("real-world-test"),
+                false,
+                false,
+                InitTemplate::Full,
+                ProjectFormat::Yaml,
+            )
             .await
             .unwrap();
 
-        let loaded = engine.load_project(tmp.path()).await.unwrap();
+        let loaded = engine.load_project(tmp.path(), &LoadOptions::default()).await.unwrap();
         assert_eq!(loaded.project.name, "real-world-test");
         assert_eq!(loaded.project.spec.tables.len(), 5);
         assert_eq!(loaded.tables.len(), 5);
     }
+
+    #[tokio::test]
+    async fn tables_in_dependency_order_puts_relationship_targets_first() {
+        use super::tables_in_dependency_order;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let engine = ComponentAssembler::new().engine();
+        engine
+            .init_project_dir(tmp.path(), Some("proj"), false, false, InitTemplate::Full, ProjectFormat::Yaml)
+            .await
+            .unwrap();
+        let mut loaded = engine.load_project(tmp.path(), &LoadOptions::default()).await.unwrap();
+        let load_order: Vec<String> = loaded.tables.iter().map(|t| t.name.clone()).collect();
+
+        loaded.tables.reverse();
+        let names: Vec<String> = tables_in_dependency_order(&loaded).iter().map(|t| t.name.clone()).collect();
+        assert_eq!(names, load_order);
+        assert!(names.iter().position(|n| n == "city") < names.iter().position(|n| n == "office"));
+    }
 }
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use async_trait::async_trait;
 use crate::traits::{Logger, FileSystem, FileSystemError};
 
@@ -50,6 +50,15 @@ impl FileSystem for DiskFileSystem {
         Ok(bytes)
     }
 
+    async fn open_read(&self, path: &Path) -> Result<Box<dyn std::io::Read + Send>, FileSystemError> {
+        self.logger.debug(&format!("opening file: {}", path.display())).await;
+        let file = tokio::fs::File::open(path).await.map_err(|e| FileSystemError::ReadError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Ok(Box::new(std::io::BufReader::new(file.into_std().await)))
+    }
+
     async fn ensure_dir(&self, path: &Path) -> Result<(), FileSystemError> {
         self.logger.debug(&format!("ensuring directory: {}", path.display())).await;
         tokio::fs::create_dir_all(path).await.map_err(|e| FileSystemError::DirCreateError {
@@ -59,6 +68,28 @@ impl FileSystem for DiskFileSystem {
         self.logger.info(&format!("ensured directory: {}", path.display())).await;
         Ok(())
     }
+
+    async fn is_special_file(&self, path: &Path) -> Result<bool, FileSystemError> {
+        let metadata = tokio::fs::metadata(path).await.map_err(|e| FileSystemError::ReadError {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+        Ok(!metadata.is_file() && !metadata.is_dir())
+    }
+
+    async fn exists(&self, path: &Path) -> Result<bool, FileSystemError> {
+        tokio::fs::try_exists(path).await.map_err(|e| FileSystemError::ReadError {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
+
+    async fn canonicalize(&self, path: &Path) -> Result<PathBuf, FileSystemError> {
+        tokio::fs::canonicalize(path).await.map_err(|e| FileSystemError::ReadError {
+            path: path.to_path_buf(),
+            source: e,
+        })
+    }
 }
 
 #[cfg(test)]
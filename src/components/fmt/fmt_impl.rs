@@ -0,0 +1,227 @@
+use std::path::Path;
+use async_trait::async_trait;
+use crate::components::load::project_file_path;
+use crate::models::ProjectFormat;
+use crate::traits::{FileSystem, Fmt, FmtError, Logger, ProjectSerialization};
+
+/// Returns the lines that differ between `before` and `after`, prefixed with
+/// `-` and `+`, aligned on their longest common subsequence.
+pub fn line_diff(before: &str, after: &str) -> String {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            diff.push_str(&format!("+ {}\n", b[j]));
+            j += 1;
+        } else {
+            diff.push_str(&format!("- {}\n", a[i]));
+            i += 1;
+        }
+    }
+    diff
+}
+
+pub struct FmtImpl {
+    logger: Box<dyn Logger>,
+    file_system: Box<dyn FileSystem>,
+    project_serialization: Box<dyn ProjectSerialization>,
+    json_serialization: Box<dyn ProjectSerialization>,
+}
+
+impl FmtImpl {
+    /// `json_serialization` formats dbloada.json projects, `project_serialization` all others.
+    pub fn new(
+        logger: Box<dyn Logger>,
+        file_system: Box<dyn FileSystem>,
+        project_serialization: Box<dyn ProjectSerialization>,
+        json_serialization: Box<dyn ProjectSerialization>,
+    ) -> Self {
+        FmtImpl { logger, file_system, project_serialization, json_serialization }
+    }
+}
+
+#[async_trait]
+impl Fmt for FmtImpl {
+    async fn fmt(&self, path: &Path, verify: bool) -> Result<bool, FmtError> {
+        let metadata = tokio::fs::metadata(path).await;
+        if metadata.is_err() || !metadata.unwrap().is_dir() {
+            return Err(FmtError::DirectoryNotFound(path.display().to_string()));
+        }
+
+        let file_path = project_file_path(path).await;
+        if tokio::fs::metadata(&file_path).await.is_err() {
+            return Err(FmtError::ProjectFileNotFound(file_path.display().to_string()));
+        }
+
+        let serialization = match ProjectFormat::from_path(&file_path) {
+            ProjectFormat::Yaml => &self.project_serialization,
+            ProjectFormat::Json => &self.json_serialization,
+        };
+        let content = self.file_system.load(&file_path).await?;
+        let project = serialization.deserialize(&content).await?;
+        let formatted = serialization.serialize(&project).await?;
+
+        if verify {
+            let reparsed = serialization.deserialize(&formatted).await?;
+            if reparsed != project {
+                let diff = line_diff(&format!("{:#?}", project), &format!("{:#?}", reparsed));
+                return Err(FmtError::RoundTripMismatch { diff });
+            }
+            self.logger.debug("project survived a serialization round trip").await;
+        }
+
+        if formatted == content {
+            self.logger.info(&format!("already formatted: {}", file_path.display())).await;
+            return Ok(false);
+        }
+        self.file_system.save(&formatted, &file_path).await?;
+        self.logger.info(&format!("formatted: {}", file_path.display())).await;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::file_system::DiskFileSystem;
+    use crate::components::init::init_impl::example_project;
+    use crate::components::project_serialization::{JsonProjectSerialization, YamlProjectSerialization};
+    use crate::components::test_helpers::TestLogger;
+    use crate::models::{Project, ProjectSpec};
+    use crate::traits::ProjectSerializationError;
+
+    /// Serializes like the YAML implementation but forgets relationships on
+    /// the way out, the kind of asymmetry `--verify` is meant to catch.
+    struct LossySerialization(YamlProjectSerialization);
+
+    #[async_trait]
+    impl ProjectSerialization for LossySerialization {
+        async fn serialize(&self, project: &Project) -> Result<String, ProjectSerializationError> {
+            let mut stripped = project.clone();
+            for table in &mut stripped.spec.tables {
+                table.relationships.clear();
+            }
+            self.0.serialize(&stripped).await
+        }
+
+        async fn deserialize(&self, content: &str) -> Result<Project, ProjectSerializationError> {
+            self.0.deserialize(content).await
+        }
+
+        async fn deserialize_include(&self, content: &str) -> Result<ProjectSpec, ProjectSerializationError> {
+            self.0.deserialize_include(content).await
+        }
+    }
+
+    fn yaml_serialization() -> YamlProjectSerialization {
+        YamlProjectSerialization::new(Box::new(TestLogger))
+    }
+
+    fn make_fmt(project_serialization: Box<dyn ProjectSerialization>) -> FmtImpl {
+        FmtImpl::new(
+            Box::new(TestLogger),
+            Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+            project_serialization,
+            Box::new(JsonProjectSerialization::new(Box::new(TestLogger))),
+        )
+    }
+
+    async fn write_example_project(dir: &Path) -> String {
+        let content = yaml_serialization().serialize(&example_project("test")).await.unwrap();
+        tokio::fs::write(project_file_path(dir).await, &content).await.unwrap();
+        content
+    }
+
+    #[test]
+    fn line_diff_reports_removed_and_added_lines() {
+        assert_eq!(line_diff("a\nb\nc\n", "a\nc\nd\n"), "- b\n+ d\n");
+        assert_eq!(line_diff("a\nb\n", "a\nb\n"), "");
+    }
+
+    #[tokio::test]
+    async fn fmt_verify_passes_for_round_trippable_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_example_project(tmp.path()).await;
+
+        let changed = make_fmt(Box::new(yaml_serialization())).fmt(tmp.path(), true).await.unwrap();
+        assert!(!changed);
+    }
+
+    #[tokio::test]
+    async fn fmt_verify_fails_with_diff_when_round_trip_loses_data() {
+        let tmp = tempfile::tempdir().unwrap();
+        let original = write_example_project(tmp.path()).await;
+
+        let err = make_fmt(Box::new(LossySerialization(yaml_serialization())))
+            .fmt(tmp.path(), true)
+            .await
+            .unwrap_err();
+        match &err {
+            FmtError::RoundTripMismatch { diff } => {
+                assert!(diff.contains("- ") && diff.contains("located_in_country"), "diff was:\n{diff}");
+            }
+            other => panic!("expected RoundTripMismatch, got {other:?}"),
+        }
+        let after = tokio::fs::read_to_string(project_file_path(tmp.path()).await).await.unwrap();
+        assert_eq!(after, original, "project file must not be rewritten when verification fails");
+    }
+
+    #[tokio::test]
+    async fn fmt_rewrites_unformatted_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        let original = write_example_project(tmp.path()).await;
+        let messy = format!("# a comment that fmt drops\n{original}");
+        tokio::fs::write(project_file_path(tmp.path()).await, &messy).await.unwrap();
+
+        let changed = make_fmt(Box::new(yaml_serialization())).fmt(tmp.path(), false).await.unwrap();
+        assert!(changed);
+        let after = tokio::fs::read_to_string(project_file_path(tmp.path()).await).await.unwrap();
+        assert_eq!(after, original);
+    }
+
+    #[tokio::test]
+    async fn fmt_keeps_env_references_as_written() {
+        let tmp = tempfile::tempdir().unwrap();
+        let original = write_example_project(tmp.path()).await;
+        let with_env = original.replace("data/countries.csv", "data/${DBLOADA_TEST_UNSET}/countries.csv");
+        let messy = format!("# a comment that fmt drops\n{with_env}");
+        tokio::fs::write(project_file_path(tmp.path()).await, &messy).await.unwrap();
+
+        let changed = make_fmt(Box::new(yaml_serialization())).fmt(tmp.path(), true).await.unwrap();
+        assert!(changed);
+        let after = tokio::fs::read_to_string(project_file_path(tmp.path()).await).await.unwrap();
+        assert_eq!(after, with_env);
+    }
+
+    #[tokio::test]
+    async fn fmt_pretty_prints_json_project() {
+        use crate::components::project_serialization::json_project_serialization::serialize_to_json;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let pretty = serialize_to_json(&example_project("test")).unwrap();
+        let compact = serde_json::to_string(&serde_json::from_str::<serde_json::Value>(&pretty).unwrap()).unwrap();
+        let path = tmp.path().join("dbloada.json");
+        tokio::fs::write(&path, &compact).await.unwrap();
+
+        let changed = make_fmt(Box::new(yaml_serialization())).fmt(tmp.path(), true).await.unwrap();
+        assert!(changed);
+        assert_eq!(tokio::fs::read_to_string(&path).await.unwrap(), pretty);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod fmt_impl;
+
+pub use fmt_impl::FmtImpl;
@@ -0,0 +1,259 @@
+use std::path::Path;
+use async_trait::async_trait;
+use crate::components::load::project_file_path;
+use crate::components::table_writer::csv_table_writer::table_to_csv;
+use crate::models::{ColumnIdentifier, FileSourceSpec, SourceSpec, TableSpec};
+use crate::traits::{FileSystem, Freeze, FreezeError, Logger, ProjectIO, TableReader};
+use crate::traits::table_reader;
+
+pub fn frozen_filename(table_name: &str) -> String {
+    format!("data/{table_name}.csv")
+}
+
+/// Rewrites a table spec to read the frozen CSV written by `table_to_csv`.
+/// The frozen file always has a header row named after the project columns.
+pub fn frozen_table_spec(table: &TableSpec) -> TableSpec {
+    let mut frozen = table.clone();
+    frozen.has_header = true;
+    frozen.source = SourceSpec::File(FileSourceSpec {
+        filename: frozen_filename(&table.name),
+        character_encoding: "utf-8".to_string(),
+        allow_special_files: false,
+        glob: false,
+        sheet: None,
+        csv: Default::default(),
+    });
+    for column in &mut frozen.columns {
+        column.column_identifier = ColumnIdentifier::Name(column.name.clone());
+    }
+    frozen
+}
+
+pub struct FreezeImpl {
+    logger: Box<dyn Logger>,
+    project_io: Box<dyn ProjectIO>,
+    file_system: Box<dyn FileSystem>,
+    table_readers: Vec<Box<dyn TableReader>>,
+}
+
+impl FreezeImpl {
+    pub fn new(
+        logger: Box<dyn Logger>,
+        project_io: Box<dyn ProjectIO>,
+        file_system: Box<dyn FileSystem>,
+        table_readers: Vec<Box<dyn TableReader>>,
+    ) -> Self {
+        FreezeImpl {
+            logger,
+            project_io,
+            file_system,
+            table_readers,
+        }
+    }
+}
+
+#[async_trait]
+impl Freeze for FreezeImpl {
+    async fn freeze(&self, path: &Path) -> Result<Vec<String>, FreezeError> {
+        let metadata = tokio::fs::metadata(path).await;
+        if metadata.is_err() || !metadata.unwrap().is_dir() {
+            return Err(FreezeError::DirectoryNotFound(path.display().to_string()));
+        }
+
+        let file_path = project_file_path(path).await;
+        if tokio::fs::metadata(&file_path).await.is_err() {
+            return Err(FreezeError::ProjectFileNotFound(file_path.display().to_string()));
+        }
+
+        let mut project = self.project_io.load(&file_path).await?;
+        let included = project.spec.tables.iter().find_map(|t| match (&t.source, &t.included_from) {
+            (SourceSpec::Cmd(_), Some(file)) => Some((t.name.clone(), file.clone())),
+            _ => None,
+        });
+        if let Some((table, file)) = included {
+            return Err(FreezeError::IncludedTable { table, file });
+        }
+        let mut frozen_tables = Vec::new();
+
+        for table_spec in &mut project.spec.tables {
+            if !matches!(table_spec.source, SourceSpec::Cmd(_)) {
+                continue;
+            }
+            self.logger.debug(&format!("freezing table '{}'", table_spec.name)).await;
+            let table = table_reader::read(&self.table_readers, table_spec, path).await?;
+            let content = table_to_csv(&table, true).map_err(|message| FreezeError::CsvWriteError {
+                table_name: table_spec.name.clone(),
+                message,
+            })?;
+
+            let frozen = frozen_table_spec(table_spec);
+            if let SourceSpec::File(fs) = &frozen.source {
+                self.file_system.save(&content, &path.join(&fs.filename)).await?;
+                self.logger.info(&format!(
+                    "froze table '{}' into {}",
+                    table_spec.name, fs.filename
+                )).await;
+            }
+            frozen_tables.push(table_spec.name.clone());
+            *table_spec = frozen;
+        }
+
+        self.project_io.save(&project, &file_path).await?;
+        Ok(frozen_tables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::csv_parser::CsvParserImpl;
+    use crate::components::file_system::DiskFileSystem;
+    use crate::components::project_io::YamlProjectIO;
+    use crate::components::project_serialization::YamlProjectSerialization;
+    use crate::components::table_reader::{CmdCsvTableReader, CsvTableReader, DEFAULT_MAX_CMD_PROCESSES};
+    use crate::components::test_helpers::TestLogger;
+    use crate::models::{
+        CmdSourceSpec, ColumnSpec, ColumnType, Project, ProjectSpec, PROJECT_API_VERSION, Table,
+    };
+
+    fn make_project_io() -> Box<dyn ProjectIO> {
+        Box::new(YamlProjectIO::new(
+            Box::new(TestLogger),
+            Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+            Box::new(YamlProjectSerialization::new(Box::new(TestLogger))),
+        ))
+    }
+
+    fn make_freeze() -> FreezeImpl {
+        FreezeImpl::new(
+            Box::new(TestLogger),
+            make_project_io(),
+            Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+            vec![
+                Box::new(CsvTableReader::new(
+                    Box::new(TestLogger),
+                    Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+                    Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+                    false,
+                )),
+                Box::new(CmdCsvTableReader::new(
+                    Box::new(TestLogger),
+                    Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+                    DEFAULT_MAX_CMD_PROCESSES,
+                )),
+            ],
+        )
+    }
+
+    fn employee_table() -> TableSpec {
+        TableSpec {
+            name: "employee".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Cmd(CmdSourceSpec {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "printf 'Office,Name\\nStar Tower,Alice\\n'".to_string()],
+                stdout: true,
+                character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
+            }),
+            columns: vec![ColumnSpec {
+                name: "name".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("Name".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    #[test]
+    fn table_to_csv_writes_header_and_quotes_values() {
+        let table = Table::new(
+            "t".to_string(),
+            vec!["name".to_string(), "note".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
+            vec![vec!["Alice".to_string(), "a, b".to_string()]],
+        );
+        assert_eq!(table_to_csv(&table, true).unwrap(), "name,note\nAlice,\"a, b\"\n");
+    }
+
+    #[test]
+    fn frozen_table_spec_uses_file_source_and_named_columns() {
+        let frozen = frozen_table_spec(&employee_table());
+        assert_eq!(
+            frozen.source,
+            SourceSpec::File(FileSourceSpec {
+                filename: "data/employee.csv".to_string(),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            })
+        );
+        assert!(frozen.has_header);
+        assert_eq!(frozen.columns[0].column_identifier, ColumnIdentifier::Name("name".to_string()));
+    }
+
+    #[tokio::test]
+    async fn freeze_rewrites_cmd_source_to_generated_csv() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = Project {
+            name: "test".to_string(),
+            api_version: PROJECT_API_VERSION.to_string(),
+            spec: ProjectSpec { tables: vec![employee_table()], includes: vec![], defaults: Default::default() },
+        };
+        let file_path = project_file_path(tmp.path()).await;
+        make_project_io().save(&project, &file_path).await.unwrap();
+
+        let frozen = make_freeze().freeze(tmp.path()).await.unwrap();
+        assert_eq!(frozen, vec!["employee"]);
+
+        let rewritten = make_project_io().load(&file_path).await.unwrap();
+        match &rewritten.spec.tables[0].source {
+            SourceSpec::File(fs) => assert_eq!(fs.filename, "data/employee.csv"),
+            _ => panic!("expected File source after freeze"),
+        }
+        let csv = tokio::fs::read_to_string(tmp.path().join("data/employee.csv")).await.unwrap();
+        assert_eq!(csv, "name\nAlice\n");
+    }
+
+    #[tokio::test]
+    async fn freeze_refuses_cmd_table_from_included_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let includes = vec!["staff.yaml".to_string()];
+        let spec = ProjectSpec { tables: vec![], includes, defaults: Default::default() };
+        let project = Project { name: "test".to_string(), api_version: PROJECT_API_VERSION.to_string(), spec };
+        let file_path = project_file_path(tmp.path()).await;
+        make_project_io().save(&project, &file_path).await.unwrap();
+        let included = "tables:\n  - name: employee\n    description: ''\n    hasHeader: true\n    columns: []\n    \
+                        source:\n      type: cmd\n      command: echo\n      args: [Name]\n";
+        tokio::fs::write(tmp.path().join("staff.yaml"), included).await.unwrap();
+        let before = tokio::fs::read_to_string(&file_path).await.unwrap();
+
+        let err = make_freeze().freeze(tmp.path()).await.unwrap_err();
+        assert!(
+            matches!(err, FreezeError::IncludedTable { ref table, ref file }
+                if table == "employee" && file == "staff.yaml"),
+            "error was: {err}"
+        );
+        assert_eq!(tokio::fs::read_to_string(&file_path).await.unwrap(), before);
+        assert!(!tmp.path().join("data").exists());
+    }
+}
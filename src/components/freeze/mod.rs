@@ -0,0 +1,3 @@
+pub mod freeze_impl;
+
+pub use freeze_impl::FreezeImpl;
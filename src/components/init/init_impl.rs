@@ -1,69 +1,14 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use async_trait::async_trait;
 use crate::models::{
     Project, ProjectSpec, TableSpec, SourceSpec, FileSourceSpec, CmdSourceSpec,
     ColumnSpec, ColumnIdentifier, ColumnType,
-    RelationshipSpec, PROJECT_API_VERSION,
+    RelationshipSpec, InitTemplate, ProjectFormat, PROJECT_API_VERSION, sanitize_resource_name, validate_resource_name,
 };
-use crate::traits::{ProjectIO, Init, InitError, Logger, FileSystem};
-
-pub fn sanitize_resource_name(raw: &str) -> String {
-    let s: String = raw
-        .to_lowercase()
-        .chars()
-        .map(|c| if c == ' ' || c == '_' { '-' } else { c })
-        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '-')
-        .collect();
-
-    let s = s.trim_matches('-').to_string();
-
-    // collapse consecutive hyphens
-    let mut result = String::with_capacity(s.len());
-    let mut prev_hyphen = false;
-    for c in s.chars() {
-        if c == '-' {
-            if !prev_hyphen {
-                result.push(c);
-            }
-            prev_hyphen = true;
-        } else {
-            result.push(c);
-            prev_hyphen = false;
-        }
-    }
-
-    // truncate to 63 chars, trim trailing hyphens
-    if result.len() > 63 {
-        result.truncate(63);
-    }
-    result.trim_end_matches('-').to_string()
-}
-
-pub fn validate_resource_name(name: &str) -> Result<(), String> {
-    if name.is_empty() {
-        return Err("name must not be empty".to_string());
-    }
-    if name.len() > 63 {
-        return Err(format!(
-            "name must be no more than 63 characters, got {}",
-            name.len()
-        ));
-    }
-    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
-        return Err(
-            "name must contain only lowercase alphanumeric characters or '-'".to_string(),
-        );
-    }
-    let first = name.chars().next().unwrap();
-    if !first.is_ascii_alphanumeric() {
-        return Err("name must start with an alphanumeric character".to_string());
-    }
-    let last = name.chars().last().unwrap();
-    if !last.is_ascii_alphanumeric() {
-        return Err("name must end with an alphanumeric character".to_string());
-    }
-    Ok(())
-}
+use crate::traits::{ProjectIO, Init, InitError, Logger, FileSystem, FileSystemError, ProjectSerializationError};
+use crate::components::project_serialization::yaml_project_serialization::serialize_to_yaml;
+use crate::components::load::project_file_path;
+use crate::components::validate::validate_impl::validate_project;
 
 pub fn example_project(name: &str) -> Project {
     Project {
@@ -75,40 +20,68 @@ pub fn example_project(name: &str) -> Project {
                     name: "country".to_string(),
                     description: "Countries where cities and by extension offices are located in".to_string(),
                     has_header: false,
+                    keep_raw_line: false,
                     source: SourceSpec::File(FileSourceSpec {
                         filename: "data/countries.csv".to_string(),
                         character_encoding: "utf-8".to_string(),
+                        allow_special_files: false,
+                        glob: false,
+                        sheet: None,
+                        csv: Default::default(),
                     }),
                     columns: vec![
                         ColumnSpec {
                             name: "name".to_string(),
                             description: "The official name of the country".to_string(),
                             column_identifier: ColumnIdentifier::Index(0),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: false,
+                            primary_key: true,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                     ],
                     relationships: vec![],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 },
                 TableSpec {
                     name: "city".to_string(),
                     description: "Cities located within a country".to_string(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::File(FileSourceSpec {
                         filename: "data/cities.csv".to_string(),
                         character_encoding: "utf-8".to_string(),
+                        allow_special_files: false,
+                        glob: false,
+                        sheet: None,
+                        csv: Default::default(),
                     }),
                     columns: vec![
                         ColumnSpec {
                             name: "name".to_string(),
                             description: "The official name of the city".to_string(),
                             column_identifier: ColumnIdentifier::Name("Name".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: false,
+                            primary_key: true,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                         ColumnSpec {
                             name: "country".to_string(),
                             description: "The country where the city is located in".to_string(),
                             column_identifier: ColumnIdentifier::Name("Country".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                     ],
                     relationships: vec![
@@ -118,29 +91,48 @@ pub fn example_project(name: &str) -> Project {
                             source_column: "country".to_string(),
                             target_table: "country".to_string(),
                             target_column: "name".to_string(),
+                            on_delete: Default::default(),
                         },
                     ],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 },
                 TableSpec {
                     name: "office".to_string(),
                     description: "The physical building where people in this company work".to_string(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::File(FileSourceSpec {
                         filename: "data/offices.csv".to_string(),
                         character_encoding: "utf-8".to_string(),
+                        allow_special_files: false,
+                        glob: false,
+                        sheet: None,
+                        csv: Default::default(),
                     }),
                     columns: vec![
                         ColumnSpec {
                             name: "building_name".to_string(),
                             description: "The name of the building".to_string(),
                             column_identifier: ColumnIdentifier::Name("Building Name".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                         ColumnSpec {
                             name: "location".to_string(),
                             description: "The city where the office is located".to_string(),
                             column_identifier: ColumnIdentifier::Name("Location".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                     ],
                     relationships: vec![
@@ -150,31 +142,53 @@ pub fn example_project(name: &str) -> Project {
                             source_column: "location".to_string(),
                             target_table: "city".to_string(),
                             target_column: "name".to_string(),
+                            on_delete: Default::default(),
                         },
                     ],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 },
                 TableSpec {
                     name: "employee".to_string(),
                     description: "Employees generated by a script".to_string(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::Cmd(CmdSourceSpec {
                         command: "bash".to_string(),
                         args: vec!["scripts/generate-employees.sh".to_string()],
                         stdout: true,
                         character_encoding: "utf-8".to_string(),
+                        csv: Default::default(),
+                        timeout_seconds: None,
+                        env: vec![],
+                        capture_stderr: false,
+                        retries: 0,
+                        retry_delay_seconds: 0,
+                        allowed_exit_codes: vec![0],
                     }),
                     columns: vec![
                         ColumnSpec {
                             name: "name".to_string(),
                             description: "The employee name".to_string(),
                             column_identifier: ColumnIdentifier::Name("Name".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                         ColumnSpec {
                             name: "office".to_string(),
                             description: "The office where the employee works".to_string(),
                             column_identifier: ColumnIdentifier::Name("Office".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                     ],
                     relationships: vec![
@@ -184,13 +198,18 @@ pub fn example_project(name: &str) -> Project {
                             source_column: "office".to_string(),
                             target_table: "office".to_string(),
                             target_column: "building_name".to_string(),
+                            on_delete: Default::default(),
                         },
                     ],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 },
                 TableSpec {
                     name: "department".to_string(),
                     description: "Departments generated by a script writing to a temp file".to_string(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::Cmd(CmdSourceSpec {
                         command: "bash".to_string(),
                         args: vec![
@@ -199,19 +218,36 @@ pub fn example_project(name: &str) -> Project {
                         ],
                         stdout: false,
                         character_encoding: "utf-8".to_string(),
+                        csv: Default::default(),
+                        timeout_seconds: None,
+                        env: vec![],
+                        capture_stderr: false,
+                        retries: 0,
+                        retry_delay_seconds: 0,
+                        allowed_exit_codes: vec![0],
                     }),
                     columns: vec![
                         ColumnSpec {
                             name: "name".to_string(),
                             description: "The department name".to_string(),
                             column_identifier: ColumnIdentifier::Name("Name".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                         ColumnSpec {
                             name: "head".to_string(),
                             description: "The head of the department".to_string(),
                             column_identifier: ColumnIdentifier::Name("Head".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                     ],
                     relationships: vec![
@@ -221,14 +257,81 @@ pub fn example_project(name: &str) -> Project {
                             source_column: "head".to_string(),
                             target_table: "employee".to_string(),
                             target_column: "name".to_string(),
+                            on_delete: Default::default(),
                         },
                     ],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 },
             ],
+            includes: vec![],
+            defaults: Default::default(),
         },
     }
 }
 
+/// A project without any tables, for `dbloada init --template minimal`.
+pub fn minimal_project(name: &str) -> Project {
+    Project {
+        name: name.to_string(),
+        api_version: PROJECT_API_VERSION.to_string(),
+        spec: ProjectSpec { tables: vec![], includes: vec![], defaults: Default::default() },
+    }
+}
+
+/// A file source table with a string column per name in `columns`, read by
+/// header name, or by position when the file has no header.
+pub fn file_table(name: &str, filename: &str, has_header: bool, columns: &[String]) -> TableSpec {
+    TableSpec {
+        name: name.to_string(),
+        description: String::new(),
+        has_header,
+        keep_raw_line: false,
+        source: SourceSpec::File(FileSourceSpec {
+            filename: filename.to_string(),
+            character_encoding: "utf-8".to_string(),
+            allow_special_files: false,
+            glob: false,
+            sheet: None,
+            csv: Default::default(),
+        }),
+        columns: columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| ColumnSpec {
+                name: column.clone(),
+                description: String::new(),
+                column_identifier: if has_header {
+                    ColumnIdentifier::Name(column.clone())
+                } else {
+                    ColumnIdentifier::Index(i as u64)
+                },
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            })
+            .collect(),
+        relationships: vec![],
+        filter: None,
+        dedup_by: vec![],
+        included_from: None,
+    }
+}
+
+/// The example project as it would be written to dbloada.yaml, for printing by `dbloada example`.
+pub fn example_project_yaml(name: &str) -> Result<String, ProjectSerializationError> {
+    let header = "\
+# Example dbloada project. Save it as dbloada.yaml and adapt it to your data.
+# Tables read CSV either from a file (type: file) or from the output of a
+# command (type: cmd); relationships reference columns of other tables.
+";
+    Ok(format!("{}{}", header, serialize_to_yaml(&example_project(name))?))
+}
+
 pub fn example_data_files() -> Vec<(&'static str, &'static str)> {
     vec![
         ("data/countries.csv", "\"United Kingdom\"\n\"Germany\"\n"),
@@ -270,6 +373,19 @@ impl InitImpl {
         InitImpl { logger, project_io, file_system }
     }
 
+    /// Removes the replaced project file in the other format, which would otherwise shadow or be
+    /// shadowed by the new one. Only called once the new file is written.
+    async fn remove_other_format(&self, other_format: Option<PathBuf>) -> Result<(), InitError> {
+        let Some(existing) = other_format else {
+            return Ok(());
+        };
+        tokio::fs::remove_file(&existing)
+            .await
+            .map_err(|source| FileSystemError::WriteError { path: existing.clone(), source })?;
+        self.logger.info(&format!("removed {}", existing.display())).await;
+        Ok(())
+    }
+
     fn resolve_name(path: &Path, name: Option<&str>) -> Result<String, InitError> {
         match name {
             Some(n) => {
@@ -303,7 +419,15 @@ impl InitImpl {
 
 #[async_trait]
 impl Init for InitImpl {
-    async fn init(&self, path: &Path, name: Option<&str>, force: bool) -> Result<(), InitError> {
+    async fn init(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        force: bool,
+        overwrite_project: bool,
+        template: InitTemplate,
+        format: ProjectFormat,
+    ) -> Result<(), InitError> {
         let metadata = tokio::fs::metadata(path).await;
         if metadata.is_err() || !metadata.unwrap().is_dir() {
             return Err(InitError::DirectoryNotFound(path.display().to_string()));
@@ -316,7 +440,22 @@ impl Init for InitImpl {
             }
         }
 
+        let existing = project_file_path(path).await;
+        let existing = tokio::fs::metadata(&existing).await.is_ok().then_some(existing);
+        if let Some(existing) = &existing
+            && !overwrite_project
+        {
+            return Err(InitError::ProjectFileExists(existing.display().to_string()));
+        }
         let project_name = Self::resolve_name(path, name)?;
+        let file_path = path.join(format.filename());
+        let other_format = existing.filter(|existing| *existing != file_path);
+
+        if template == InitTemplate::Minimal {
+            self.project_io.save(&minimal_project(&project_name), &file_path).await?;
+            self.logger.info(&format!("created {}", file_path.display())).await;
+            return self.remove_other_format(other_format).await;
+        }
 
         for dir in example_directories() {
             let dir_path = path.join(dir);
@@ -345,12 +484,54 @@ impl Init for InitImpl {
 
         let project = example_project(&project_name);
 
-        let file_path = path.join("dbloada.yaml");
         self.project_io.save(&project, &file_path).await?;
 
         self.logger.info(&format!("created {}", file_path.display())).await;
+        self.remove_other_format(other_format).await
+    }
+
+    async fn add_table(
+        &self,
+        path: &Path,
+        name: &str,
+        filename: &str,
+        has_header: bool,
+        columns: &[String],
+        force: bool,
+    ) -> Result<(), InitError> {
+        let table = file_table(name, filename, has_header, columns);
+        let file_path = project_file_path(path).await;
+        if tokio::fs::metadata(&file_path).await.is_err() {
+            return Err(InitError::ProjectFileNotFound(file_path.display().to_string()));
+        }
+        let mut project = self.project_io.load(&file_path).await?;
+
+        match project.spec.tables.iter_mut().find(|t| t.name == table.name) {
+            Some(TableSpec { included_from: Some(file), .. }) => {
+                return Err(InitError::IncludedTable { table: table.name, file: file.clone() });
+            }
+            Some(_) if !force => return Err(InitError::TableExists(table.name)),
+            Some(existing) => {
+                self.logger.info(&format!("replacing table '{}'", table.name)).await;
+                *existing = table;
+            }
+            None => {
+                self.logger.info(&format!("adding table '{}'", table.name)).await;
+                project.spec.tables.push(table);
+            }
+        }
+
+        let problems = validate_project(&project);
+        if !problems.is_empty() {
+            return Err(InitError::InvalidProject(problems));
+        }
+        self.project_io.save(&project, &file_path).await?;
         Ok(())
     }
+
+    fn example_project_yaml(&self) -> Result<String, InitError> {
+        example_project_yaml("example").map_err(|e| InitError::IOError(e.into()))
+    }
 }
 
 #[cfg(test)]
@@ -425,6 +606,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn example_project_yaml_reparses_into_five_tables() {
+        use crate::components::project_serialization::yaml_project_serialization::deserialize_from_yaml;
+
+        let yaml = example_project_yaml("example").unwrap();
+        assert!(yaml.starts_with("# Example dbloada project"));
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert_eq!(project, example_project("example"));
+        assert_eq!(project.spec.tables.len(), 5);
+        assert!(project.spec.tables.iter().any(|t| matches!(t.source, SourceSpec::Cmd(_))));
+        assert!(project.spec.tables.iter().any(|t| matches!(t.source, SourceSpec::File(_))));
+    }
+
     #[test]
     fn example_data_files_has_five_entries() {
         let files = example_data_files();
@@ -447,13 +641,14 @@ mod tests {
                 }
                 SourceSpec::Cmd(cs) => {
                     // For cmd sources, check the script is in the data files
-                    let script_path = format!("scripts/{}", cs.args[0].split('/').last().unwrap());
+                    let script_path = format!("scripts/{}", cs.args[0].split('/').next_back().unwrap());
                     assert!(
-                        file_paths.iter().any(|p| p.ends_with(cs.args[0].split('/').last().unwrap())),
+                        file_paths.iter().any(|p| p.ends_with(cs.args[0].split('/').next_back().unwrap())),
                         "script '{}' not found in example data files",
                         script_path
                     );
                 }
+                SourceSpec::Http(_) | SourceSpec::Stdin(_) => panic!("the example project has only file and cmd sources"),
             }
         }
     }
@@ -491,13 +686,195 @@ mod tests {
         tokio::fs::write(tmp.path().join("existing.txt"), "data").await.unwrap();
 
         let init = InitImpl::new(mock_logger(), mock_project_io(), mock_file_system());
-        let result = init.init(tmp.path(), Some("test-proj"), false).await;
+        let result = init
+            .init(tmp.path(), Some("test-proj"), false, false, InitTemplate::Full, ProjectFormat::Yaml)
+            .await;
 
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(matches!(err, InitError::DirectoryNotEmpty(_)));
     }
 
+    #[test]
+    fn minimal_project_has_no_tables() {
+        let project = minimal_project("empty");
+        assert_eq!(project.name, "empty");
+        assert_eq!(project.api_version, PROJECT_API_VERSION);
+        assert!(project.spec.tables.is_empty());
+    }
+
+    fn disk_project_io() -> Box<dyn ProjectIO> {
+        use crate::components::file_system::DiskFileSystem;
+        use crate::components::project_io::{FormatProjectIO, YamlProjectIO};
+        use crate::components::project_serialization::{JsonProjectSerialization, YamlProjectSerialization};
+        use crate::components::test_helpers::TestLogger;
+
+        let file_system = || Box::new(DiskFileSystem::new(Box::new(TestLogger)));
+        Box::new(FormatProjectIO::new(
+            Box::new(YamlProjectIO::new(
+                Box::new(TestLogger),
+                file_system(),
+                Box::new(YamlProjectSerialization::new(Box::new(TestLogger))),
+            )),
+            Box::new(YamlProjectIO::new(
+                Box::new(TestLogger),
+                file_system(),
+                Box::new(JsonProjectSerialization::new(Box::new(TestLogger))),
+            )),
+        ))
+    }
+
+    fn disk_init() -> InitImpl {
+        use crate::components::file_system::DiskFileSystem;
+        use crate::components::test_helpers::TestLogger;
+
+        InitImpl::new(Box::new(TestLogger), disk_project_io(), Box::new(DiskFileSystem::new(Box::new(TestLogger))))
+    }
+
+    #[tokio::test]
+    async fn init_minimal_writes_only_project_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("empty"), false, false, InitTemplate::Minimal, ProjectFormat::Yaml).await.unwrap();
+
+        let mut entries: Vec<String> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec!["dbloada.yaml"]);
+        let project = disk_project_io().load(&tmp.path().join("dbloada.yaml")).await.unwrap();
+        assert!(project.spec.tables.is_empty());
+    }
+
+    #[tokio::test]
+    async fn init_json_writes_dbloada_json() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("proj"), false, false, InitTemplate::Full, ProjectFormat::Json).await.unwrap();
+
+        assert!(!tmp.path().join("dbloada.yaml").exists());
+        let content = std::fs::read_to_string(tmp.path().join("dbloada.json")).unwrap();
+        assert!(content.starts_with('{'), "project file was:\n{content}");
+        let project = disk_project_io().load(&tmp.path().join("dbloada.json")).await.unwrap();
+        assert_eq!(project, example_project("proj"));
+
+        let columns = vec!["name".to_string()];
+        disk_init().add_table(tmp.path(), "region", "data/regions.csv", true, &columns, false).await.unwrap();
+        let project = disk_project_io().load(&tmp.path().join("dbloada.json")).await.unwrap();
+        assert_eq!(project.spec.tables.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn init_refuses_project_in_other_format_unless_overwriting() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("proj"), false, false, InitTemplate::Minimal, ProjectFormat::Yaml).await.unwrap();
+
+        let err = disk_init()
+            .init(tmp.path(), Some("proj"), true, false, InitTemplate::Minimal, ProjectFormat::Json)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, InitError::ProjectFileExists(ref path) if path.ends_with("dbloada.yaml")));
+
+        disk_init()
+            .init(tmp.path(), Some("proj"), true, true, InitTemplate::Minimal, ProjectFormat::Json).await.unwrap();
+        assert!(!tmp.path().join("dbloada.yaml").exists());
+        assert!(tmp.path().join("dbloada.json").exists());
+    }
+
+    #[tokio::test]
+    async fn init_keeps_project_in_other_format_when_the_name_is_invalid() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("proj"), false, false, InitTemplate::Minimal, ProjectFormat::Yaml).await.unwrap();
+        let before = tokio::fs::read_to_string(tmp.path().join("dbloada.yaml")).await.unwrap();
+
+        let err = disk_init()
+            .init(tmp.path(), Some("Bad_Name"), true, true, InitTemplate::Minimal, ProjectFormat::Json)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, InitError::InvalidResourceName { .. }), "error was: {err}");
+        assert_eq!(tokio::fs::read_to_string(tmp.path().join("dbloada.yaml")).await.unwrap(), before);
+        assert!(!tmp.path().join("dbloada.json").exists());
+    }
+
+    #[test]
+    fn file_table_reads_columns_by_name_or_position() {
+        let columns = vec!["code".to_string(), "label".to_string()];
+        let table = file_table("t", "data/t.csv", true, &columns);
+        assert_eq!(table.columns[1].column_identifier, ColumnIdentifier::Name("label".to_string()));
+        let table = file_table("t", "data/t.csv", false, &columns);
+        assert_eq!(table.columns[1].column_identifier, ColumnIdentifier::Index(1));
+    }
+
+    #[tokio::test]
+    async fn add_table_appends_after_existing_tables() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("proj"), false, false, InitTemplate::Full, ProjectFormat::Yaml).await.unwrap();
+
+        let columns = vec!["name".to_string()];
+        disk_init().add_table(tmp.path(), "region", "data/regions.csv", true, &columns, false).await.unwrap();
+
+        let project = disk_project_io().load(&tmp.path().join("dbloada.yaml")).await.unwrap();
+        let names: Vec<&str> = project.spec.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["country", "city", "office", "employee", "department", "region"]);
+        assert_eq!(project.spec.tables[5], file_table("region", "data/regions.csv", true, &columns));
+    }
+
+    #[tokio::test]
+    async fn add_table_refuses_existing_name_unless_forced() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("proj"), false, false, InitTemplate::Full, ProjectFormat::Yaml).await.unwrap();
+        let columns = vec!["name".to_string()];
+
+        let err =
+            disk_init().add_table(tmp.path(), "city", "data/towns.csv", false, &columns, false).await.unwrap_err();
+        assert!(matches!(err, InitError::TableExists(ref name) if name == "city"));
+
+        // the office relationship targets city.name, which the replacement still has
+        disk_init().add_table(tmp.path(), "city", "data/towns.csv", false, &columns, true).await.unwrap();
+        let project = disk_project_io().load(&tmp.path().join("dbloada.yaml")).await.unwrap();
+        assert_eq!(project.spec.tables.len(), 5);
+        assert_eq!(project.spec.tables[1], file_table("city", "data/towns.csv", false, &columns));
+    }
+
+    #[tokio::test]
+    async fn add_table_refuses_to_replace_table_from_included_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("proj"), false, false, InitTemplate::Minimal, ProjectFormat::Yaml).await.unwrap();
+        let file_path = tmp.path().join("dbloada.yaml");
+        let mut project = disk_project_io().load(&file_path).await.unwrap();
+        project.spec.includes = vec!["geo.yaml".to_string()];
+        disk_project_io().save(&project, &file_path).await.unwrap();
+        let included = "tables:\n  - name: region\n    description: ''\n    hasHeader: true\n    columns: []\n    \
+                        source:\n      type: file\n      filename: data/regions.csv\n";
+        tokio::fs::write(tmp.path().join("geo.yaml"), included).await.unwrap();
+        let before = tokio::fs::read_to_string(&file_path).await.unwrap();
+
+        let columns = vec!["name".to_string()];
+        let err = disk_init().add_table(tmp.path(), "region", "data/towns.csv", true, &columns, true).await.unwrap_err();
+        assert!(
+            matches!(err, InitError::IncludedTable { ref table, ref file } if table == "region" && file == "geo.yaml"),
+            "error was: {err}"
+        );
+        assert_eq!(tokio::fs::read_to_string(&file_path).await.unwrap(), before);
+    }
+
+    #[tokio::test]
+    async fn add_table_rejects_table_that_breaks_the_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        disk_init()
+            .init(tmp.path(), Some("proj"), false, false, InitTemplate::Full, ProjectFormat::Yaml).await.unwrap();
+        let columns = vec!["town".to_string()];
+
+        let err = disk_init().add_table(tmp.path(), "city", "data/towns.csv", true, &columns, true).await.unwrap_err();
+        assert!(matches!(err, InitError::InvalidProject(_)), "error was: {err}");
+    }
+
     #[tokio::test]
     async fn init_allows_non_empty_directory_with_force() {
         use crate::components::test_helpers::{mock_logger, mock_project_io, mock_file_system};
@@ -506,8 +883,26 @@ mod tests {
         tokio::fs::write(tmp.path().join("existing.txt"), "data").await.unwrap();
 
         let init = InitImpl::new(mock_logger(), mock_project_io(), mock_file_system());
-        let result = init.init(tmp.path(), Some("test-proj"), true).await;
+        let result = init
+            .init(tmp.path(), Some("test-proj"), true, false, InitTemplate::Full, ProjectFormat::Yaml)
+            .await;
 
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn init_with_force_refuses_to_overwrite_project_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file_path = tmp.path().join("dbloada.yaml");
+        tokio::fs::write(&file_path, "hand-written").await.unwrap();
+
+        let err = disk_init()
+            .init(tmp.path(), Some("proj"), true, false, InitTemplate::Full, ProjectFormat::Yaml).await.unwrap_err();
+        assert!(matches!(err, InitError::ProjectFileExists(_)), "error was: {err}");
+        assert_eq!(tokio::fs::read_to_string(&file_path).await.unwrap(), "hand-written");
+
+        disk_init().init(tmp.path(), Some("proj"), true, true, InitTemplate::Full, ProjectFormat::Yaml).await.unwrap();
+        let project = disk_project_io().load(&file_path).await.unwrap();
+        assert_eq!(project.name, "proj");
+    }
 }
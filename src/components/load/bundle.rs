@@ -0,0 +1,29 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use super::load_impl::project_file_path;
+
+/// Unpacks a `.tar.gz` bundle into `dest`.
+pub fn extract_bundle(bundle: &Path, dest: &Path) -> Result<(), String> {
+    let file = File::open(bundle).map_err(|e| format!("failed to open bundle: {}", e))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    archive.unpack(dest).map_err(|e| format!("failed to extract bundle: {}", e))
+}
+
+/// Finds the directory holding the project file: either the root of the
+/// extracted tree or its single top-level directory.
+pub async fn bundle_project_dir(extracted: &Path) -> Option<PathBuf> {
+    let has_project_file =
+        async |dir: &Path| tokio::fs::metadata(project_file_path(dir).await).await.is_ok_and(|m| m.is_file());
+    if has_project_file(extracted).await {
+        return Some(extracted.to_path_buf());
+    }
+    let mut read_dir = tokio::fs::read_dir(extracted).await.ok()?;
+    let mut entries = Vec::new();
+    while let Ok(Some(entry)) = read_dir.next_entry().await {
+        entries.push(entry.path());
+    }
+    match entries.as_slice() {
+        [single] if has_project_file(single).await => Some(single.clone()),
+        _ => None,
+    }
+}
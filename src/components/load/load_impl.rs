@@ -1,13 +1,237 @@
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use async_trait::async_trait;
-use crate::models::{LoadedProject, Project, Table};
-use crate::traits::{ProjectIO, Load, LoadError, Logger, TableReader};
+use futures::StreamExt;
+use crate::models::{
+    ColumnIdentifier, ColumnMapping, ColumnSpec, DanglingReference, DuplicateValue, LoadOptions, LoadReport,
+    LoadedProject, Project, ProjectFormat, SourceSpec, StdinSourceSpec, Table, TableBlanks, TableMapping, TableSpec,
+    TableTiming, count_blank_rows,
+};
+use crate::traits::{ProjectIO, Load, LoadError, Logger, RowCallback, TableReader};
 use crate::traits::table_reader;
+use super::bundle::{bundle_project_dir, extract_bundle};
 
-pub const DBLOADA_PROJECT_FILENAME: &str = "dbloada.yaml";
+/// The project file in `dir`: dbloada.yaml, or dbloada.json when only that one exists.
+pub async fn project_file_path(dir: &Path) -> PathBuf {
+    let yaml = dir.join(ProjectFormat::Yaml.filename());
+    let json = dir.join(ProjectFormat::Json.filename());
+    let exists = async |path: &Path| tokio::fs::try_exists(path).await.unwrap_or(false);
+    if !exists(&yaml).await && exists(&json).await { json } else { yaml }
+}
+
+pub fn validate_self_references(project: &Project) -> Result<(), LoadError> {
+    for table in &project.spec.tables {
+        for rel in &table.relationships {
+            if rel.is_ambiguous_self_reference(&table.name) {
+                return Err(LoadError::AmbiguousSelfReference {
+                    table: table.name.clone(),
+                    relationship: rel.name.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    Visiting,
+    Done,
+}
+
+fn visit<'a>(
+    index: usize,
+    tables: &'a [TableSpec],
+    states: &mut Vec<Option<VisitState>>,
+    stack: &mut Vec<usize>,
+    order: &mut Vec<&'a TableSpec>,
+    skipped: &[String],
+) -> Result<(), LoadError> {
+    match states[index] {
+        Some(VisitState::Done) => return Ok(()),
+        Some(VisitState::Visiting) => {
+            let start = stack.iter().position(|&i| i == index).unwrap_or(0);
+            let mut cycle: Vec<String> = stack[start..].iter().map(|&i| tables[i].name.clone()).collect();
+            cycle.push(tables[index].name.clone());
+            return Err(LoadError::RelationshipCycle { tables: cycle });
+        }
+        None => {}
+    }
+
+    states[index] = Some(VisitState::Visiting);
+    stack.push(index);
+    let table = &tables[index];
+    for rel in &table.relationships {
+        if rel.is_self_reference(&table.name) || skipped.contains(&rel.name) {
+            continue;
+        }
+        // unknown target tables are reported by validation, not here
+        if let Some(target) = tables.iter().position(|t| t.name == rel.target_table) {
+            visit(target, tables, states, stack, order, skipped)?;
+        }
+    }
+    stack.pop();
+    states[index] = Some(VisitState::Done);
+    order.push(table);
+    Ok(())
+}
+
+/// Orders tables so that every relationship target comes before the table
+/// referencing it. Tables without dependencies keep their declared order.
+/// Relationships named in `skipped` are ignored.
+pub fn dependency_order<'a>(tables: &'a [TableSpec], skipped: &[String]) -> Result<Vec<&'a TableSpec>, LoadError> {
+    let mut states = vec![None; tables.len()];
+    let mut stack = Vec::new();
+    let mut order = Vec::with_capacity(tables.len());
+    for index in 0..tables.len() {
+        visit(index, tables, &mut states, &mut stack, &mut order, skipped)?;
+    }
+    Ok(order)
+}
+
+/// Lists everything the load would write to disk: a spec dump and cmd sources
+/// of the selected tables that run in temp file mode.
+pub fn side_effects(project: &Project, options: &LoadOptions) -> Vec<String> {
+    let mut effects = Vec::new();
+    if let Some(dump_path) = &options.dump_spec {
+        effects.push(format!("the resolved spec would be written to {}", dump_path.display()));
+    }
+    for table in &project.spec.tables {
+        if !options.tables.is_empty() && !options.tables.contains(&table.name) {
+            continue;
+        }
+        if let SourceSpec::Cmd(cmd) = &table.source
+            && !cmd.stdout
+        {
+            effects.push(format!("table '{}': command '{}' writes a temp file", table.name, cmd.command));
+        }
+    }
+    effects
+}
+
+/// Rebinds `table_name` to read standard input, keeping the encoding and CSV options of its own source.
+pub fn bind_stdin(project: &mut Project, table_name: &str) -> Result<(), LoadError> {
+    let table = project
+        .spec
+        .tables
+        .iter_mut()
+        .find(|t| t.name == table_name)
+        .ok_or_else(|| LoadError::UnknownTable(table_name.to_string()))?;
+    table.source = SourceSpec::Stdin(StdinSourceSpec {
+        character_encoding: table.source.character_encoding().to_string(),
+        csv: table.source.csv_options().clone(),
+    });
+    Ok(())
+}
+
+/// Names of the selected tables that read standard input.
+pub fn stdin_tables(project: &Project, options: &LoadOptions) -> Vec<String> {
+    project
+        .spec
+        .tables
+        .iter()
+        .filter(|t| options.tables.is_empty() || options.tables.contains(&t.name))
+        .filter(|t| matches!(t.source, SourceSpec::Stdin(_)))
+        .map(|t| t.name.clone())
+        .collect()
+}
 
-pub fn project_file_path(dir: &Path) -> PathBuf {
-    dir.join(DBLOADA_PROJECT_FILENAME)
+fn column_values<'a>(table: &'a Table, column: &str) -> Option<impl Iterator<Item = &'a str>> {
+    let index = table.headers().iter().position(|h| h == column)?;
+    Some(table.rows.iter().map(move |row| row.get(index).map(|s| s.as_str()).unwrap_or("")))
+}
+
+/// Every non-empty relationship source value missing from its target column, in project order.
+/// Relationships whose tables or columns are missing are left to validation.
+pub fn find_dangling_references(project: &Project, tables: &[Table], skipped: &[String]) -> Vec<DanglingReference> {
+    let find = |name: &str| tables.iter().find(|t| t.name == name);
+    let mut dangling = Vec::new();
+    for table_spec in &project.spec.tables {
+        for rel in table_spec.relationships.iter().filter(|r| !skipped.contains(&r.name)) {
+            let (Some(source), Some(target)) = (find(&table_spec.name), find(&rel.target_table)) else {
+                continue;
+            };
+            let (Some(source_values), Some(target_values)) =
+                (column_values(source, &rel.source_column), column_values(target, &rel.target_column))
+            else {
+                continue;
+            };
+            let known: HashSet<&str> = target_values.collect();
+            dangling.extend(
+                source_values
+                    .enumerate()
+                    .filter(|(_, v)| !v.is_empty() && !known.contains(v))
+                    .map(|(row, value)| DanglingReference {
+                        table: table_spec.name.clone(),
+                        relationship: rel.name.clone(),
+                        row,
+                        value: value.to_string(),
+                    }),
+            );
+        }
+    }
+    dangling
+}
+
+/// Every value held by more than one row of a unique column, in project and then
+/// first-row order. Empty cells of columns reading them as NULL are exempt.
+pub fn find_duplicate_values(project: &Project, tables: &[Table]) -> Vec<DuplicateValue> {
+    let mut duplicates = Vec::new();
+    for table_spec in &project.spec.tables {
+        let Some(table) = tables.iter().find(|t| t.name == table_spec.name) else {
+            continue;
+        };
+        for col in table_spec.columns.iter().filter(|c| c.unique) {
+            let Some(values) = column_values(table, &col.name) else {
+                continue;
+            };
+            let mut rows_by_value: HashMap<&str, Vec<usize>> = HashMap::new();
+            let mut order = Vec::new();
+            for (row, value) in values.enumerate().filter(|(_, v)| !(col.empty_is_null && v.is_empty())) {
+                let rows = rows_by_value.entry(value).or_default();
+                if rows.is_empty() {
+                    order.push(value);
+                }
+                rows.push(row);
+            }
+            duplicates.extend(order.into_iter().filter(|v| rows_by_value[v].len() > 1).map(|value| DuplicateValue {
+                table: table_spec.name.clone(),
+                column: col.name.clone(),
+                value: value.to_string(),
+                rows: rows_by_value[value].clone(),
+            }));
+        }
+    }
+    duplicates
+}
+
+/// The field a column reads when that follows from the spec alone: index columns
+/// and captures from them.
+pub fn spec_column_index(table: &TableSpec, col: &ColumnSpec) -> Option<usize> {
+    match &col.column_identifier {
+        ColumnIdentifier::Index(i) => Some(*i as usize),
+        ColumnIdentifier::Name(_) => None,
+        ColumnIdentifier::Capture { source, .. } => table
+            .columns
+            .iter()
+            .find(|c| &c.name == source && matches!(c.column_identifier, ColumnIdentifier::Index(_)))
+            .and_then(|c| spec_column_index(table, c)),
+    }
+}
+
+/// Tables read at the same time when `LoadOptions::jobs` is unset: one per available CPU.
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// With `options.partial`, wraps `error` together with the tables read before it.
+fn partial(options: &LoadOptions, tables: Vec<Table>, error: LoadError) -> LoadError {
+    if options.partial {
+        LoadError::Partial { tables, error: Box::new(error) }
+    } else {
+        error
+    }
 }
 
 pub struct LoadImpl {
@@ -29,32 +253,32 @@ impl LoadImpl {
         }
     }
 
-    async fn read_tables(&self, project: &Project, project_dir: &Path) -> Result<Vec<Table>, LoadError> {
-        let mut tables = Vec::new();
-        for table_spec in &project.spec.tables {
-            self.logger.debug(&format!("reading table '{}'", table_spec.name)).await;
-            let table = table_reader::read(&self.table_readers, table_spec, project_dir).await?;
-            self.logger.info(&format!(
-                "loaded table '{}': {} rows, {} columns",
-                table.name,
-                table.num_rows(),
-                table.num_columns(),
-            )).await;
-            tables.push(table);
+    /// The file sources of the selected tables that do not exist, as `table 'x': path`.
+    async fn missing_source_files(
+        &self,
+        project: &Project,
+        project_dir: &Path,
+        options: &LoadOptions,
+    ) -> Result<Vec<String>, LoadError> {
+        let mut missing = Vec::new();
+        for table in &project.spec.tables {
+            if !options.tables.is_empty() && !options.tables.contains(&table.name) {
+                continue;
+            }
+            if let Some(path) = table_reader::missing_file(&self.table_readers, table, project_dir).await? {
+                missing.push(format!("table '{}': {}", table.name, path.display()));
+            }
         }
-        Ok(tables)
+        Ok(missing)
     }
-}
 
-#[async_trait]
-impl Load for LoadImpl {
-    async fn load(&self, path: &Path) -> Result<LoadedProject, LoadError> {
+    async fn load_project_file(&self, path: &Path) -> Result<Project, LoadError> {
         let metadata = tokio::fs::metadata(path).await;
         if metadata.is_err() || !metadata.unwrap().is_dir() {
             return Err(LoadError::DirectoryNotFound(path.display().to_string()));
         }
 
-        let file_path = project_file_path(path);
+        let file_path = project_file_path(path).await;
         let file_metadata = tokio::fs::metadata(&file_path).await;
         if file_metadata.is_err() {
             return Err(LoadError::ProjectFileNotFound(file_path.display().to_string()));
@@ -63,9 +287,227 @@ impl Load for LoadImpl {
         self.logger.debug(&format!("loading project from: {}", file_path.display())).await;
         let project = self.project_io.load(&file_path).await?;
         self.logger.info(&format!("loaded project '{}' from: {}", project.name, file_path.display())).await;
-        let tables = self.read_tables(&project, path).await?;
+        validate_self_references(&project)?;
+        Ok(project)
+    }
+
+    async fn read_tables(
+        &self,
+        project: &Project,
+        project_dir: &Path,
+        options: &LoadOptions,
+    ) -> Result<(Vec<Table>, LoadReport), LoadError> {
+        let selected = |spec: &&TableSpec| options.tables.is_empty() || options.tables.contains(&spec.name);
+        // reads start in dependency order; each keeps its position in the spec for the result
+        let position = |spec: &TableSpec| project.spec.tables.iter().position(|t| std::ptr::eq(t, spec)).unwrap_or(0);
+        let specs: Vec<(usize, &TableSpec)> = dependency_order(&project.spec.tables, &options.skip_relationships)?
+            .into_iter()
+            .filter(selected)
+            .map(|spec| (position(spec), spec))
+            .collect();
+        let jobs = options.jobs.unwrap_or_else(default_jobs).max(1);
+        self.logger.debug(&format!("reading {} table(s), at most {} at a time", specs.len(), jobs)).await;
+
+        let reads: Vec<_> = specs
+            .iter()
+            .map(|&(index, table_spec)| async move {
+                self.logger.debug(&format!("reading table '{}'", table_spec.name)).await;
+                let started = Instant::now();
+                let read = if options.dry_run {
+                    table_reader::dry_read(&self.table_readers, table_spec, project_dir).await
+                } else {
+                    table_reader::read(&self.table_readers, table_spec, project_dir).await
+                };
+                (index, read, started.elapsed())
+            })
+            .collect();
+        let mut reads = futures::stream::iter(reads).buffer_unordered(jobs);
 
-        Ok(LoadedProject { project, tables })
+        // reads finish in any order; slots keep the tables in the order the spec lists them
+        let mut slots: Vec<Option<(Table, Duration)>> = project.spec.tables.iter().map(|_| None).collect();
+        while let Some((index, read, duration)) = reads.next().await {
+            match read {
+                Ok(table) => {
+                    self.logger.info(&format!(
+                        "loaded table '{}': {} rows, {} columns",
+                        table.name,
+                        table.num_rows(),
+                        table.num_columns(),
+                    )).await;
+                    slots[index] = Some((table, duration));
+                }
+                Err(e) => {
+                    // dropping the stream cancels the reads still in flight
+                    drop(reads);
+                    let tables = slots.into_iter().flatten().map(|(table, _)| table).collect();
+                    return Err(partial(options, tables, e.into()));
+                }
+            }
+        }
+
+        let mut tables = Vec::new();
+        let mut report = LoadReport::default();
+        for (table, duration) in slots.into_iter().flatten() {
+            report.table_timings.push(TableTiming { table_name: table.name.clone(), duration });
+            report.table_blanks.push(TableBlanks {
+                table_name: table.name.clone(),
+                blank_rows: count_blank_rows(&table),
+                total_rows: table.num_rows(),
+            });
+            tables.push(table);
+        }
+        Ok((tables, report))
+    }
+}
+
+#[async_trait]
+impl Load for LoadImpl {
+    async fn load(&self, path: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError> {
+        let mut project = self.load_project_file(path).await?;
+        if let Some(table_name) = &options.stdin_table {
+            bind_stdin(&mut project, table_name)?;
+        }
+        let stdin = stdin_tables(&project, options);
+        if stdin.len() > 1 {
+            return Err(LoadError::MultipleStdinTables(stdin));
+        }
+        if let Some(limit) = options.limit {
+            for table_spec in &mut project.spec.tables {
+                let csv = table_spec.source.csv_options_mut();
+                csv.limit = Some(csv.limit.map_or(limit, |own| own.min(limit)));
+            }
+        }
+
+        if options.no_side_effects {
+            let effects = side_effects(&project, options);
+            if !effects.is_empty() {
+                return Err(LoadError::SideEffects(effects));
+            }
+        }
+
+        if let Some(dump_path) = &options.dump_spec {
+            let flattened = Project { spec: project.spec.flattened(), ..project.clone() };
+            self.project_io.save(&flattened, dump_path).await?;
+            self.logger.info(&format!("dumped effective project spec to: {}", dump_path.display())).await;
+        }
+
+        if let Some(unknown) = options.tables.iter().find(|name| !project.spec.tables.iter().any(|t| t.name == **name)) {
+            return Err(LoadError::UnknownTable(unknown.clone()));
+        }
+
+        let missing = self.missing_source_files(&project, path, options).await?;
+        if !missing.is_empty() {
+            return Err(LoadError::MissingSourceFiles(missing));
+        }
+
+        let skipped = &options.skip_relationships;
+        for name in skipped {
+            let known = project.spec.tables.iter().any(|t| t.relationships.iter().any(|r| &r.name == name));
+            if !known {
+                self.logger.warn(&format!("cannot skip relationship '{}': no relationship has that name", name)).await;
+            }
+        }
+
+        let (tables, mut report) = self.read_tables(&project, path, options).await?;
+        if options.check_relationships && options.limit.is_some() {
+            // capped tables may leave out rows that other tables reference
+            self.logger.info("skipped referential integrity check: rows are limited").await;
+        } else if options.check_relationships {
+            let dangling = find_dangling_references(&project, &tables, skipped);
+            if !dangling.is_empty() {
+                return Err(partial(options, tables, LoadError::DanglingReferences(dangling)));
+            }
+            let duplicates = find_duplicate_values(&project, &tables);
+            if !duplicates.is_empty() {
+                return Err(partial(options, tables, LoadError::DuplicateValues(duplicates)));
+            }
+        }
+        for table_spec in &project.spec.tables {
+            for rel in table_spec.relationships.iter().filter(|r| skipped.contains(&r.name)) {
+                self.logger.info(&format!(
+                    "skipped relationship '{}' on table '{}'",
+                    rel.name, table_spec.name
+                )).await;
+                report.skipped_relationships.push(format!("{}.{}", table_spec.name, rel.name));
+            }
+        }
+
+        Ok(LoadedProject { project, tables, report })
+    }
+
+    async fn load_bundle(&self, bundle: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError> {
+        let bundle_error = |message: String| LoadError::Bundle { path: bundle.display().to_string(), message };
+        if options.no_side_effects {
+            return Err(LoadError::SideEffects(vec!["a bundle is extracted to a temp directory".to_string()]));
+        }
+        let extract_dir = std::env::temp_dir().join(format!("dbloada-bundle-{}", uuid::Uuid::new_v4()));
+        self.logger.debug(&format!("extracting bundle {} to: {}", bundle.display(), extract_dir.display())).await;
+
+        let (bundle_path, dest) = (bundle.to_path_buf(), extract_dir.clone());
+        let extracted = tokio::task::spawn_blocking(move || extract_bundle(&bundle_path, &dest))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result);
+        let result = match extracted {
+            Ok(()) => match bundle_project_dir(&extract_dir).await {
+                Some(project_dir) => self.load(&project_dir, options).await,
+                None => Err(bundle_error(format!(
+                    "no {} or {} found in bundle",
+                    ProjectFormat::Yaml.filename(),
+                    ProjectFormat::Json.filename()
+                ))),
+            },
+            Err(message) => Err(bundle_error(message)),
+        };
+
+        if let Err(e) = tokio::fs::remove_dir_all(&extract_dir).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            self.logger.warn(&format!("failed to remove {}: {}", extract_dir.display(), e)).await;
+        }
+        result
+    }
+
+    async fn stream_table(&self, path: &Path, table_name: &str, on_row: &mut RowCallback<'_>) -> Result<usize, LoadError> {
+        let project = self.load_project_file(path).await?;
+        let table_spec = project
+            .spec
+            .tables
+            .iter()
+            .find(|t| t.name == table_name)
+            .ok_or_else(|| LoadError::UnknownTable(table_name.to_string()))?;
+
+        self.logger.debug(&format!("streaming table '{}'", table_spec.name)).await;
+        let row_count = table_reader::read_rows(&self.table_readers, table_spec, path, on_row).await?;
+        self.logger.info(&format!("streamed table '{}': {} rows", table_spec.name, row_count)).await;
+        Ok(row_count)
+    }
+
+    async fn column_mappings(&self, path: &Path) -> Result<Vec<TableMapping>, LoadError> {
+        let project = self.load_project_file(path).await?;
+        let mut mappings = Vec::new();
+        for table_spec in &project.spec.tables {
+            let indices = table_reader::column_indices(&self.table_readers, table_spec, path).await?;
+            let columns = table_spec
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, col)| ColumnMapping {
+                    output_column: col.name.clone(),
+                    identifier: col.column_identifier.clone(),
+                    resolved_index: match &indices {
+                        Some(indices) => indices.get(i).copied(),
+                        None => spec_column_index(table_spec, col),
+                    },
+                })
+                .collect();
+            mappings.push(TableMapping { table_name: table_spec.name.clone(), columns });
+        }
+        Ok(mappings)
+    }
+
+    async fn read_project(&self, path: &Path) -> Result<Project, LoadError> {
+        self.load_project_file(path).await
     }
 }
 
@@ -73,18 +515,1005 @@ impl Load for LoadImpl {
 mod tests {
     use super::*;
 
+    const DBLOADA_PROJECT_FILENAME: &str = "dbloada.yaml";
+
+    fn disk_loader() -> LoadImpl {
+        disk_loader_with(vec![])
+    }
+
+    /// The disk loader with `extra_readers` tried after the CSV and command readers.
+    fn disk_loader_with(extra_readers: Vec<Box<dyn TableReader>>) -> LoadImpl {
+        use crate::components::csv_parser::CsvParserImpl;
+        use crate::components::file_system::DiskFileSystem;
+        use crate::components::project_io::{FormatProjectIO, YamlProjectIO};
+        use crate::components::project_serialization::{JsonProjectSerialization, YamlProjectSerialization};
+        use crate::components::table_reader::{CmdCsvTableReader, CsvTableReader, DEFAULT_MAX_CMD_PROCESSES};
+        use crate::components::test_helpers::TestLogger;
+        use crate::traits::ProjectSerialization;
+
+        let mut table_readers: Vec<Box<dyn TableReader>> = vec![
+            Box::new(CsvTableReader::new(
+                Box::new(TestLogger),
+                Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+                Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+                false,
+            )),
+            Box::new(CmdCsvTableReader::new(
+                Box::new(TestLogger),
+                Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+                DEFAULT_MAX_CMD_PROCESSES,
+            )),
+        ];
+        table_readers.extend(extra_readers);
+        let project_io = |serialization: Box<dyn ProjectSerialization>| {
+            let file_system = Box::new(DiskFileSystem::new(Box::new(TestLogger)));
+            Box::new(YamlProjectIO::new(Box::new(TestLogger), file_system, serialization))
+        };
+        LoadImpl::new(
+            Box::new(TestLogger),
+            Box::new(FormatProjectIO::new(
+                project_io(Box::new(YamlProjectSerialization::new(Box::new(TestLogger)))),
+                project_io(Box::new(JsonProjectSerialization::new(Box::new(TestLogger)))),
+            )),
+            table_readers,
+        )
+    }
+
+    async fn save_project(dir: &Path, project: &Project) {
+        use crate::components::file_system::DiskFileSystem;
+        use crate::components::project_io::YamlProjectIO;
+        use crate::components::project_serialization::YamlProjectSerialization;
+        use crate::components::test_helpers::TestLogger;
+
+        YamlProjectIO::new(
+            Box::new(TestLogger),
+            Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+            Box::new(YamlProjectSerialization::new(Box::new(TestLogger))),
+        )
+        .save(project, &dir.join(DBLOADA_PROJECT_FILENAME))
+        .await
+        .unwrap();
+    }
+
+    fn employee_project(manager_target_column: &str) -> Project {
+        use crate::models::{
+            ColumnIdentifier, ColumnSpec, ColumnType, FileSourceSpec, ProjectSpec, RelationshipSpec,
+            SourceSpec, TableSpec,
+        };
+
+        let column = |name: &str, header: &str| ColumnSpec {
+            name: name.to_string(),
+            description: String::new(),
+            column_identifier: ColumnIdentifier::Name(header.to_string()),
+            column_type: ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
+        };
+        Project {
+            name: "test".to_string(),
+            api_version: "project.dbloada.io/v1".to_string(),
+            spec: ProjectSpec {
+                tables: vec![TableSpec {
+                    name: "employee".to_string(),
+                    description: String::new(),
+                    has_header: true,
+                    keep_raw_line: false,
+                    source: SourceSpec::File(FileSourceSpec {
+                        filename: "data/employees.csv".to_string(),
+                        character_encoding: "utf-8".to_string(),
+                        allow_special_files: false,
+                        glob: false,
+                        sheet: None,
+                        csv: Default::default(),
+                    }),
+                    columns: vec![column("name", "Name"), column("manager", "Manager")],
+                    relationships: vec![RelationshipSpec {
+                        name: "reports_to".to_string(),
+                        description: String::new(),
+                        source_column: "manager".to_string(),
+                        target_table: "employee".to_string(),
+                        target_column: manager_target_column.to_string(),
+                        on_delete: Default::default(),
+                    }],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
+                }],
+                includes: vec![],
+                defaults: Default::default(),
+            },
+        }
+    }
+
+    async fn write_employees(dir: &Path) {
+        let data_dir = dir.join("data");
+        tokio::fs::create_dir_all(&data_dir).await.unwrap();
+        tokio::fs::write(data_dir.join("employees.csv"), "Name,Manager\nAlice,\nBob,Alice\n").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn load_reads_self_referential_table_once() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_employees(tmp.path()).await;
+        save_project(tmp.path(), &employee_project("name")).await;
+
+        let loaded = disk_loader().load(tmp.path(), &LoadOptions::default()).await.unwrap();
+        assert_eq!(loaded.tables.len(), 1);
+        assert_eq!(loaded.tables[0].cell(1, 1), Some("Alice"));
+    }
+
+    #[tokio::test]
+    async fn load_reads_json_project_file() {
+        use crate::components::project_serialization::json_project_serialization::serialize_to_json;
+
+        let tmp = tempfile::tempdir().unwrap();
+        write_employees(tmp.path()).await;
+        let json = serialize_to_json(&employee_project("name")).unwrap();
+        tokio::fs::write(tmp.path().join("dbloada.json"), json).await.unwrap();
+
+        let loaded = disk_loader().load(tmp.path(), &LoadOptions::default()).await.unwrap();
+        assert_eq!(loaded.project, employee_project("name"));
+        assert_eq!(loaded.tables[0].cell(1, 1), Some("Alice"));
+    }
+
+    #[tokio::test]
+    async fn read_project_does_not_read_sources() {
+        let tmp = tempfile::tempdir().unwrap();
+        save_project(tmp.path(), &employee_project("name")).await;
+
+        // data/employees.csv was never written
+        let project = disk_loader().read_project(tmp.path()).await.unwrap();
+        assert_eq!(project, employee_project("name"));
+
+        tokio::fs::write(tmp.path().join(DBLOADA_PROJECT_FILENAME), "kind: [").await.unwrap();
+        assert!(matches!(disk_loader().read_project(tmp.path()).await, Err(LoadError::IOError(_))));
+    }
+
+    #[tokio::test]
+    async fn load_rejects_self_reference_onto_same_column() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_employees(tmp.path()).await;
+        save_project(tmp.path(), &employee_project("manager")).await;
+
+        let err = disk_loader().load(tmp.path(), &LoadOptions::default()).await.unwrap_err();
+        assert!(matches!(err, LoadError::AmbiguousSelfReference { ref relationship, .. } if relationship == "reports_to"));
+    }
+
+    struct DelayedReader {
+        table_name: &'static str,
+        delay: std::time::Duration,
+    }
+
+    #[async_trait]
+    impl TableReader for DelayedReader {
+        fn name(&self) -> &str {
+            "delayed"
+        }
+
+        fn can_read(&self, table: &TableSpec) -> bool {
+            table.name == self.table_name
+        }
+
+        async fn read_table(&self, table: &TableSpec, _project_dir: &Path) -> Result<Table, crate::traits::TableReaderError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(Table::new(table.name.clone(), vec![], vec![], vec![]))
+        }
+    }
+
+    #[tokio::test]
+    async fn load_reports_per_table_timings() {
+        use crate::components::file_system::DiskFileSystem;
+        use crate::components::project_io::YamlProjectIO;
+        use crate::components::project_serialization::YamlProjectSerialization;
+        use crate::components::test_helpers::TestLogger;
+        use crate::models::ProjectSpec;
+        use std::time::Duration;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let project = Project {
+            name: "test".to_string(),
+            api_version: "project.dbloada.io/v1".to_string(),
+            spec: ProjectSpec {
+                tables: vec![table_referencing("fast", &[]), table_referencing("slow", &[])],
+                includes: vec![],
+                defaults: Default::default(),
+            },
+        };
+        save_project(tmp.path(), &project).await;
+
+        let loader = LoadImpl::new(
+            Box::new(TestLogger),
+            Box::new(YamlProjectIO::new(
+                Box::new(TestLogger),
+                Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+                Box::new(YamlProjectSerialization::new(Box::new(TestLogger))),
+            )),
+            vec![
+                Box::new(DelayedReader { table_name: "fast", delay: Duration::from_millis(5) }),
+                Box::new(DelayedReader { table_name: "slow", delay: Duration::from_millis(80) }),
+            ],
+        );
+
+        let loaded = loader.load(tmp.path(), &LoadOptions::default()).await.unwrap();
+        let slowest_first: Vec<&str> = loaded
+            .report
+            .timings_slowest_first()
+            .iter()
+            .map(|t| t.table_name.as_str())
+            .collect();
+        assert_eq!(slowest_first, vec!["slow", "fast"]);
+        assert!(loaded.report.table_timings[1].duration >= Duration::from_millis(80));
+    }
+
+    #[tokio::test]
+    async fn stream_table_sums_int_column_row_by_row() {
+        use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let mut csv = String::from("Name,Amount\n");
+        for i in 1..=10_000 {
+            csv.push_str(&format!("row{i},{i}\n"));
+        }
+        tokio::fs::write(tmp.path().join("amounts.csv"), csv).await.unwrap();
+
+        let mut table = table_referencing("amount", &[]);
+        if let crate::models::SourceSpec::File(fs) = &mut table.source {
+            fs.filename = "amounts.csv".to_string();
+        }
+        table.columns = vec![ColumnSpec {
+            name: "amount".to_string(),
+            description: String::new(),
+            column_identifier: ColumnIdentifier::Name("Amount".to_string()),
+            column_type: ColumnType::Int64,
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
+        }];
+        let project = Project {
+            name: "test".to_string(),
+            api_version: "project.dbloada.io/v1".to_string(),
+            spec: crate::models::ProjectSpec { tables: vec![table], includes: vec![], defaults: Default::default() },
+        };
+        save_project(tmp.path(), &project).await;
+
+        let mut sum: i64 = 0;
+        let mut widest_row = 0;
+        let row_count = disk_loader()
+            .stream_table(tmp.path(), "amount", &mut |row| {
+                widest_row = widest_row.max(row.len());
+                sum += row[0].parse::<i64>().unwrap();
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(row_count, 10_000);
+        assert_eq!(sum, 10_000 * 10_001 / 2);
+        assert_eq!(widest_row, 1);
+    }
+
+    #[tokio::test]
+    async fn stream_table_rejects_unknown_table() {
+        let tmp = tempfile::tempdir().unwrap();
+        save_project(tmp.path(), &employee_project("name")).await;
+
+        let err = disk_loader().stream_table(tmp.path(), "missing", &mut |_| {}).await.unwrap_err();
+        assert!(matches!(err, LoadError::UnknownTable(ref name) if name == "missing"));
+    }
+
+    #[tokio::test]
+    async fn load_dumps_resolved_spec_before_reading_tables() {
+        use crate::components::project_serialization::yaml_project_serialization::deserialize_from_yaml;
+
+        let tmp = tempfile::tempdir().unwrap();
+        // stdout and args are omitted and must come out defaulted; the data
+        // file is missing so the dump has to happen before any table is read
+        let yaml = r#"
+apiVersion: project.dbloada.io/v1
+kind: DBLoadaProject
+metadata:
+  name: test
+spec:
+  tables:
+    - name: city
+      description: Cities
+      hasHeader: true
+      source:
+        type: file
+        filename: data/missing.csv
+        characterEncoding: utf-8
+      columns:
+        - name: name
+          description: City name
+          columnIdentifier: "Name"
+          type: string
+    - name: employee
+      description: Employees
+      hasHeader: true
+      source:
+        type: cmd
+        command: sh
+        characterEncoding: utf-8
+      columns:
+        - name: name
+          description: Employee name
+          columnIdentifier: "Name"
+          type: string
+"#;
+        tokio::fs::write(tmp.path().join(DBLOADA_PROJECT_FILENAME), yaml).await.unwrap();
+
+        let dump_path = tmp.path().join("spec.out.yaml");
+        let options = LoadOptions { dump_spec: Some(dump_path.clone()), ..Default::default() };
+        let err = disk_loader().load(tmp.path(), &options).await.unwrap_err();
+        assert!(matches!(err, LoadError::MissingSourceFiles(_)));
+
+        let dumped = tokio::fs::read_to_string(&dump_path).await.unwrap();
+        assert!(dumped.contains("stdout: true"), "dumped spec was:\n{dumped}");
+        assert!(dumped.contains("args: []"), "dumped spec was:\n{dumped}");
+        assert_eq!(deserialize_from_yaml(&dumped).unwrap(), deserialize_from_yaml(yaml).unwrap());
+    }
+
+    fn office_project() -> Project {
+        use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType, ProjectSpec, RelationshipSpec};
+
+        let column = |name: &str| ColumnSpec {
+            name: name.to_string(),
+            description: String::new(),
+            column_identifier: ColumnIdentifier::Name(name.to_string()),
+            column_type: ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
+        };
+        let mut city = table_referencing("city", &[]);
+        city.columns = vec![column("name")];
+        let mut office = table_referencing("office", &[]);
+        office.columns = vec![column("name"), column("city")];
+        office.relationships = vec![RelationshipSpec {
+            name: "located_in".to_string(),
+            description: String::new(),
+            source_column: "city".to_string(),
+            target_table: "city".to_string(),
+            target_column: "name".to_string(),
+            on_delete: Default::default(),
+        }];
+        Project {
+            name: "test".to_string(),
+            api_version: "project.dbloada.io/v1".to_string(),
+            spec: ProjectSpec { tables: vec![office, city], includes: vec![], defaults: Default::default() },
+        }
+    }
+
+    async fn write_offices(dir: &Path) {
+        let data_dir = dir.join("data");
+        tokio::fs::create_dir_all(&data_dir).await.unwrap();
+        tokio::fs::write(data_dir.join("city.csv"), "name\nLondon\n").await.unwrap();
+        tokio::fs::write(data_dir.join("office.csv"), "name,city\nStar Tower,London\nMercator II,Berlin\nAnnex,\n")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn column_mappings_json_resolves_reordered_columns() {
+        use crate::models::{ColumnSpec, ColumnType, mappings_to_json};
+
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        let mut project = office_project();
+        let office = &mut project.spec.tables[0];
+        office.columns.reverse();
+        office.columns.push(ColumnSpec {
+            name: "first".to_string(),
+            description: String::new(),
+            column_identifier: ColumnIdentifier::Index(0),
+            column_type: ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
+        });
+        save_project(tmp.path(), &project).await;
+
+        let mappings = disk_loader().column_mappings(tmp.path()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_str(&mappings_to_json(&mappings)).unwrap();
+        let office = json["office"].as_array().unwrap();
+        assert_eq!(office[0]["output_column"], "city");
+        assert_eq!(office[0]["identifier"], serde_json::json!({ "kind": "name", "value": "city" }));
+        assert_eq!(office[0]["resolved_index"], 1);
+        assert_eq!(office[1]["output_column"], "name");
+        assert_eq!(office[1]["resolved_index"], 0);
+        assert_eq!(office[2]["identifier"], serde_json::json!({ "kind": "index", "value": 0 }));
+        assert_eq!(office[2]["resolved_index"], 0);
+        assert_eq!(json["city"][0]["resolved_index"], 0);
+    }
+
     #[test]
-    fn project_file_path_appends_filename() {
-        let path = project_file_path(Path::new("/some/dir"));
-        assert_eq!(path, PathBuf::from("/some/dir/dbloada.yaml"));
+    fn spec_column_index_needs_no_header_for_index_columns() {
+        let mut table = table_referencing("t", &[]);
+        let col = |name: &str, id: ColumnIdentifier| ColumnSpec {
+            name: name.to_string(),
+            description: String::new(),
+            column_identifier: id,
+            column_type: crate::models::ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
+        };
+        table.columns = vec![
+            col("a", ColumnIdentifier::Index(3)),
+            col("b", ColumnIdentifier::Name("B".to_string())),
+            col("c", ColumnIdentifier::Capture { source: "a".to_string(), pattern: "x".to_string(), group: 1 }),
+        ];
+        let indices: Vec<_> = table.columns.iter().map(|c| spec_column_index(&table, c)).collect();
+        assert_eq!(indices, vec![Some(3), None, Some(3)]);
+    }
+
+    #[tokio::test]
+    async fn load_with_mock_readers_orders_and_checks_tables() {
+        use crate::components::test_helpers::{InMemoryProjectIO, MockTableReader, MockTableReaderBuilder, TestLogger};
+        use crate::models::ColumnType;
+
+        let readers = MockTableReaderBuilder::default()
+            .table("office", &["name", "city"], &[&["Star Tower", "London"], &["Annex", ""]])
+            .reader(MockTableReader::new(
+                |spec| spec.name.starts_with("ci"),
+                Table::new(
+                    "city".to_string(),
+                    vec!["name".to_string()],
+                    vec![ColumnType::String { max_length: None }],
+                    vec![vec!["London".to_string()]],
+                ),
+            ))
+            .build();
+        let loader = LoadImpl::new(
+            Box::new(TestLogger),
+            Box::new(InMemoryProjectIO::with_project(office_project())),
+            readers,
+        );
+
+        // only the existence of the directory and its project file is checked on disk; the
+        // project itself comes from the in-memory project IO and no data file exists
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(project_file_path(tmp.path()).await, "").await.unwrap();
+        let options = LoadOptions { check_relationships: true, ..Default::default() };
+        let loaded = loader.load(tmp.path(), &options).await.unwrap();
+
+        let names: Vec<&str> = loaded.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["office", "city"]);
+        assert_eq!(loaded.tables[0].cell(0, 1), Some("London"));
+        assert_eq!(loaded.report.table_timings.len(), 2);
+        assert_eq!(loaded.report.table_blanks[0].blank_rows, 1);
+    }
+
+    #[tokio::test]
+    async fn load_does_not_check_integrity_by_default() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+
+        let loaded = disk_loader().load(tmp.path(), &LoadOptions::default()).await.unwrap();
+        assert_eq!(loaded.tables.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn load_with_check_relationships_reports_every_dangling_reference() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+
+        let options = LoadOptions { check_relationships: true, ..Default::default() };
+        let err = disk_loader().load(tmp.path(), &options).await.unwrap_err();
+        match err {
+            LoadError::DanglingReferences(dangling) => assert_eq!(dangling, vec![DanglingReference {
+                table: "office".to_string(),
+                relationship: "located_in".to_string(),
+                row: 1,
+                value: "Berlin".to_string(),
+            }]),
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn load_with_check_relationships_reports_duplicates_in_unique_columns() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        tokio::fs::write(tmp.path().join("data/office.csv"), "name,city\nStar Tower,London\nAnnex,\nDepot,London\nShed,\n")
+            .await
+            .unwrap();
+        let mut project = office_project();
+        project.spec.tables[0].columns[1].unique = true;
+        save_project(tmp.path(), &project).await;
+
+        let options = LoadOptions { check_relationships: true, ..Default::default() };
+        let err = disk_loader().load(tmp.path(), &options).await.unwrap_err();
+        match err {
+            LoadError::DuplicateValues(duplicates) => assert_eq!(duplicates, vec![DuplicateValue {
+                table: "office".to_string(),
+                column: "city".to_string(),
+                value: "London".to_string(),
+                rows: vec![0, 2],
+            }]),
+            other => panic!("unexpected error: {other}"),
+        }
+        assert!(disk_loader().load(tmp.path(), &LoadOptions::default()).await.is_ok());
+    }
+
+    #[test]
+    fn find_duplicate_values_counts_empty_cells_that_are_not_null() {
+        let mut project = office_project();
+        project.spec.tables[0].columns[1].unique = true;
+        project.spec.tables[0].columns[1].empty_is_null = false;
+        let row = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let office = Table::new(
+            "office".to_string(),
+            vec!["name".to_string(), "city".to_string()],
+            vec![crate::models::ColumnType::String { max_length: None }; 2],
+            vec![row(&["Star Tower", ""]), row(&["Annex", "London"]), row(&["Depot", ""])],
+        );
+        let duplicates = find_duplicate_values(&project, &[office]);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!((duplicates[0].value.as_str(), duplicates[0].rows.clone()), ("", vec![0, 2]));
+    }
+
+    #[tokio::test]
+    async fn load_skips_integrity_check_for_skipped_relationship() {
+        use crate::components::test_helpers::RecordingLogger;
+
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+
+        let logger = RecordingLogger::default();
+        let mut loader = disk_loader();
+        loader.logger = Box::new(logger.clone());
+        let options = LoadOptions {
+            skip_relationships: vec!["located_in".to_string(), "no_such_relationship".to_string()],
+            check_relationships: true,
+            ..Default::default()
+        };
+        let loaded = loader.load(tmp.path(), &options).await.unwrap();
+
+        assert_eq!(loaded.tables.len(), 2);
+        assert_eq!(loaded.report.skipped_relationships, vec!["office.located_in"]);
+        let warnings = logger.messages_at("warn");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("no_such_relationship"), "warnings were: {warnings:?}");
+    }
+
+    fn append_file(builder: &mut tar::Builder<impl std::io::Write>, path: &str, content: &str) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content.as_bytes()).unwrap();
+    }
+
+    fn write_bundle(path: &Path, prefix: &str, files: &[(&str, &str)]) {
+        let encoder = flate2::write::GzEncoder::new(std::fs::File::create(path).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for (name, content) in files {
+            append_file(&mut builder, &format!("{prefix}{name}"), content);
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn load_bundle_reads_tables_from_extracted_tree() {
+        use crate::components::project_serialization::yaml_project_serialization::serialize_to_yaml;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let yaml = serialize_to_yaml(&office_project()).unwrap();
+        let files = [
+            (DBLOADA_PROJECT_FILENAME, yaml.as_str()),
+            ("data/city.csv", "name\nLondon\nBerlin\n"),
+            ("data/office.csv", "name,city\nStar Tower,London\nMercator II,Berlin\n"),
+        ];
+        for prefix in ["", "project/"] {
+            let bundle = tmp.path().join("project.tar.gz");
+            write_bundle(&bundle, prefix, &files);
+
+            let loaded = disk_loader().load_bundle(&bundle, &LoadOptions::default()).await.unwrap();
+
+            let names: Vec<&str> = loaded.tables.iter().map(|t| t.name.as_str()).collect();
+            assert_eq!(names, vec!["office", "city"]);
+            assert_eq!(loaded.tables[0].cell(1, 0), Some("Mercator II"));
+            assert_eq!(loaded.tables[0].cell(1, 1), Some("Berlin"));
+        }
+    }
+
+    #[tokio::test]
+    async fn load_bundle_without_project_file_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bundle = tmp.path().join("project.tar.gz");
+        write_bundle(&bundle, "", &[("data/city.csv", "name\nLondon\n")]);
+
+        let err = disk_loader().load_bundle(&bundle, &LoadOptions::default()).await.unwrap_err();
+        assert!(matches!(&err, LoadError::Bundle { message, .. } if message.contains(DBLOADA_PROJECT_FILENAME)));
+
+        let err = disk_loader().load_bundle(&tmp.path().join("missing.tar.gz"), &LoadOptions::default()).await.unwrap_err();
+        assert!(matches!(err, LoadError::Bundle { .. }));
+    }
+
+    #[tokio::test]
+    async fn load_dry_run_resolves_sources_without_rows() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+
+        let options = LoadOptions { dry_run: true, ..Default::default() };
+        let loaded = disk_loader().load(tmp.path(), &options).await.unwrap();
+
+        assert_eq!(loaded.tables.len(), 2);
+        assert!(loaded.tables.iter().all(|t| t.num_rows() == 0));
+        assert_eq!(loaded.tables[0].headers(), ["name", "city"]);
+
+        tokio::fs::remove_file(tmp.path().join("data/office.csv")).await.unwrap();
+        assert!(disk_loader().load(tmp.path(), &options).await.is_err());
+    }
+
+    fn cmd_table(name: &str, stdout: bool, script: &str) -> TableSpec {
+        use crate::models::{CmdSourceSpec, ColumnIdentifier, ColumnSpec, ColumnType};
+
+        TableSpec {
+            name: name.to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Cmd(CmdSourceSpec {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                stdout,
+                character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
+            }),
+            columns: vec![ColumnSpec {
+                name: "name".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("name".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn load_reads_tables_concurrently_and_keeps_load_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut project = office_project();
+        project.spec.tables = vec![
+            cmd_table("slow", false, "sleep 0.5; printf 'name\\nSlow\\n' > $TEMP_CSV_PATH"),
+            cmd_table("fast", false, "printf 'name\\nFast\\n' > $TEMP_CSV_PATH"),
+            cmd_table("piped", true, "sleep 0.2; printf 'name\\nPiped\\n'"),
+        ];
+        save_project(tmp.path(), &project).await;
+
+        let options = LoadOptions { jobs: Some(3), ..Default::default() };
+        let loaded = disk_loader().load(tmp.path(), &options).await.unwrap();
+        let names: Vec<&str> = loaded.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["slow", "fast", "piped"]);
+        // each temp file command wrote its own file
+        let cells: Vec<Option<&str>> = loaded.tables.iter().map(|t| t.cell(0, 0)).collect();
+        assert_eq!(cells, vec![Some("Slow"), Some("Fast"), Some("Piped")]);
+        let timings: Vec<&str> = loaded.report.table_timings.iter().map(|t| t.table_name.as_str()).collect();
+        assert_eq!(timings, names);
+    }
+
+    #[tokio::test]
+    async fn load_returns_tables_in_spec_order_when_relationships_reorder_the_reads() {
+        let tmp = tempfile::tempdir().unwrap();
+        // office references city, so city is read first
+        save_project(tmp.path(), &office_project()).await;
+        write_offices(tmp.path()).await;
+
+        let loaded = disk_loader().load(tmp.path(), &LoadOptions::default()).await.unwrap();
+        let names: Vec<&str> = loaded.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["office", "city"]);
+        let timings: Vec<&str> = loaded.report.table_timings.iter().map(|t| t.table_name.as_str()).collect();
+        assert_eq!(timings, names);
+        let blanks: Vec<&str> = loaded.report.table_blanks.iter().map(|t| t.table_name.as_str()).collect();
+        assert_eq!(blanks, names);
+    }
+
+    #[tokio::test]
+    async fn load_cancels_remaining_reads_after_the_first_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut project = office_project();
+        project.spec.tables = vec![
+            cmd_table("slow", true, "sleep 1; touch finished; printf 'name\\n'"),
+            cmd_table("broken", true, "exit 3"),
+        ];
+        save_project(tmp.path(), &project).await;
+
+        let started = Instant::now();
+        let options = LoadOptions { jobs: Some(2), ..Default::default() };
+        let err = disk_loader().load(tmp.path(), &options).await.unwrap_err();
+        assert!(err.to_string().contains("'broken'"), "error was: {err}");
+        assert!(started.elapsed() < Duration::from_secs(1), "load waited for the slow table");
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!tmp.path().join("finished").exists());
+    }
+
+    #[tokio::test]
+    async fn load_without_side_effects_rejects_temp_file_command_before_running_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        let mut project = office_project();
+        project.spec.tables.push(cmd_table("piped", true, "printf 'name\\nPiped\\n'"));
+        project.spec.tables.push(cmd_table("temp", false, "touch ran; printf 'name\\n' > \"$1\"; # $TEMP_CSV_PATH"));
+        save_project(tmp.path(), &project).await;
+
+        let options = LoadOptions { no_side_effects: true, ..Default::default() };
+        let err = disk_loader().load(tmp.path(), &options).await.unwrap_err();
+        assert!(
+            matches!(&err, LoadError::SideEffects(effects) if effects.len() == 1 && effects[0].contains("'temp'")),
+            "error was: {err}"
+        );
+        assert!(!tmp.path().join("ran").exists());
+
+        let options = LoadOptions {
+            no_side_effects: true,
+            tables: vec!["city".to_string(), "piped".to_string()],
+            ..Default::default()
+        };
+        let loaded = disk_loader().load(tmp.path(), &options).await.unwrap();
+        assert_eq!(loaded.tables.len(), 2);
+        assert_eq!(loaded.tables[1].cell(0, 0), Some("Piped"));
     }
 
     #[test]
-    fn project_file_path_with_trailing_slash() {
-        let path = project_file_path(Path::new("/some/dir/"));
+    fn side_effects_lists_spec_dump() {
+        let options = LoadOptions { dump_spec: Some(PathBuf::from("spec.yaml")), ..Default::default() };
+        let effects = side_effects(&office_project(), &options);
+        assert_eq!(effects, vec!["the resolved spec would be written to spec.yaml"]);
+    }
+
+    #[tokio::test]
+    async fn load_reads_only_selected_tables() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+
+        let options = LoadOptions { tables: vec!["city".to_string()], ..Default::default() };
+        let loaded = disk_loader().load(tmp.path(), &options).await.unwrap();
+
+        assert_eq!(loaded.tables.len(), 1);
+        assert_eq!(loaded.tables[0].name, "city");
+        assert_eq!(loaded.project.spec.tables.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn load_reads_stdin_table_from_standard_input() {
+        use crate::components::csv_parser::CsvParserImpl;
+        use crate::components::table_reader::StdinCsvTableReader;
+        use crate::components::test_helpers::TestLogger;
+
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+        let stdin = StdinCsvTableReader::with_input(
+            Box::new(TestLogger),
+            Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+            Box::new(std::io::Cursor::new("name
+London
+Berlin
+")),
+        );
+
+        let options = LoadOptions { stdin_table: Some("city".to_string()), ..Default::default() };
+        let loaded = disk_loader_with(vec![Box::new(stdin)]).load(tmp.path(), &options).await.unwrap();
+
+        let city = loaded.tables.iter().find(|t| t.name == "city").unwrap();
+        assert_eq!(city.num_rows(), 2);
+        assert!(matches!(loaded.project.spec.tables[1].source, SourceSpec::Stdin(_)));
+    }
+
+    #[test]
+    fn load_rejects_two_tables_reading_stdin() {
+        let mut project = office_project();
+        bind_stdin(&mut project, "city").unwrap();
+        bind_stdin(&mut project, "office").unwrap();
+        assert_eq!(stdin_tables(&project, &LoadOptions::default()), vec!["office", "city"]);
+        let only_city = LoadOptions { tables: vec!["city".to_string()], ..Default::default() };
+        assert_eq!(stdin_tables(&project, &only_city), vec!["city"]);
+    }
+
+    #[tokio::test]
+    async fn load_limit_caps_rows_of_every_table() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+
+        // Mercator II references Berlin, which is not a city; limited loads skip the integrity check
+        let options = LoadOptions { limit: Some(2), check_relationships: true, ..Default::default() };
+        let loaded = disk_loader().load(tmp.path(), &options).await.unwrap();
+
+        let office = loaded.tables.iter().find(|t| t.name == "office").unwrap();
+        assert_eq!(office.num_rows(), 2);
+        assert_eq!(office.cell(1, 0), Some("Mercator II"));
+        assert!(loaded.project.spec.tables.iter().all(|t| t.source.csv_options().limit == Some(2)));
+    }
+
+    #[tokio::test]
+    async fn load_limit_keeps_a_lower_limit_of_the_source() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        let options = LoadOptions { limit: Some(2), ..Default::default() };
+        for (own, expected) in [(Some(1), 1), (Some(3), 2)] {
+            let mut project = office_project();
+            project.spec.tables[0].source.csv_options_mut().limit = own;
+            save_project(tmp.path(), &project).await;
+
+            let loaded = disk_loader().load(tmp.path(), &options).await.unwrap();
+            let office = loaded.tables.iter().find(|t| t.name == "office").unwrap();
+            assert_eq!(office.num_rows(), expected, "own limit {own:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn load_partial_returns_tables_read_before_failure() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        // the city header is missing, so reading office fails after city was read
+        tokio::fs::write(tmp.path().join("data/office.csv"), "name\nStar Tower\n").await.unwrap();
+        save_project(tmp.path(), &office_project()).await;
+
+        let options = LoadOptions { partial: true, ..Default::default() };
+        let err = disk_loader().load(tmp.path(), &options).await.unwrap_err();
+        match err {
+            LoadError::Partial { tables, error } => {
+                assert_eq!(tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["city"]);
+                assert_eq!(tables[0].cell(0, 0), Some("London"));
+                assert!(matches!(*error, LoadError::TableReaderError(_)), "error was: {error}");
+            }
+            other => panic!("expected Partial, got {other}"),
+        }
+
+        let err = disk_loader().load(tmp.path(), &LoadOptions::default()).await.unwrap_err();
+        assert!(matches!(err, LoadError::TableReaderError(_)));
+    }
+
+    #[tokio::test]
+    async fn load_rejects_unknown_selected_table() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_offices(tmp.path()).await;
+        save_project(tmp.path(), &office_project()).await;
+
+        let options = LoadOptions { tables: vec!["city".to_string(), "moon".to_string()], ..Default::default() };
+        let err = disk_loader().load(tmp.path(), &options).await.unwrap_err();
+        assert!(matches!(err, LoadError::UnknownTable(ref name) if name == "moon"));
+    }
+
+    #[test]
+    fn dependency_order_ignores_skipped_relationships() {
+        let tables = vec![table_referencing("a", &["b"]), table_referencing("b", &["a"])];
+        assert!(dependency_order(&tables, &[]).is_err());
+        let order = dependency_order(&tables, &["b_to_a".to_string()]).unwrap();
+        assert_eq!(order.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), vec!["b", "a"]);
+    }
+
+    fn table_referencing(name: &str, targets: &[&str]) -> TableSpec {
+        use crate::models::{FileSourceSpec, RelationshipSpec, SourceSpec};
+
+        TableSpec {
+            name: name.to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: format!("data/{name}.csv"),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns: vec![],
+            relationships: targets
+                .iter()
+                .map(|target| RelationshipSpec {
+                    name: format!("{name}_to_{target}"),
+                    description: String::new(),
+                    source_column: format!("{target}_id"),
+                    target_table: target.to_string(),
+                    target_column: "id".to_string(),
+                    on_delete: Default::default(),
+                })
+                .collect(),
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    fn order_names(tables: &[TableSpec]) -> Result<Vec<String>, LoadError> {
+        dependency_order(tables, &[]).map(|order| order.iter().map(|t| t.name.clone()).collect())
+    }
+
+    #[test]
+    fn dependency_order_puts_targets_first() {
+        let tables = vec![
+            table_referencing("employee", &["office"]),
+            table_referencing("office", &["city"]),
+            table_referencing("city", &["country"]),
+            table_referencing("country", &[]),
+        ];
+        assert_eq!(order_names(&tables).unwrap(), vec!["country", "city", "office", "employee"]);
+    }
+
+    #[test]
+    fn dependency_order_keeps_declared_order_for_independent_tables() {
+        let tables = vec![
+            table_referencing("b", &[]),
+            table_referencing("a", &[]),
+            table_referencing("c", &["a"]),
+        ];
+        assert_eq!(order_names(&tables).unwrap(), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn dependency_order_tolerates_self_references() {
+        let tables = vec![table_referencing("employee", &["employee", "office"]), table_referencing("office", &[])];
+        assert_eq!(order_names(&tables).unwrap(), vec!["office", "employee"]);
+    }
+
+    #[test]
+    fn dependency_order_reports_cycle() {
+        let tables = vec![
+            table_referencing("country", &[]),
+            table_referencing("a", &["b"]),
+            table_referencing("b", &["c"]),
+            table_referencing("c", &["a", "country"]),
+        ];
+        let err = order_names(&tables).unwrap_err();
+        assert!(matches!(&err, LoadError::RelationshipCycle { tables } if tables == &["a", "b", "c", "a"]));
+        assert_eq!(err.to_string(), "relationships form a cycle between tables: a -> b -> c -> a");
+    }
+
+    #[tokio::test]
+    async fn project_file_path_appends_filename() {
+        let path = project_file_path(Path::new("/some/dir")).await;
         assert_eq!(path, PathBuf::from("/some/dir/dbloada.yaml"));
     }
 
+    #[tokio::test]
+    async fn project_file_path_with_trailing_slash() {
+        let path = project_file_path(Path::new("/some/dir/")).await;
+        assert_eq!(path, PathBuf::from("/some/dir/dbloada.yaml"));
+    }
+
+    #[tokio::test]
+    async fn project_file_path_uses_json_only_without_yaml() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("dbloada.json"), "{}").await.unwrap();
+        assert_eq!(project_file_path(tmp.path()).await, tmp.path().join("dbloada.json"));
+        tokio::fs::write(tmp.path().join("dbloada.yaml"), "").await.unwrap();
+        assert_eq!(project_file_path(tmp.path()).await, tmp.path().join("dbloada.yaml"));
+    }
+
     #[tokio::test]
     async fn load_returns_error_for_nonexistent_directory() {
         use crate::components::test_helpers::TestLogger;
@@ -105,7 +1534,7 @@ mod tests {
         ));
         let loader = LoadImpl::new(Box::new(TestLogger), project_io, vec![]);
 
-        let result = loader.load(Path::new("/nonexistent/dir")).await;
+        let result = loader.load(Path::new("/nonexistent/dir"), &LoadOptions::default()).await;
         assert!(matches!(result, Err(LoadError::DirectoryNotFound(_))));
     }
 
@@ -134,26 +1563,46 @@ mod tests {
                     name: "city".to_string(),
                     description: String::new(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::File(FileSourceSpec {
                         filename: "data/cities.csv".to_string(),
                         character_encoding: "utf-8".to_string(),
+                        allow_special_files: false,
+                        glob: false,
+                        sheet: None,
+                        csv: Default::default(),
                     }),
                     columns: vec![
                         ColumnSpec {
                             name: "name".to_string(),
                             description: String::new(),
                             column_identifier: ColumnIdentifier::Name("Name".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                         ColumnSpec {
                             name: "country".to_string(),
                             description: String::new(),
                             column_identifier: ColumnIdentifier::Name("Country".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                     ],
                     relationships: vec![],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 }],
+                includes: vec![],
+                defaults: Default::default(),
             },
         };
 
@@ -176,10 +1625,11 @@ mod tests {
                 Box::new(TestLogger),
                 Box::new(DiskFileSystem::new(Box::new(TestLogger))),
                 Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+                false,
             ))],
         );
 
-        let loaded = loader.load(tmp.path()).await.unwrap();
+        let loaded = loader.load(tmp.path(), &LoadOptions::default()).await.unwrap();
         assert_eq!(loaded.project.name, "test");
         assert_eq!(loaded.tables.len(), 1);
         assert_eq!(loaded.tables[0].name, "city");
@@ -187,7 +1637,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn load_returns_table_reader_error_when_table_source_is_missing() {
+    async fn load_reports_missing_table_source_before_reading() {
         use crate::components::csv_parser::CsvParserImpl;
         use crate::components::file_system::DiskFileSystem;
         use crate::components::project_io::YamlProjectIO;
@@ -207,20 +1657,35 @@ mod tests {
                     name: "city".to_string(),
                     description: String::new(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::File(FileSourceSpec {
                         filename: "data/missing.csv".to_string(),
                         character_encoding: "utf-8".to_string(),
+                        allow_special_files: false,
+                        glob: false,
+                        sheet: None,
+                        csv: Default::default(),
                     }),
                     columns: vec![
                         ColumnSpec {
                             name: "name".to_string(),
                             description: String::new(),
                             column_identifier: ColumnIdentifier::Name("Name".to_string()),
-                            column_type: ColumnType::String,
+                            column_type: ColumnType::String { max_length: None },
+                            empty_is_null: true,
+                            primary_key: false,
+                            unique: false,
+                            ignore_header_case: false,
+                            aliases: vec![],
                         },
                     ],
                     relationships: vec![],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 }],
+                includes: vec![],
+                defaults: Default::default(),
             },
         };
 
@@ -243,10 +1708,31 @@ mod tests {
                 Box::new(TestLogger),
                 Box::new(DiskFileSystem::new(Box::new(TestLogger))),
                 Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+                false,
             ))],
         );
 
-        let err = loader.load(tmp.path()).await.unwrap_err();
-        assert!(matches!(err, LoadError::TableReaderError(_)));
+        let err = loader.load(tmp.path(), &LoadOptions::default()).await.unwrap_err();
+        assert!(matches!(err, LoadError::MissingSourceFiles(ref missing) if missing.len() == 1));
+    }
+
+    #[tokio::test]
+    async fn load_lists_every_missing_source_file_before_running_commands() {
+        let tmp = tempfile::tempdir().unwrap();
+        let marker = tmp.path().join("ran");
+        let mut project = office_project();
+        project.spec.tables.push(cmd_table("employee", true, &format!("touch {}; echo name", marker.display())));
+        save_project(tmp.path(), &project).await;
+
+        let err = disk_loader().load(tmp.path(), &LoadOptions::default()).await.unwrap_err();
+        let LoadError::MissingSourceFiles(missing) = &err else { panic!("unexpected error: {err}") };
+        let tables: Vec<&str> = missing.iter().map(|m| m.split(':').next().unwrap()).collect();
+        assert_eq!(tables, ["table 'office'", "table 'city'"]);
+        assert!(err.to_string().starts_with("2 source file(s) not found: table 'office': "), "error was: {err}");
+        assert!(!marker.exists());
+
+        let options = LoadOptions { tables: vec!["employee".to_string()], ..Default::default() };
+        disk_loader().load(tmp.path(), &options).await.unwrap();
+        assert!(marker.exists());
     }
 }
@@ -1,2 +1,3 @@
 mod load_impl;
-pub use load_impl::LoadImpl;
+mod bundle;
+pub use load_impl::{LoadImpl, dependency_order, project_file_path};
@@ -1,3 +1,3 @@
 pub mod tokio_logger;
 
-pub use tokio_logger::TokioLogger;
+pub use tokio_logger::{LogLevel, TokioLogger, level_from_flags};
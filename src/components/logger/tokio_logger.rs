@@ -1,9 +1,10 @@
+use std::time::SystemTime;
 use async_trait::async_trait;
 use tokio::io::{self, AsyncWriteExt};
 use crate::traits::Logger;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-enum LogLevel {
+pub enum LogLevel {
     Error = 0,
     Warn = 1,
     Info = 2,
@@ -22,8 +23,42 @@ fn parse_log_level(s: &str) -> LogLevel {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `<timestamp> [LEVEL] message`
+    Text,
+    /// One JSON object per line with `level`, `message` and `timestamp`.
+    Json,
+}
+
+fn parse_log_format(s: &str) -> LogFormat {
+    match s.to_lowercase().as_str() {
+        "json" => LogFormat::Json,
+        _ => LogFormat::Text,
+    }
+}
+
+/// The level picked by `-v`/`-q` style flags, or `None` to fall back to `RUST_LOG`.
+pub fn level_from_flags(verbose: u8, quiet: bool) -> Option<LogLevel> {
+    match (quiet, verbose) {
+        (true, _) => Some(LogLevel::Error),
+        (false, 0) => None,
+        (false, 1) => Some(LogLevel::Debug),
+        (false, _) => Some(LogLevel::Trace),
+    }
+}
+
+/// Writes log lines to stderr so they never mix with command output on stdout.
 pub struct TokioLogger {
     level: LogLevel,
+    timestamps: bool,
+    format: LogFormat,
+}
+
+impl Default for TokioLogger {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TokioLogger {
@@ -31,13 +66,57 @@ impl TokioLogger {
         let level = std::env::var("RUST_LOG")
             .map(|s| parse_log_level(&s))
             .unwrap_or(LogLevel::Info);
-        TokioLogger { level }
+        Self::with_level(level)
+    }
+
+    /// Logs at `level`; the format still comes from `DBLOADA_LOG_FORMAT`.
+    pub fn with_level(level: LogLevel) -> Self {
+        let format = std::env::var("DBLOADA_LOG_FORMAT")
+            .map(|s| parse_log_format(&s))
+            .unwrap_or(LogFormat::Text);
+        TokioLogger { level, timestamps: true, format }
+    }
+
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    pub fn format(&self) -> LogFormat {
+        self.format
+    }
+
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Leave out the timestamp prefix, e.g. for output compared in tests.
+    pub fn with_timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    fn format_line(&self, label: &str, msg: &str, now: SystemTime) -> String {
+        match (self.format, self.timestamps) {
+            (LogFormat::Text, true) => format!("{} [{label}] {msg}\n", humantime::format_rfc3339_seconds(now)),
+            (LogFormat::Text, false) => format!("[{label}] {msg}\n"),
+            (LogFormat::Json, _) => {
+                let mut line = serde_json::json!({
+                    "level": label.to_lowercase(),
+                    "message": msg,
+                });
+                if self.timestamps {
+                    line["timestamp"] = humantime::format_rfc3339_seconds(now).to_string().into();
+                }
+                format!("{line}\n")
+            }
+        }
     }
 
     async fn log(&self, level: LogLevel, label: &str, msg: &str) {
         if level <= self.level {
-            let line = format!("[{label}] {msg}\n");
-            let _ = io::stdout().write_all(line.as_bytes()).await;
+            let line = self.format_line(label, msg, SystemTime::now());
+            let _ = io::stderr().write_all(line.as_bytes()).await;
         }
     }
 }
@@ -64,3 +143,39 @@ impl Logger for TokioLogger {
         self.log(LogLevel::Trace, "TRACE", msg).await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_line_prefixes_rfc3339_timestamp() {
+        let at = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_704_164_645);
+        let logger = TokioLogger::with_level(LogLevel::Info).with_format(LogFormat::Text);
+        assert_eq!(logger.format_line("INFO", "loaded", at), "2024-01-02T03:04:05Z [INFO] loaded\n");
+        let logger = logger.with_timestamps(false);
+        assert_eq!(logger.format_line("INFO", "loaded", at), "[INFO] loaded\n");
+    }
+
+    #[test]
+    fn format_line_json_parses_back() {
+        let at = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_704_164_645);
+        let logger = TokioLogger::with_level(LogLevel::Info).with_format(LogFormat::Json);
+        let line = logger.format_line("WARN", "table 'city': \"quoted\"\nsecond line", at);
+        assert_eq!(line.matches('\n').count(), 1);
+
+        let parsed: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(parsed["level"], "warn");
+        assert_eq!(parsed["message"], "table 'city': \"quoted\"\nsecond line");
+        assert_eq!(parsed["timestamp"], "2024-01-02T03:04:05Z");
+    }
+
+    #[test]
+    fn level_from_flags_maps_verbosity() {
+        assert_eq!(level_from_flags(0, false), None);
+        assert_eq!(level_from_flags(1, false), Some(LogLevel::Debug));
+        assert_eq!(level_from_flags(2, false), Some(LogLevel::Trace));
+        assert_eq!(level_from_flags(5, false), Some(LogLevel::Trace));
+        assert_eq!(level_from_flags(0, true), Some(LogLevel::Error));
+    }
+}
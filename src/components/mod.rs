@@ -7,6 +7,11 @@ pub mod project_io;
 pub mod load;
 pub mod csv_parser;
 pub mod table_reader;
+pub mod ddl;
+pub mod freeze;
+pub mod table_writer;
+pub mod validate;
+pub mod fmt;
 
 #[cfg(test)]
 pub mod test_helpers;
@@ -0,0 +1,84 @@
+use std::path::Path;
+use async_trait::async_trait;
+use crate::models::{Project, ProjectFormat};
+use crate::traits::{ProjectIO, ProjectIOError};
+
+/// Loads and saves each project file with the project IO for its format.
+pub struct FormatProjectIO {
+    yaml: Box<dyn ProjectIO>,
+    json: Box<dyn ProjectIO>,
+}
+
+impl FormatProjectIO {
+    pub fn new(yaml: Box<dyn ProjectIO>, json: Box<dyn ProjectIO>) -> Self {
+        FormatProjectIO { yaml, json }
+    }
+
+    fn io_for(&self, path: &Path) -> &dyn ProjectIO {
+        match ProjectFormat::from_path(path) {
+            ProjectFormat::Yaml => self.yaml.as_ref(),
+            ProjectFormat::Json => self.json.as_ref(),
+        }
+    }
+}
+
+#[async_trait]
+impl ProjectIO for FormatProjectIO {
+    async fn load(&self, path: &Path) -> Result<Project, ProjectIOError> {
+        self.io_for(path).load(path).await
+    }
+
+    async fn save(&self, project: &Project, path: &Path) -> Result<(), ProjectIOError> {
+        self.io_for(path).save(project, path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use crate::components::init::init_impl::example_project;
+    use crate::components::project_io::YamlProjectIO;
+    use crate::components::project_serialization::{JsonProjectSerialization, YamlProjectSerialization};
+    use crate::components::test_helpers::{InMemoryFileSystem, TestLogger};
+    use crate::traits::ProjectSerialization;
+
+    fn make_io() -> (FormatProjectIO, Arc<Mutex<HashMap<PathBuf, String>>>) {
+        let store = Arc::new(Mutex::new(HashMap::new()));
+        let io = |serialization: Box<dyn ProjectSerialization>| -> Box<dyn ProjectIO> {
+            let file_system = Box::new(InMemoryFileSystem::new(store.clone()));
+            Box::new(YamlProjectIO::new(Box::new(TestLogger), file_system, serialization))
+        };
+        let format_io = FormatProjectIO::new(
+            io(Box::new(YamlProjectSerialization::new(Box::new(TestLogger)))),
+            io(Box::new(JsonProjectSerialization::new(Box::new(TestLogger)))),
+        );
+        (format_io, store)
+    }
+
+    #[test]
+    fn format_follows_extension() {
+        assert_eq!(ProjectFormat::from_path(Path::new("p/dbloada.json")), ProjectFormat::Json);
+        assert_eq!(ProjectFormat::from_path(Path::new("p/dbloada.JSON")), ProjectFormat::Json);
+        assert_eq!(ProjectFormat::from_path(Path::new("p/dbloada.yaml")), ProjectFormat::Yaml);
+        assert_eq!(ProjectFormat::from_path(Path::new("p/dbloada")), ProjectFormat::Yaml);
+    }
+
+    #[tokio::test]
+    async fn saves_and_loads_each_file_in_its_format() {
+        let (io, store) = make_io();
+        let project = example_project("test");
+        let (yaml_path, json_path) = (PathBuf::from("/p/dbloada.yaml"), PathBuf::from("/p/dbloada.json"));
+        io.save(&project, &yaml_path).await.unwrap();
+        io.save(&project, &json_path).await.unwrap();
+
+        let files = store.lock().await.clone();
+        assert!(files[&yaml_path].starts_with("apiVersion: "), "yaml was:\n{}", files[&yaml_path]);
+        assert!(files[&json_path].starts_with("{\n  \"apiVersion\": "), "json was:\n{}", files[&json_path]);
+        assert_eq!(io.load(&yaml_path).await.unwrap(), project);
+        assert_eq!(io.load(&json_path).await.unwrap(), project);
+    }
+}
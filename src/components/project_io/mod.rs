@@ -1,3 +1,5 @@
 pub mod yaml_project_io;
+pub mod format_project_io;
 
 pub use yaml_project_io::YamlProjectIO;
+pub use format_project_io::FormatProjectIO;
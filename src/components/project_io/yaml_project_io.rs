@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
 use async_trait::async_trait;
 use crate::models::Project;
 use crate::traits::{
@@ -6,10 +7,15 @@ use crate::traits::{
     ProjectSerialization, Logger, FileSystem,
 };
 
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 8;
+pub const DEFAULT_MAX_INCLUDE_FILES: usize = 256;
+
 pub struct YamlProjectIO {
     logger: Box<dyn Logger>,
     file_system: Box<dyn FileSystem>,
     serialization: Box<dyn ProjectSerialization>,
+    max_include_depth: usize,
+    max_include_files: usize,
 }
 
 impl YamlProjectIO {
@@ -22,8 +28,103 @@ impl YamlProjectIO {
             logger,
             file_system,
             serialization,
+            max_include_depth: DEFAULT_MAX_INCLUDE_DEPTH,
+            max_include_files: DEFAULT_MAX_INCLUDE_FILES,
+        }
+    }
+
+    /// Caps how deeply includes may nest; a file included by the project itself is at depth 1.
+    pub fn with_max_include_depth(mut self, max_include_depth: usize) -> Self {
+        self.max_include_depth = max_include_depth;
+        self
+    }
+
+    /// Caps how many distinct files a project may include, counting nested includes.
+    pub fn with_max_include_files(mut self, max_include_files: usize) -> Self {
+        self.max_include_files = max_include_files;
+        self
+    }
+
+    /// Merges the tables of every included file into the project, depth first in the order listed.
+    /// Nested includes are relative to the project directory too. A file reached through several
+    /// includes is read once, where it is first reached.
+    async fn resolve_includes(&self, project: &mut Project, path: &Path) -> Result<(), ProjectIOError> {
+        let project_dir = path.parent().unwrap_or(Path::new(""));
+        let main_file = path.file_name().map(PathBuf::from).unwrap_or_default();
+        let main_chain = vec![(self.file_system.canonicalize(path).await?, main_file.display().to_string())];
+        let mut pending: Vec<(PathBuf, Vec<(PathBuf, String)>)> = project
+            .spec
+            .includes
+            .iter()
+            .rev()
+            .map(|file| (normalize(Path::new(file)), main_chain.clone()))
+            .collect();
+        let mut read: HashSet<PathBuf> = HashSet::new();
+
+        while let Some((file, mut chain)) = pending.pop() {
+            let name = file.display().to_string();
+            let canonical = self.file_system.canonicalize(&project_dir.join(&file)).await?;
+            if chain.iter().any(|(seen, _)| *seen == canonical) {
+                let files: Vec<&str> = chain.iter().map(|(_, name)| name.as_str()).chain([name.as_str()]).collect();
+                return Err(ProjectIOError::IncludeCycle(files.join(" -> ")));
+            }
+            if chain.len() > self.max_include_depth {
+                let files: Vec<&str> = chain.iter().map(|(_, name)| name.as_str()).chain([name.as_str()]).collect();
+                return Err(ProjectIOError::IncludeDepthExceeded {
+                    limit: self.max_include_depth,
+                    chain: files.join(" -> "),
+                });
+            }
+            if !read.insert(canonical.clone()) {
+                self.logger.debug(&format!("already included: {name}")).await;
+                continue;
+            }
+            if read.len() > self.max_include_files {
+                return Err(ProjectIOError::TooManyIncludes(self.max_include_files));
+            }
+
+            self.logger.debug(&format!("loading included file: {name}")).await;
+            let content = self.file_system.load(&canonical).await?;
+            let included = self
+                .serialization
+                .deserialize_include(&content)
+                .await
+                .map_err(|source| ProjectIOError::IncludeError { file: name.clone(), source })?;
+
+            for mut table in included.tables {
+                if let Some(existing) = project.spec.tables.iter().find(|t| t.name == table.name) {
+                    return Err(ProjectIOError::DuplicateTable {
+                        table: table.name,
+                        file: name,
+                        defined_in: existing.included_from.clone().unwrap_or_else(|| main_file.display().to_string()),
+                    });
+                }
+                table.included_from = Some(name.clone());
+                project.spec.tables.push(table);
+            }
+
+            chain.push((canonical, name));
+            for nested in included.includes.iter().rev() {
+                pending.push((normalize(Path::new(nested)), chain.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Drops `.` and folds `..` into the preceding directory so the same file always gets the same path.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
         }
     }
+    normalized
 }
 
 #[async_trait]
@@ -31,7 +132,8 @@ impl ProjectIO for YamlProjectIO {
     async fn load(&self, path: &Path) -> Result<Project, ProjectIOError> {
         self.logger.debug(&format!("loading project from: {}", path.display())).await;
         let content = self.file_system.load(path).await?;
-        let project = self.serialization.deserialize(&content).await?;
+        let mut project = self.serialization.deserialize(&content).await?;
+        self.resolve_includes(&mut project, path).await?;
         self.logger.info(&format!("loaded project '{}' from: {}", project.name, path.display())).await;
         Ok(project)
     }
@@ -68,7 +170,7 @@ mod tests {
         Project {
             name: name.to_string(),
             api_version: PROJECT_API_VERSION.to_string(),
-            spec: ProjectSpec { tables: vec![] },
+            spec: ProjectSpec { tables: vec![], includes: vec![], defaults: Default::default() },
         }
     }
 
@@ -164,4 +266,150 @@ mod tests {
         assert_eq!(loaded_a, project_a);
         assert_eq!(loaded_b, project_b);
     }
+
+    fn tables_yaml(names: &[&str]) -> String {
+        let mut yaml = String::from("defaults:\n  hasHeader: true\ntables:\n");
+        for name in names {
+            yaml.push_str(&format!("  - name: {name}\n    description: ''\n    columns: []\n"));
+            yaml.push_str(&format!("    source:\n      type: file\n      filename: {name}.csv\n"));
+        }
+        yaml
+    }
+
+    fn project_yaml(includes: &[&str], tables: &[&str]) -> String {
+        let mut yaml = String::from("apiVersion: project.dbloada.io/v1\nkind: DBLoadaProject\n");
+        yaml.push_str("metadata:\n  name: test\nspec:\n");
+        yaml.push_str(&format!("  includes: [{}]\n", includes.join(", ")));
+        for line in tables_yaml(tables).lines() {
+            yaml.push_str(&format!("  {line}\n"));
+        }
+        yaml
+    }
+
+    async fn write_files(store: &Arc<Mutex<HashMap<PathBuf, String>>>, files: &[(&str, String)]) {
+        let mut store = store.lock().await;
+        for (path, content) in files {
+            store.insert(PathBuf::from(path), content.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn load_merges_tables_from_included_files() {
+        let (io, store) = make_io();
+        write_files(&store, &[
+            ("/projects/dbloada.yaml", project_yaml(&["tables/geo.yaml", "./tables/staff.yaml"], &["office"])),
+            ("/projects/tables/geo.yaml", tables_yaml(&["country", "city"])),
+            ("/projects/tables/staff.yaml", tables_yaml(&["employee"])),
+        ]).await;
+
+        let project = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap();
+
+        let names: Vec<&str> = project.spec.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["office", "country", "city", "employee"]);
+        let sources: Vec<Option<&str>> = project.spec.tables.iter().map(|t| t.included_from.as_deref()).collect();
+        assert_eq!(sources, [None, Some("tables/geo.yaml"), Some("tables/geo.yaml"), Some("tables/staff.yaml")]);
+        assert!(project.spec.tables.iter().all(|t| t.has_header));
+    }
+
+    #[tokio::test]
+    async fn load_reports_file_that_redefines_a_table() {
+        let (io, store) = make_io();
+        write_files(&store, &[
+            ("/projects/dbloada.yaml", project_yaml(&["geo.yaml", "more.yaml"], &["office"])),
+            ("/projects/geo.yaml", tables_yaml(&["country", "city"])),
+            ("/projects/more.yaml", tables_yaml(&["region", "city"])),
+        ]).await;
+
+        let err = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap_err();
+        assert_eq!(err.to_string(), "table 'city' in included file 'more.yaml' is already defined in 'geo.yaml'");
+
+        write_files(&store, &[("/projects/more.yaml", tables_yaml(&["office"]))]).await;
+        let err = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap_err();
+        assert_eq!(err.to_string(), "table 'office' in included file 'more.yaml' is already defined in 'dbloada.yaml'");
+    }
+
+    #[tokio::test]
+    async fn load_detects_include_cycles() {
+        let (io, store) = make_io();
+        write_files(&store, &[
+            ("/projects/dbloada.yaml", project_yaml(&["a.yaml"], &[])),
+            ("/projects/a.yaml", format!("includes: [b.yaml]\n{}", tables_yaml(&["country"]))),
+            ("/projects/b.yaml", format!("includes: [sub/../a.yaml]\n{}", tables_yaml(&["city"]))),
+        ]).await;
+
+        let err = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap_err();
+        assert_eq!(err.to_string(), "include cycle: dbloada.yaml -> a.yaml -> b.yaml -> a.yaml");
+    }
+
+    #[tokio::test]
+    async fn load_tells_includes_nested_too_deep_apart_from_a_cycle() {
+        let (io, store) = make_io();
+        let mut files = vec![("/projects/dbloada.yaml".to_string(), project_yaml(&["f1.yaml"], &[]))];
+        for i in 1..=DEFAULT_MAX_INCLUDE_DEPTH + 1 {
+            let content = format!("includes: [f{}.yaml]\n{}", i + 1, tables_yaml(&[&format!("t{i}")]));
+            files.push((format!("/projects/f{i}.yaml"), content));
+        }
+        files.push((format!("/projects/f{}.yaml", DEFAULT_MAX_INCLUDE_DEPTH + 2), tables_yaml(&["last"])));
+        let files: Vec<(&str, String)> = files.iter().map(|(path, content)| (path.as_str(), content.clone())).collect();
+        write_files(&store, &files).await;
+
+        let err = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap_err();
+        assert!(
+            matches!(err, ProjectIOError::IncludeDepthExceeded { limit: DEFAULT_MAX_INCLUDE_DEPTH, ref chain }
+                if chain.ends_with("f8.yaml -> f9.yaml")),
+            "unexpected error: {err}"
+        );
+
+        write_files(&store, &[("/projects/f3.yaml", format!("includes: [f1.yaml]\n{}", tables_yaml(&["t3"])))]).await;
+        let err = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap_err();
+        assert!(matches!(err, ProjectIOError::IncludeCycle(_)), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn load_reads_a_file_included_twice_once() {
+        let (io, store) = make_io();
+        write_files(&store, &[
+            ("/projects/dbloada.yaml", project_yaml(&["a.yaml", "b.yaml"], &[])),
+            ("/projects/a.yaml", format!("includes: [shared.yaml]\n{}", tables_yaml(&["country"]))),
+            ("/projects/b.yaml", format!("includes: [./sub/../shared.yaml]\n{}", tables_yaml(&["city"]))),
+            ("/projects/shared.yaml", tables_yaml(&["currency"])),
+        ]).await;
+
+        let project = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap();
+        let names: Vec<&str> = project.spec.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["country", "currency", "city"]);
+    }
+
+    #[tokio::test]
+    async fn load_rejects_more_included_files_than_the_limit() {
+        let (io, store) = make_io();
+        let io = io.with_max_include_files(2);
+        write_files(&store, &[
+            ("/projects/dbloada.yaml", project_yaml(&["a.yaml", "b.yaml"], &[])),
+            ("/projects/a.yaml", format!("includes: [c.yaml]\n{}", tables_yaml(&["country"]))),
+            ("/projects/b.yaml", tables_yaml(&["city"])),
+            ("/projects/c.yaml", tables_yaml(&["currency"])),
+        ]).await;
+
+        let err = io.load(Path::new("/projects/dbloada.yaml")).await.unwrap_err();
+        assert!(matches!(err, ProjectIOError::TooManyIncludes(2)), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn save_keeps_includes_and_leaves_included_tables_out() {
+        let (io, store) = make_io();
+        let path = PathBuf::from("/projects/dbloada.yaml");
+        write_files(&store, &[
+            ("/projects/dbloada.yaml", project_yaml(&["geo.yaml"], &["office"])),
+            ("/projects/geo.yaml", tables_yaml(&["country", "city"])),
+        ]).await;
+
+        let project = io.load(&path).await.unwrap();
+        io.save(&project, &path).await.unwrap();
+
+        let saved = store.lock().await.get(&path).cloned().unwrap();
+        assert!(saved.contains("includes:\n  - geo.yaml"), "saved:\n{saved}");
+        assert!(!saved.contains("country"), "saved:\n{saved}");
+        assert_eq!(io.load(&path).await.unwrap(), project);
+    }
 }
@@ -0,0 +1,144 @@
+use async_trait::async_trait;
+use crate::models::{Project, ProjectSpec};
+use crate::traits::{ProjectSerialization, ProjectSerializationError, Logger};
+use super::yaml_project_serialization::{
+    ProjectSpecYaml, ProjectYaml, include_from_model, project_from_model, project_to_model,
+};
+
+/// Writes the same fields as the YAML format, pretty-printed with a trailing newline.
+pub fn serialize_to_json(project: &Project) -> Result<String, ProjectSerializationError> {
+    serde_json::to_string_pretty(&project_to_model(project))
+        .map(|json| json + "\n")
+        .map_err(|e| ProjectSerializationError::SerializeError(e.to_string()))
+}
+
+pub fn deserialize_from_json(content: &str) -> Result<Project, ProjectSerializationError> {
+    let model: ProjectYaml = serde_json::from_str(content)
+        .map_err(|e| ProjectSerializationError::DeserializeError(e.to_string()))?;
+    project_from_model(model)
+}
+
+/// Parses an included file: `defaults`, `tables` and further `includes`, without the project header.
+pub fn deserialize_include_from_json(content: &str) -> Result<ProjectSpec, ProjectSerializationError> {
+    let model: ProjectSpecYaml =
+        serde_json::from_str(content).map_err(|e| ProjectSerializationError::DeserializeError(e.to_string()))?;
+    include_from_model(model)
+}
+
+pub struct JsonProjectSerialization {
+    logger: Box<dyn Logger>,
+}
+
+impl JsonProjectSerialization {
+    pub fn new(logger: Box<dyn Logger>) -> Self {
+        JsonProjectSerialization { logger }
+    }
+}
+
+#[async_trait]
+impl ProjectSerialization for JsonProjectSerialization {
+    async fn serialize(&self, project: &Project) -> Result<String, ProjectSerializationError> {
+        self.logger.debug(&format!("serializing project as JSON: {}", project.name)).await;
+        let result = serialize_to_json(project)?;
+        self.logger.info(&format!("serialized project: {}", project.name)).await;
+        Ok(result)
+    }
+
+    async fn deserialize(&self, content: &str) -> Result<Project, ProjectSerializationError> {
+        self.logger.debug("deserializing project from JSON").await;
+        let project = deserialize_from_json(content)?;
+        self.logger.info(&format!("deserialized project: {}", project.name)).await;
+        Ok(project)
+    }
+
+    async fn deserialize_include(&self, content: &str) -> Result<ProjectSpec, ProjectSerializationError> {
+        self.logger.debug("deserializing included file from JSON").await;
+        deserialize_include_from_json(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::init::init_impl::example_project;
+    use crate::components::project_serialization::yaml_project_serialization::{deserialize_from_yaml, serialize_to_yaml};
+    use crate::models::{PROJECT_API_VERSION, ProjectSpec, SourceSpec};
+
+    fn empty_spec_project(name: &str) -> Project {
+        Project {
+            name: name.to_string(),
+            api_version: PROJECT_API_VERSION.to_string(),
+            spec: ProjectSpec { tables: vec![], includes: vec![], defaults: Default::default() },
+        }
+    }
+
+    fn json_with(api_version: &str, kind: &str, name: &str) -> String {
+        format!(r#"{{"apiVersion": "{api_version}", "kind": "{kind}", "metadata": {{"name": "{name}"}}}}"#)
+    }
+
+    #[test]
+    fn serialize_to_json_uses_camel_case_fields() {
+        let json = serialize_to_json(&example_project("test")).unwrap();
+        assert!(json.starts_with("{\n  \"apiVersion\": \"project.dbloada.io/v1\""), "json was:\n{json}");
+        assert!(json.contains("\"kind\": \"DBLoadaProject\""), "json was:\n{json}");
+        assert!(json.contains("\"hasHeader\": true"), "json was:\n{json}");
+        assert!(json.contains("\"columnIdentifier\": \"Name\""), "json was:\n{json}");
+        assert!(json.ends_with("}\n"));
+    }
+
+    #[test]
+    fn round_trip_preserves_data() {
+        let project = empty_spec_project("test-project");
+        let json = serialize_to_json(&project).unwrap();
+        assert_eq!(deserialize_from_json(&json).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_example_project_matches_yaml() {
+        let project = example_project("test");
+        let from_json = deserialize_from_json(&serialize_to_json(&project).unwrap()).unwrap();
+        let from_yaml = deserialize_from_yaml(&serialize_to_yaml(&project).unwrap()).unwrap();
+        assert_eq!(from_json, project);
+        assert_eq!(from_json, from_yaml);
+    }
+
+    #[test]
+    fn deserialize_rejects_wrong_kind() {
+        let err = deserialize_from_json(&json_with(PROJECT_API_VERSION, "WrongKind", "test")).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::UnexpectedKind { ref actual, .. } if actual == "WrongKind"));
+    }
+
+    #[test]
+    fn deserialize_rejects_newer_api_version() {
+        let err = deserialize_from_json(&json_with("project.dbloada.io/v2", "DBLoadaProject", "test")).unwrap_err();
+        assert!(matches!(
+            err,
+            ProjectSerializationError::UnsupportedApiVersion { ref expected, ref actual }
+                if expected == PROJECT_API_VERSION && actual == "project.dbloada.io/v2"
+        ));
+    }
+
+    #[test]
+    fn deserialize_rejects_invalid_name_and_invalid_json() {
+        let err = deserialize_from_json(&json_with(PROJECT_API_VERSION, "DBLoadaProject", "MyProject")).unwrap_err();
+        assert!(err.to_string().contains("lowercase"), "error was: {err}");
+        assert!(matches!(deserialize_from_json("{not json"), Err(ProjectSerializationError::DeserializeError(_))));
+        assert!(matches!(deserialize_from_json(""), Err(ProjectSerializationError::DeserializeError(_))));
+    }
+
+    #[test]
+    fn deserialize_handles_missing_spec() {
+        let project = deserialize_from_json(&json_with(PROJECT_API_VERSION, "DBLoadaProject", "test")).unwrap();
+        assert_eq!(project.spec.tables.len(), 0);
+    }
+
+    #[test]
+    fn deserialize_keeps_env_references_in_sources() {
+        let json = serialize_to_json(&example_project("test")).unwrap().replace("data/countries.csv", "${COUNTRIES}");
+        let project = deserialize_from_json(&json).unwrap();
+        match &project.spec.tables[0].source {
+            SourceSpec::File(fs) => assert_eq!(fs.filename, "${COUNTRIES}"),
+            _ => panic!("expected File source"),
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod yaml_project_serialization;
+pub mod json_project_serialization;
 
 pub use yaml_project_serialization::YamlProjectSerialization;
+pub use json_project_serialization::JsonProjectSerialization;
@@ -1,15 +1,17 @@
 use serde::{Deserialize, Serialize};
 use async_trait::async_trait;
 use crate::models::{
-    Project, PROJECT_KIND,
-    ProjectSpec, TableSpec, SourceSpec, FileSourceSpec, CmdSourceSpec,
-    ColumnSpec, ColumnIdentifier, ColumnType, RelationshipSpec,
+    Project, PROJECT_API_VERSION, PROJECT_KIND,
+    ProjectSpec, ProjectDefaults, TableSpec, SourceSpec, FileSourceSpec, CmdSourceSpec, HttpSourceSpec, StdinSourceSpec,
+    ColumnSpec, ColumnIdentifier, ColumnType, RelationshipSpec, OnDelete, RowFilter, CsvOptions, TrimMode, RaggedRowMode,
+    effective_character_encoding, validate_resource_name,
 };
 use crate::traits::{ProjectSerialization, ProjectSerializationError, Logger};
 
+/// The project as written to the file. The JSON format uses the same model.
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ProjectYaml {
+pub(crate) struct ProjectYaml {
     api_version: String,
     kind: String,
     metadata: MetadataYaml,
@@ -22,22 +24,50 @@ struct MetadataYaml {
     name: String,
 }
 
+/// The spec of a project file, and the whole content of an included file.
 #[derive(Serialize, Deserialize)]
-struct ProjectSpecYaml {
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProjectSpecYaml {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    defaults: Option<DefaultsYaml>,
+    /// Read as `defaults.characterEncoding`, where it used to live; written there too.
+    #[serde(default, skip_serializing)]
+    character_encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    includes: Vec<String>,
     #[serde(default)]
     tables: Vec<TableSpecYaml>,
 }
 
+/// Values for tables that leave them out. Written back once; tables only pin values that differ.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct DefaultsYaml {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    character_encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    has_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ignore_header_case: Option<bool>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct TableSpecYaml {
     name: String,
     description: String,
-    has_header: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    has_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    keep_raw_line: bool,
     source: SourceSpecYaml,
     columns: Vec<ColumnSpecYaml>,
     #[serde(default)]
     relationships: Vec<RelationshipSpecYaml>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    filter: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    dedup_by: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -47,19 +77,92 @@ enum SourceSpecYaml {
     File(FileSourceSpecYaml),
     #[serde(rename = "cmd")]
     Cmd(CmdSourceSpecYaml),
+    #[serde(rename = "http")]
+    Http(HttpSourceSpecYaml),
+    #[serde(rename = "stdin")]
+    Stdin(StdinSourceSpecYaml),
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct FileSourceSpecYaml {
     filename: String,
-    character_encoding: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    character_encoding: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    allow_special_files: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    glob: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sheet: Option<String>,
+    #[serde(flatten)]
+    csv: CsvOptionsYaml,
 }
 
 fn default_stdout() -> bool {
     true
 }
 
+fn default_allowed_exit_codes() -> Vec<i32> {
+    vec![0]
+}
+
+fn is_default_allowed_exit_codes(codes: &Vec<i32>) -> bool {
+    *codes == default_allowed_exit_codes()
+}
+
+fn default_empty_is_null() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn is_zero<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// `env` as a map in the file, keeping its variables in the order they are written.
+mod ordered_env {
+    use std::fmt;
+    use serde::de::{Error, MapAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(env: &[(String, String)], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(env.iter().map(|(key, value)| (key, value)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<(String, String)>, D::Error> {
+        struct EnvVisitor;
+
+        impl<'de> Visitor<'de> for EnvVisitor {
+            type Value = Vec<(String, String)>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of environment variables")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut env: Vec<(String, String)> = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, String>()? {
+                    if env.iter().any(|(k, _)| *k == key) {
+                        return Err(A::Error::custom(format!("duplicate env variable '{key}'")));
+                    }
+                    env.push((key, value));
+                }
+                Ok(env)
+            }
+        }
+
+        deserializer.deserialize_map(EnvVisitor)
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CmdSourceSpecYaml {
@@ -68,7 +171,64 @@ struct CmdSourceSpecYaml {
     args: Vec<String>,
     #[serde(default = "default_stdout")]
     stdout: bool,
-    character_encoding: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    character_encoding: Option<String>,
+    #[serde(flatten)]
+    csv: CsvOptionsYaml,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout_seconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "ordered_env")]
+    env: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    capture_stderr: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    retries: u32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    retry_delay_seconds: u64,
+    #[serde(default = "default_allowed_exit_codes", skip_serializing_if = "is_default_allowed_exit_codes")]
+    allowed_exit_codes: Vec<i32>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HttpSourceSpecYaml {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    character_encoding: Option<String>,
+    #[serde(flatten)]
+    csv: CsvOptionsYaml,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout_seconds: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StdinSourceSpecYaml {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    character_encoding: Option<String>,
+    #[serde(flatten)]
+    csv: CsvOptionsYaml,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CsvOptionsYaml {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    delimiter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trim: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    on_ragged_row: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_errors: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    skip_rows: usize,
+    #[serde(default, skip_serializing_if = "is_false")]
+    keep_quotes: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -79,6 +239,16 @@ struct ColumnSpecYaml {
     column_identifier: ColumnIdentifierYaml,
     #[serde(rename = "type")]
     column_type: String,
+    #[serde(default = "default_empty_is_null", skip_serializing_if = "is_true")]
+    empty_is_null: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    primary_key: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    unique: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ignore_header_case: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -86,6 +256,19 @@ struct ColumnSpecYaml {
 enum ColumnIdentifierYaml {
     Index(u64),
     Name(String),
+    Capture { capture: CaptureYaml },
+}
+
+#[derive(Serialize, Deserialize)]
+struct CaptureYaml {
+    source: String,
+    pattern: String,
+    #[serde(default = "default_capture_group")]
+    group: usize,
+}
+
+fn default_capture_group() -> usize {
+    1
 }
 
 #[derive(Serialize, Deserialize)]
@@ -96,63 +279,196 @@ struct RelationshipSpecYaml {
     source_column: String,
     target_table: String,
     target_column: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    on_delete: Option<String>,
 }
 
 pub fn parse_column_type(s: &str) -> Result<ColumnType, String> {
     let trimmed = s.trim();
-    if trimmed == "string" {
-        return Ok(ColumnType::String);
+    match trimmed {
+        "string" => return Ok(ColumnType::String { max_length: None }),
+        "int64" => return Ok(ColumnType::Int64),
+        "bool" => return Ok(ColumnType::Bool),
+        _ => {}
+    }
+    if let Some(len) = trimmed
+        .strip_prefix("string(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let max_length = len
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("invalid string length in column type: '{trimmed}'"))?;
+        return Ok(ColumnType::String { max_length: Some(max_length) });
     }
     Err(format!("unknown column type: '{trimmed}'"))
 }
 
 pub fn column_type_to_string(ct: &ColumnType) -> String {
     match ct {
-        ColumnType::String => "string".to_string(),
+        ColumnType::String { max_length: None } => "string".to_string(),
+        ColumnType::String { max_length: Some(n) } => format!("string({n})"),
+        ColumnType::Int64 => "int64".to_string(),
+        ColumnType::Bool => "bool".to_string(),
+    }
+}
+
+pub fn parse_delimiter(s: &str) -> Result<char, String> {
+    parse_ascii_char("delimiter", s)
+}
+
+fn parse_ascii_char(field: &str, s: &str) -> Result<char, String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii() => Ok(c),
+        _ => Err(format!("{field} must be a single ASCII character, got '{s}'")),
     }
 }
 
+fn csv_options_to_yaml(csv: &CsvOptions) -> CsvOptionsYaml {
+    CsvOptionsYaml {
+        delimiter: csv.delimiter.map(|c| c.to_string()),
+        trim: (csv.trim != TrimMode::default()).then(|| csv.trim.as_str().to_string()),
+        on_ragged_row: (csv.on_ragged_row != RaggedRowMode::default()).then(|| csv.on_ragged_row.as_str().to_string()),
+        limit: csv.limit,
+        max_errors: csv.max_errors,
+        comment: csv.comment.map(|c| c.to_string()),
+        skip_rows: csv.skip_rows,
+        keep_quotes: csv.keep_quotes,
+    }
+}
+
+fn csv_options_from_yaml(yaml: CsvOptionsYaml) -> Result<CsvOptions, ProjectSerializationError> {
+    let delimiter = yaml
+        .delimiter
+        .as_deref()
+        .map(parse_delimiter)
+        .transpose()
+        .map_err(ProjectSerializationError::DeserializeError)?;
+    let trim = yaml
+        .trim
+        .as_deref()
+        .map(TrimMode::parse)
+        .transpose()
+        .map_err(ProjectSerializationError::DeserializeError)?
+        .unwrap_or_default();
+    let on_ragged_row = yaml
+        .on_ragged_row
+        .as_deref()
+        .map(RaggedRowMode::parse)
+        .transpose()
+        .map_err(ProjectSerializationError::DeserializeError)?
+        .unwrap_or_default();
+    let comment = yaml
+        .comment
+        .as_deref()
+        .map(|s| parse_ascii_char("comment", s))
+        .transpose()
+        .map_err(ProjectSerializationError::DeserializeError)?;
+    Ok(CsvOptions {
+        delimiter,
+        trim,
+        on_ragged_row,
+        limit: yaml.limit,
+        max_errors: yaml.max_errors,
+        comment,
+        skip_rows: yaml.skip_rows,
+        keep_quotes: yaml.keep_quotes,
+    })
+}
+
 fn spec_to_yaml(spec: &ProjectSpec) -> ProjectSpecYaml {
+    let defaults = &spec.defaults;
     ProjectSpecYaml {
-        tables: spec.tables.iter().map(table_to_yaml).collect(),
+        defaults: (*defaults != ProjectDefaults::default()).then(|| DefaultsYaml {
+            character_encoding: defaults.character_encoding.clone(),
+            has_header: defaults.has_header,
+            ignore_header_case: defaults.ignore_header_case,
+        }),
+        character_encoding: None,
+        includes: spec.includes.clone(),
+        tables: spec
+            .tables
+            .iter()
+            .filter(|t| t.included_from.is_none())
+            .map(|t| table_to_yaml(t, defaults))
+            .collect(),
     }
 }
 
-fn table_to_yaml(table: &TableSpec) -> TableSpecYaml {
+/// `value` unless it equals the default it would be read back from.
+fn unless_default<T: PartialEq>(value: T, default: Option<T>) -> Option<T> {
+    (default.as_ref() != Some(&value)).then_some(value)
+}
+
+fn table_to_yaml(table: &TableSpec, defaults: &ProjectDefaults) -> TableSpecYaml {
     TableSpecYaml {
         name: table.name.clone(),
         description: table.description.clone(),
-        has_header: table.has_header,
-        source: source_to_yaml(&table.source),
-        columns: table.columns.iter().map(column_to_yaml).collect(),
+        has_header: unless_default(table.has_header, defaults.has_header),
+        keep_raw_line: table.keep_raw_line,
+        source: source_to_yaml(&table.source, defaults.character_encoding.as_deref()),
+        columns: table.columns.iter().map(|c| column_to_yaml(c, defaults)).collect(),
         relationships: table.relationships.iter().map(relationship_to_yaml).collect(),
+        filter: table.filter.as_ref().map(RowFilter::to_string),
+        dedup_by: table.dedup_by.clone(),
     }
 }
 
-fn source_to_yaml(source: &SourceSpec) -> SourceSpecYaml {
+fn source_to_yaml(source: &SourceSpec, default_encoding: Option<&str>) -> SourceSpecYaml {
+    let encoding = |encoding: &String| unless_default(encoding.as_str(), default_encoding).map(str::to_string);
     match source {
         SourceSpec::File(fs) => SourceSpecYaml::File(FileSourceSpecYaml {
             filename: fs.filename.clone(),
-            character_encoding: fs.character_encoding.clone(),
+            character_encoding: encoding(&fs.character_encoding),
+            allow_special_files: fs.allow_special_files,
+            glob: fs.glob,
+            sheet: fs.sheet.clone(),
+            csv: csv_options_to_yaml(&fs.csv),
         }),
         SourceSpec::Cmd(cs) => SourceSpecYaml::Cmd(CmdSourceSpecYaml {
             command: cs.command.clone(),
             args: cs.args.clone(),
             stdout: cs.stdout,
-            character_encoding: cs.character_encoding.clone(),
+            character_encoding: encoding(&cs.character_encoding),
+            csv: csv_options_to_yaml(&cs.csv),
+            timeout_seconds: cs.timeout_seconds,
+            env: cs.env.clone(),
+            capture_stderr: cs.capture_stderr,
+            retries: cs.retries,
+            retry_delay_seconds: cs.retry_delay_seconds,
+            allowed_exit_codes: cs.allowed_exit_codes.clone(),
+        }),
+        SourceSpec::Http(hs) => SourceSpecYaml::Http(HttpSourceSpecYaml {
+            url: hs.url.clone(),
+            character_encoding: encoding(&hs.character_encoding),
+            csv: csv_options_to_yaml(&hs.csv),
+            timeout_seconds: hs.timeout_seconds,
+        }),
+        SourceSpec::Stdin(ss) => SourceSpecYaml::Stdin(StdinSourceSpecYaml {
+            character_encoding: encoding(&ss.character_encoding),
+            csv: csv_options_to_yaml(&ss.csv),
         }),
     }
 }
 
-fn column_to_yaml(col: &ColumnSpec) -> ColumnSpecYaml {
+fn column_to_yaml(col: &ColumnSpec, defaults: &ProjectDefaults) -> ColumnSpecYaml {
     ColumnSpecYaml {
         name: col.name.clone(),
         description: col.description.clone(),
         column_identifier: match &col.column_identifier {
             ColumnIdentifier::Index(i) => ColumnIdentifierYaml::Index(*i),
             ColumnIdentifier::Name(n) => ColumnIdentifierYaml::Name(n.clone()),
+            ColumnIdentifier::Capture { source, pattern, group } => ColumnIdentifierYaml::Capture {
+                capture: CaptureYaml { source: source.clone(), pattern: pattern.clone(), group: *group },
+            },
         },
         column_type: column_type_to_string(&col.column_type),
+        empty_is_null: col.empty_is_null,
+        primary_key: col.primary_key,
+        unique: col.unique,
+        ignore_header_case: unless_default(col.ignore_header_case, Some(defaults.ignore_header_case.unwrap_or(false))),
+        aliases: col.aliases.clone(),
     }
 }
 
@@ -163,100 +479,216 @@ fn relationship_to_yaml(rel: &RelationshipSpec) -> RelationshipSpecYaml {
         source_column: rel.source_column.clone(),
         target_table: rel.target_table.clone(),
         target_column: rel.target_column.clone(),
+        on_delete: (rel.on_delete != OnDelete::default()).then(|| rel.on_delete.as_str().to_string()),
     }
 }
 
 fn spec_from_yaml(yaml: Option<ProjectSpecYaml>) -> Result<ProjectSpec, ProjectSerializationError> {
     match yaml {
-        None => Ok(ProjectSpec { tables: vec![] }),
+        None => Ok(ProjectSpec { tables: vec![], includes: vec![], defaults: Default::default() }),
         Some(spec_yaml) => {
+            let mut defaults = spec_yaml.defaults.map_or_else(ProjectDefaults::default, |d| ProjectDefaults {
+                character_encoding: d.character_encoding,
+                has_header: d.has_header,
+                ignore_header_case: d.ignore_header_case,
+            });
+            if let Some(encoding) = spec_yaml.character_encoding {
+                if defaults.character_encoding.as_ref().is_some_and(|default| *default != encoding) {
+                    return Err(ProjectSerializationError::DeserializeError(
+                        "spec.characterEncoding and spec.defaults.characterEncoding differ; \
+                         keep only spec.defaults.characterEncoding"
+                            .to_string(),
+                    ));
+                }
+                defaults.character_encoding = Some(encoding);
+            }
             let tables = spec_yaml
                 .tables
                 .into_iter()
-                .map(table_from_yaml)
+                .map(|table| table_from_yaml(table, &defaults))
                 .collect::<Result<Vec<_>, _>>()?;
-            Ok(ProjectSpec { tables })
+            Ok(ProjectSpec { tables, includes: spec_yaml.includes, defaults })
         }
     }
 }
 
-fn table_from_yaml(yaml: TableSpecYaml) -> Result<TableSpec, ProjectSerializationError> {
+/// Builds the spec of an included file, whose tables use that file's own defaults.
+pub(crate) fn include_from_model(yaml: ProjectSpecYaml) -> Result<ProjectSpec, ProjectSerializationError> {
+    spec_from_yaml(Some(yaml))
+}
+
+fn table_from_yaml(yaml: TableSpecYaml, defaults: &ProjectDefaults) -> Result<TableSpec, ProjectSerializationError> {
+    let has_header = yaml.has_header.or(defaults.has_header).ok_or_else(|| {
+        ProjectSerializationError::DeserializeError(format!(
+            "table '{}': hasHeader is not set and spec.defaults has no hasHeader",
+            yaml.name
+        ))
+    })?;
     let columns = yaml
         .columns
         .into_iter()
-        .map(column_from_yaml)
+        .map(|column| column_from_yaml(column, defaults))
         .collect::<Result<Vec<_>, _>>()?;
-    Ok(TableSpec {
-        name: yaml.name,
-        description: yaml.description,
-        has_header: yaml.has_header,
-        source: source_from_yaml(yaml.source),
-        columns,
-        relationships: yaml
-            .relationships
-            .into_iter()
-            .map(|r| RelationshipSpec {
+    let relationships = yaml
+        .relationships
+        .into_iter()
+        .map(|r| {
+            let on_delete = r.on_delete.as_deref().map(OnDelete::parse).transpose().map_err(|msg| {
+                ProjectSerializationError::DeserializeError(format!(
+                    "table '{}': relationship '{}': {msg}",
+                    yaml.name, r.name
+                ))
+            })?;
+            Ok(RelationshipSpec {
                 name: r.name,
                 description: r.description,
                 source_column: r.source_column,
                 target_table: r.target_table,
                 target_column: r.target_column,
+                on_delete: on_delete.unwrap_or_default(),
             })
-            .collect(),
+        })
+        .collect::<Result<Vec<_>, ProjectSerializationError>>()?;
+    let filter = yaml
+        .filter
+        .as_deref()
+        .map(|expr| {
+            let filter = RowFilter::parse(expr)
+                .map_err(|msg| format!("table '{}': invalid filter '{}': {}", yaml.name, expr, msg))?;
+            if !columns.iter().any(|c| c.name == filter.column) {
+                return Err(format!("table '{}': filter references unknown column '{}'", yaml.name, filter.column));
+            }
+            Ok(filter)
+        })
+        .transpose()
+        .map_err(ProjectSerializationError::DeserializeError)?;
+    if let Some(unknown) = yaml.dedup_by.iter().find(|name| !columns.iter().any(|c| &c.name == *name)) {
+        return Err(ProjectSerializationError::DeserializeError(format!(
+            "table '{}': dedupBy references unknown column '{}'",
+            yaml.name, unknown
+        )));
+    }
+    let source = source_from_yaml(yaml.source, defaults.character_encoding.as_deref())?;
+    Ok(TableSpec {
+        name: yaml.name,
+        description: yaml.description,
+        has_header,
+        keep_raw_line: yaml.keep_raw_line,
+        source,
+        columns,
+        relationships,
+        filter,
+        dedup_by: yaml.dedup_by,
+        included_from: None,
     })
 }
 
-fn source_from_yaml(yaml: SourceSpecYaml) -> SourceSpec {
-    match yaml {
+fn source_from_yaml(
+    yaml: SourceSpecYaml,
+    default_encoding: Option<&str>,
+) -> Result<SourceSpec, ProjectSerializationError> {
+    Ok(match yaml {
         SourceSpecYaml::File(fs) => SourceSpec::File(FileSourceSpec {
             filename: fs.filename,
-            character_encoding: fs.character_encoding,
+            character_encoding: effective_character_encoding(fs.character_encoding.as_deref(), default_encoding)
+                .to_string(),
+            allow_special_files: fs.allow_special_files,
+            glob: fs.glob,
+            sheet: fs.sheet,
+            csv: csv_options_from_yaml(fs.csv)?,
         }),
         SourceSpecYaml::Cmd(cs) => SourceSpec::Cmd(CmdSourceSpec {
             command: cs.command,
             args: cs.args,
             stdout: cs.stdout,
-            character_encoding: cs.character_encoding,
+            character_encoding: effective_character_encoding(cs.character_encoding.as_deref(), default_encoding)
+                .to_string(),
+            csv: csv_options_from_yaml(cs.csv)?,
+            timeout_seconds: cs.timeout_seconds,
+            env: cs.env,
+            capture_stderr: cs.capture_stderr,
+            retries: cs.retries,
+            retry_delay_seconds: cs.retry_delay_seconds,
+            allowed_exit_codes: cs.allowed_exit_codes,
         }),
-    }
+        SourceSpecYaml::Http(hs) => SourceSpec::Http(HttpSourceSpec {
+            url: hs.url,
+            character_encoding: effective_character_encoding(hs.character_encoding.as_deref(), default_encoding)
+                .to_string(),
+            csv: csv_options_from_yaml(hs.csv)?,
+            timeout_seconds: hs.timeout_seconds,
+        }),
+        SourceSpecYaml::Stdin(ss) => SourceSpec::Stdin(StdinSourceSpec {
+            character_encoding: effective_character_encoding(ss.character_encoding.as_deref(), default_encoding)
+                .to_string(),
+            csv: csv_options_from_yaml(ss.csv)?,
+        }),
+    })
 }
 
-fn column_from_yaml(yaml: ColumnSpecYaml) -> Result<ColumnSpec, ProjectSerializationError> {
+fn column_from_yaml(yaml: ColumnSpecYaml, defaults: &ProjectDefaults) -> Result<ColumnSpec, ProjectSerializationError> {
     let column_type = parse_column_type(&yaml.column_type)
-        .map_err(|e| ProjectSerializationError::DeserializeError(e))?;
+        .map_err(ProjectSerializationError::DeserializeError)?;
     let column_identifier = match yaml.column_identifier {
         ColumnIdentifierYaml::Index(i) => ColumnIdentifier::Index(i),
         ColumnIdentifierYaml::Name(n) => ColumnIdentifier::Name(n),
+        ColumnIdentifierYaml::Capture { capture } => ColumnIdentifier::Capture {
+            source: capture.source,
+            pattern: capture.pattern,
+            group: capture.group,
+        },
     };
     Ok(ColumnSpec {
         name: yaml.name,
         description: yaml.description,
         column_identifier,
         column_type,
+        empty_is_null: yaml.empty_is_null,
+        primary_key: yaml.primary_key,
+        unique: yaml.unique,
+        ignore_header_case: yaml.ignore_header_case.or(defaults.ignore_header_case).unwrap_or(false),
+        aliases: yaml.aliases,
     })
 }
 
-pub fn serialize_to_yaml(project: &Project) -> Result<String, ProjectSerializationError> {
-    let yaml_model = ProjectYaml {
+pub(crate) fn project_to_model(project: &Project) -> ProjectYaml {
+    ProjectYaml {
         api_version: project.api_version.clone(),
         kind: PROJECT_KIND.to_string(),
         metadata: MetadataYaml {
             name: project.name.clone(),
         },
-        spec: if project.spec.tables.is_empty() {
+        spec: if project.spec.tables.is_empty()
+            && project.spec.includes.is_empty()
+            && project.spec.defaults == ProjectDefaults::default()
+        {
             None
         } else {
             Some(spec_to_yaml(&project.spec))
         },
-    };
-    serde_yaml::to_string(&yaml_model)
+    }
+}
+
+pub fn serialize_to_yaml(project: &Project) -> Result<String, ProjectSerializationError> {
+    serde_yaml::to_string(&project_to_model(project))
         .map_err(|e| ProjectSerializationError::SerializeError(e.to_string()))
 }
 
 pub fn deserialize_from_yaml(content: &str) -> Result<Project, ProjectSerializationError> {
     let yaml_model: ProjectYaml = serde_yaml::from_str(content)
         .map_err(|e| ProjectSerializationError::DeserializeError(e.to_string()))?;
+    project_from_model(yaml_model)
+}
+
+/// Parses an included file: `defaults`, `tables` and further `includes`, without the project header.
+pub fn deserialize_include_from_yaml(content: &str) -> Result<ProjectSpec, ProjectSerializationError> {
+    let yaml: ProjectSpecYaml =
+        serde_yaml::from_str(content).map_err(|e| ProjectSerializationError::DeserializeError(e.to_string()))?;
+    include_from_model(yaml)
+}
 
+/// Checks the kind, apiVersion and name of a parsed project file and builds the project from it.
+pub(crate) fn project_from_model(yaml_model: ProjectYaml) -> Result<Project, ProjectSerializationError> {
     if yaml_model.kind != PROJECT_KIND {
         return Err(ProjectSerializationError::UnexpectedKind {
             expected: PROJECT_KIND.to_string(),
@@ -264,6 +696,20 @@ pub fn deserialize_from_yaml(content: &str) -> Result<Project, ProjectSerializat
         });
     }
 
+    if yaml_model.api_version != PROJECT_API_VERSION {
+        return Err(ProjectSerializationError::UnsupportedApiVersion {
+            expected: PROJECT_API_VERSION.to_string(),
+            actual: yaml_model.api_version,
+        });
+    }
+
+    validate_resource_name(&yaml_model.metadata.name).map_err(|reason| {
+        ProjectSerializationError::DeserializeError(format!(
+            "metadata.name '{}' is invalid: {}",
+            yaml_model.metadata.name, reason
+        ))
+    })?;
+
     let spec = spec_from_yaml(yaml_model.spec)?;
 
     Ok(Project {
@@ -298,18 +744,23 @@ impl ProjectSerialization for YamlProjectSerialization {
         self.logger.info(&format!("deserialized project: {}", project.name)).await;
         Ok(project)
     }
+
+    async fn deserialize_include(&self, content: &str) -> Result<ProjectSpec, ProjectSerializationError> {
+        self.logger.debug("deserializing included file").await;
+        deserialize_include_from_yaml(content)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::PROJECT_API_VERSION;
+    use crate::models::{FilterOp, PROJECT_API_VERSION};
 
     fn empty_spec_project(name: &str) -> Project {
         Project {
             name: name.to_string(),
             api_version: PROJECT_API_VERSION.to_string(),
-            spec: ProjectSpec { tables: vec![] },
+            spec: ProjectSpec { tables: vec![], includes: vec![], defaults: Default::default() },
         }
     }
 
@@ -380,6 +831,24 @@ mod tests {
         assert_eq!(project.name, "my-project");
     }
 
+    #[test]
+    fn deserialize_from_yaml_rejects_newer_api_version() {
+        let yaml = "apiVersion: project.dbloada.io/v2\nkind: DBLoadaProject\nmetadata:\n  name: test\nspec: {}\n";
+        let err = deserialize_from_yaml(yaml).unwrap_err();
+        assert!(matches!(
+            err,
+            ProjectSerializationError::UnsupportedApiVersion { ref expected, ref actual }
+                if expected == PROJECT_API_VERSION && actual == "project.dbloada.io/v2"
+        ));
+    }
+
+    #[test]
+    fn deserialize_from_yaml_rejects_empty_api_version() {
+        let yaml = "apiVersion: ''\nkind: DBLoadaProject\nmetadata:\n  name: test\nspec: {}\n";
+        let err = deserialize_from_yaml(yaml).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::UnsupportedApiVersion { ref actual, .. } if actual.is_empty()));
+    }
+
     #[test]
     fn deserialize_from_yaml_extracts_correct_api_version() {
         let yaml = "apiVersion: project.dbloada.io/v1\nkind: DBLoadaProject\nmetadata:\n  name: test\nspec: {}\n";
@@ -425,6 +894,31 @@ mod tests {
         ));
     }
 
+    fn yaml_with_name(name: &str) -> String {
+        format!("apiVersion: project.dbloada.io/v1\nkind: DBLoadaProject\nmetadata:\n  name: '{name}'\nspec: {{}}\n")
+    }
+
+    #[test]
+    fn deserialize_rejects_name_with_uppercase() {
+        let err = deserialize_from_yaml(&yaml_with_name("MyProject")).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::DeserializeError(_)));
+        assert!(err.to_string().contains("lowercase"), "error was: {err}");
+    }
+
+    #[test]
+    fn deserialize_rejects_name_with_leading_hyphen() {
+        let err = deserialize_from_yaml(&yaml_with_name("-project")).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::DeserializeError(_)));
+        assert!(err.to_string().contains("must start with an alphanumeric"), "error was: {err}");
+    }
+
+    #[test]
+    fn deserialize_rejects_name_longer_than_63_chars() {
+        let err = deserialize_from_yaml(&yaml_with_name(&"a".repeat(64))).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::DeserializeError(_)));
+        assert!(err.to_string().contains("no more than 63"), "error was: {err}");
+    }
+
     #[test]
     fn deserialize_rejects_invalid_yaml() {
         let yaml = "not: valid: yaml: {{{{";
@@ -509,19 +1003,48 @@ mod tests {
     fn parse_column_type_string() {
         assert_eq!(
             parse_column_type("string"),
-            Ok(ColumnType::String)
+            Ok(ColumnType::String { max_length: None })
         );
     }
 
+    #[test]
+    fn parse_column_type_string_with_max_length() {
+        assert_eq!(
+            parse_column_type("string(64)"),
+            Ok(ColumnType::String { max_length: Some(64) })
+        );
+    }
+
+    #[test]
+    fn parse_column_type_int64() {
+        assert_eq!(parse_column_type("int64"), Ok(ColumnType::Int64));
+    }
+
+    #[test]
+    fn parse_column_type_bool() {
+        assert_eq!(parse_column_type("bool"), Ok(ColumnType::Bool));
+    }
+
     #[test]
     fn parse_column_type_unknown_returns_error() {
         assert!(parse_column_type("boolean").is_err());
-        assert!(parse_column_type("int64").is_err());
+        assert!(parse_column_type("int32").is_err());
+    }
+
+    #[test]
+    fn parse_column_type_invalid_max_length_returns_error() {
+        assert!(parse_column_type("string(abc)").is_err());
+        assert!(parse_column_type("string()").is_err());
     }
 
     #[test]
     fn column_type_to_string_roundtrip() {
-        let types = vec![ColumnType::String];
+        let types = vec![
+            ColumnType::String { max_length: None },
+            ColumnType::String { max_length: Some(255) },
+            ColumnType::Int64,
+            ColumnType::Bool,
+        ];
         for ct in types {
             let s = column_type_to_string(&ct);
             let parsed = parse_column_type(&s).unwrap();
@@ -589,7 +1112,7 @@ spec:
         }
         assert_eq!(country.columns.len(), 1);
         assert_eq!(country.columns[0].column_identifier, ColumnIdentifier::Index(0));
-        assert_eq!(country.columns[0].column_type, ColumnType::String);
+        assert_eq!(country.columns[0].column_type, ColumnType::String { max_length: None });
         assert!(country.relationships.is_empty());
 
         let city = &project.spec.tables[1];
@@ -597,12 +1120,34 @@ spec:
         assert!(city.has_header);
         assert_eq!(city.columns.len(), 2);
         assert_eq!(city.columns[0].column_identifier, ColumnIdentifier::Name("Name".to_string()));
-        assert_eq!(city.columns[1].column_type, ColumnType::String);
+        assert_eq!(city.columns[1].column_type, ColumnType::String { max_length: None });
         assert_eq!(city.relationships.len(), 1);
         assert_eq!(city.relationships[0].name, "located_in_country");
         assert_eq!(city.relationships[0].target_table, "country");
     }
 
+    #[test]
+    fn round_trip_relationship_on_delete() {
+        let mut project = deserialize_from_yaml(&file_source_yaml("")).unwrap();
+        project.spec.tables[0].relationships.push(RelationshipSpec {
+            name: "parent".to_string(),
+            description: String::new(),
+            source_column: "parent".to_string(),
+            target_table: "t".to_string(),
+            target_column: "name".to_string(),
+            on_delete: OnDelete::SetNull,
+        });
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert!(yaml.contains("onDelete: set null"), "yaml was:\n{yaml}");
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+
+        project.spec.tables[0].relationships[0].on_delete = OnDelete::NoAction;
+        assert!(!serialize_to_yaml(&project).unwrap().contains("onDelete"));
+
+        let err = deserialize_from_yaml(&yaml.replace("set null", "cascade all")).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::DeserializeError(ref msg) if msg.contains("'cascade all'")));
+    }
+
     #[test]
     fn deserialize_cmd_source() {
         let yaml = r#"
@@ -668,6 +1213,562 @@ spec:
         }
     }
 
+    #[test]
+    fn deserialize_cmd_source_timeout_seconds() {
+        let yaml = r#"
+apiVersion: project.dbloada.io/v1
+kind: DBLoadaProject
+metadata:
+  name: test
+spec:
+  tables:
+    - name: t
+      description: test
+      hasHeader: true
+      source:
+        type: cmd
+        command: bash
+        timeoutSeconds: 30
+        characterEncoding: utf-8
+      columns: []
+"#;
+        let project = deserialize_from_yaml(yaml).unwrap();
+        match &project.spec.tables[0].source {
+            SourceSpec::Cmd(cs) => assert_eq!(cs.timeout_seconds, Some(30)),
+            _ => panic!("expected Cmd source"),
+        }
+        assert!(serialize_to_yaml(&project).unwrap().contains("timeoutSeconds: 30"));
+    }
+
+    #[test]
+    fn round_trip_cmd_source_env() {
+        let yaml = r#"
+apiVersion: project.dbloada.io/v1
+kind: DBLoadaProject
+metadata:
+  name: test
+spec:
+  tables:
+    - name: t
+      description: test
+      hasHeader: true
+      source:
+        type: cmd
+        command: bash
+        characterEncoding: utf-8
+        env:
+          REGION: eu-west-1
+          API_TOKEN: ${DATA_API_TOKEN}
+      columns: []
+"#;
+        let project = deserialize_from_yaml(yaml).unwrap();
+        match &project.spec.tables[0].source {
+            SourceSpec::Cmd(cs) => assert_eq!(cs.env, vec![
+                ("REGION".to_string(), "eu-west-1".to_string()),
+                ("API_TOKEN".to_string(), "${DATA_API_TOKEN}".to_string()),
+            ]),
+            _ => panic!("expected Cmd source"),
+        }
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert!(
+            serialized.contains("env:\n        REGION: eu-west-1\n        API_TOKEN: ${DATA_API_TOKEN}"),
+            "yaml was:\n{serialized}"
+        );
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    fn file_source_yaml(extra: &str) -> String {
+        format!(r#"
+apiVersion: project.dbloada.io/v1
+kind: DBLoadaProject
+metadata:
+  name: test
+spec:
+  tables:
+    - name: t
+      description: test
+      hasHeader: true
+      source:
+        type: file
+        filename: data/t.csv
+        characterEncoding: utf-8
+{extra}
+      columns: []
+"#)
+    }
+
+    #[test]
+    fn round_trip_keep_raw_line() {
+        let yaml = file_source_yaml("").replace("hasHeader: true", "hasHeader: true\n      keepRawLine: true");
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert!(project.spec.tables[0].keep_raw_line);
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert!(serialized.contains("keepRawLine: true"), "yaml was:\n{serialized}");
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+
+        let project = deserialize_from_yaml(&file_source_yaml("")).unwrap();
+        assert!(!project.spec.tables[0].keep_raw_line);
+        assert!(!serialize_to_yaml(&project).unwrap().contains("keepRawLine"));
+    }
+
+    #[test]
+    fn deserialize_delimiter() {
+        let project = deserialize_from_yaml(&file_source_yaml("        delimiter: \"|\"")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().delimiter, Some('|'));
+
+        let project = deserialize_from_yaml(&file_source_yaml("")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().delimiter, None);
+    }
+
+    #[test]
+    fn deserialize_rejects_multi_character_delimiter() {
+        let err = deserialize_from_yaml(&file_source_yaml("        delimiter: \"||\"")).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::DeserializeError(ref msg) if msg.contains("'||'")));
+        assert!(deserialize_from_yaml(&file_source_yaml("        delimiter: \"§\"")).is_err());
+    }
+
+    #[test]
+    fn round_trip_delimiter() {
+        let project = deserialize_from_yaml(&file_source_yaml("        delimiter: \";\"")).unwrap();
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert!(yaml.contains("delimiter: ;"), "yaml was:\n{yaml}");
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_skip_rows() {
+        let project = deserialize_from_yaml(&file_source_yaml("        skipRows: 3")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().skip_rows, 3);
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+        let plain = serialize_to_yaml(&deserialize_from_yaml(&file_source_yaml("")).unwrap()).unwrap();
+        assert!(!plain.contains("skipRows"), "yaml was:\n{plain}");
+    }
+
+    #[test]
+    fn round_trip_comment() {
+        let project = deserialize_from_yaml(&file_source_yaml("        comment: \"#\"")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().comment, Some('#'));
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+        let err = deserialize_from_yaml(&file_source_yaml("        comment: \"//\"")).unwrap_err();
+        assert!(err.to_string().contains("comment must be a single ASCII character"), "error was: {err}");
+    }
+
+    #[test]
+    fn round_trip_trim_mode() {
+        let project = deserialize_from_yaml(&file_source_yaml("        trim: headers")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().trim, TrimMode::Headers);
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert!(yaml.contains("trim: headers"), "yaml was:\n{yaml}");
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+
+        let project = deserialize_from_yaml(&file_source_yaml("")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().trim, TrimMode::All);
+        assert!(!serialize_to_yaml(&project).unwrap().contains("trim"));
+    }
+
+    #[test]
+    fn round_trip_on_ragged_row() {
+        let project = deserialize_from_yaml(&file_source_yaml("        onRaggedRow: error")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().on_ragged_row, RaggedRowMode::Error);
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert!(yaml.contains("onRaggedRow: error"), "yaml was:\n{yaml}");
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+
+        let project = deserialize_from_yaml(&file_source_yaml("")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().on_ragged_row, RaggedRowMode::Pad);
+        assert!(deserialize_from_yaml(&file_source_yaml("        onRaggedRow: skip")).is_err());
+    }
+
+    #[test]
+    fn round_trip_limit() {
+        let project = deserialize_from_yaml(&file_source_yaml("        limit: 100")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().limit, Some(100));
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert!(yaml.contains("limit: 100"), "yaml was:\n{yaml}");
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_max_errors() {
+        let project = deserialize_from_yaml(&file_source_yaml("        maxErrors: 20")).unwrap();
+        assert_eq!(project.spec.tables[0].source.csv_options().max_errors, Some(20));
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert!(yaml.contains("maxErrors: 20"), "yaml was:\n{yaml}");
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+    }
+
+    #[test]
+    fn deserialize_rejects_unknown_trim_mode() {
+        let err = deserialize_from_yaml(&file_source_yaml("        trim: both")).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::DeserializeError(ref msg) if msg.contains("'both'")));
+    }
+
+    fn encoding_project_yaml(project_default: &str, pinned: &str) -> String {
+        format!(r#"
+apiVersion: project.dbloada.io/v1
+kind: DBLoadaProject
+metadata:
+  name: test
+spec:
+{project_default}
+  tables:
+    - name: pinned
+      description: ''
+      hasHeader: true
+      source:
+        type: file
+        filename: data/pinned.csv
+{pinned}
+      columns: []
+    - name: unpinned
+      description: ''
+      hasHeader: true
+      source:
+        type: cmd
+        command: cat
+      columns: []
+"#)
+    }
+
+    fn encodings(yaml: &str) -> Vec<String> {
+        deserialize_from_yaml(yaml)
+            .unwrap()
+            .spec
+            .tables
+            .iter()
+            .map(|t| t.source.character_encoding().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn character_encoding_precedence() {
+        let pinned = "        characterEncoding: windows-1252";
+        let auto_default = "  defaults:\n    characterEncoding: auto";
+        // table pinned, project default auto: the table wins, auto applies to the rest
+        assert_eq!(encodings(&encoding_project_yaml(auto_default, pinned)), vec!["windows-1252", "auto"]);
+        // nothing pinned: every source inherits the project default
+        assert_eq!(encodings(&encoding_project_yaml(auto_default, "")), vec!["auto", "auto"]);
+        // no project default: pinned tables keep theirs, others fall back to utf-8
+        assert_eq!(encodings(&encoding_project_yaml("", pinned)), vec!["windows-1252", "utf-8"]);
+        assert_eq!(encodings(&encoding_project_yaml("", "")), vec!["utf-8", "utf-8"]);
+    }
+
+    fn defaults_project_yaml(defaults: &str) -> String {
+        format!(r#"
+apiVersion: project.dbloada.io/v1
+kind: DBLoadaProject
+metadata:
+  name: test
+spec:
+{defaults}
+  tables:
+    - name: inherits
+      description: ''
+      source:
+        type: file
+        filename: data/inherits.csv
+      columns: []
+    - name: overrides
+      description: ''
+      hasHeader: true
+      source:
+        type: file
+        filename: data/overrides.csv
+        characterEncoding: utf-8
+      columns: []
+"#)
+    }
+
+    #[test]
+    fn defaults_apply_to_tables_that_leave_them_out() {
+        let yaml = defaults_project_yaml("  defaults:\n    characterEncoding: latin1\n    hasHeader: false");
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        let headers: Vec<bool> = project.spec.tables.iter().map(|t| t.has_header).collect();
+        assert_eq!(headers, vec![false, true]);
+        assert_eq!(encodings(&yaml), vec!["latin1", "utf-8"]);
+    }
+
+    #[test]
+    fn defaults_are_written_back_once() {
+        let yaml = defaults_project_yaml("  defaults:\n    characterEncoding: latin1\n    hasHeader: false");
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        let serialized = serialize_to_yaml(&project).unwrap();
+        let defaults = "  defaults:\n    characterEncoding: latin1\n    hasHeader: false\n";
+        assert!(serialized.contains(defaults), "yaml was:\n{serialized}");
+        // only the overriding table pins its own values
+        assert_eq!(serialized.matches("hasHeader:").count(), 2, "yaml was:\n{serialized}");
+        assert_eq!(serialized.matches("characterEncoding:").count(), 2, "yaml was:\n{serialized}");
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn spec_character_encoding_is_read_as_the_default_encoding() {
+        let yaml = file_source_yaml("")
+            .replace("        characterEncoding: utf-8\n", "")
+            .replace("spec:\n", "spec:\n  characterEncoding: auto\n");
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert_eq!(project.spec.defaults.character_encoding.as_deref(), Some("auto"));
+        match &project.spec.tables[0].source {
+            SourceSpec::File(fs) => assert_eq!(fs.character_encoding, "auto"),
+            _ => panic!("expected File source"),
+        }
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert!(serialized.contains("  defaults:\n    characterEncoding: auto\n"), "yaml was:\n{serialized}");
+
+        let conflicting = yaml.replace("spec:\n", "spec:\n  defaults:\n    characterEncoding: latin1\n");
+        let err = deserialize_from_yaml(&conflicting).unwrap_err();
+        assert!(err.to_string().contains("keep only spec.defaults.characterEncoding"), "error was: {err}");
+    }
+
+    #[test]
+    fn ignore_header_case_default_applies_to_columns_that_leave_it_out() {
+        let yaml = file_source_yaml("")
+            .replace("spec:\n", "spec:\n  defaults:\n    ignoreHeaderCase: true\n")
+            .replace("      columns: []", &[
+                "      columns:",
+                "        - name: a",
+                "          description: ''",
+                "          columnIdentifier: A",
+                "          type: string",
+                "        - name: b",
+                "          description: ''",
+                "          columnIdentifier: B",
+                "          type: string",
+                "          ignoreHeaderCase: false",
+            ].join("\n"));
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        let flags: Vec<bool> = project.spec.tables[0].columns.iter().map(|c| c.ignore_header_case).collect();
+        assert_eq!(flags, vec![true, false]);
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(serialized.matches("ignoreHeaderCase: true").count(), 1, "yaml was:\n{serialized}");
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_column_aliases() {
+        let yaml = file_source_yaml("").replace("      columns: []", &[
+            "      columns:",
+            "        - name: building",
+            "          description: ''",
+            "          columnIdentifier: Building Name",
+            "          type: string",
+            "          aliases: [BuildingName, Building]",
+        ].join("\n"));
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert_eq!(project.spec.tables[0].columns[0].aliases, vec!["BuildingName", "Building"]);
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    fn filter_project_yaml(filter: &str) -> String {
+        file_source_yaml("")
+            .replace("hasHeader: true", &format!("hasHeader: true\n      filter: '{filter}'"))
+            .replace("      columns: []", &[
+                "      columns:",
+                "        - name: status",
+                "          description: ''",
+                "          columnIdentifier: Status",
+                "          type: string",
+            ].join("\n"))
+    }
+
+    #[test]
+    fn round_trip_filter() {
+        let project = deserialize_from_yaml(&filter_project_yaml("status != \"on hold\"")).unwrap();
+        let filter = project.spec.tables[0].filter.as_ref().unwrap();
+        assert_eq!((filter.column.as_str(), filter.op, filter.value.as_str()), ("status", FilterOp::Ne, "on hold"));
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_dedup_by_and_reject_unknown_columns() {
+        let yaml = filter_project_yaml("status != x").replace("      filter: 'status != x'", "      dedupBy: [status]");
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert_eq!(project.spec.tables[0].dedup_by, vec!["status"]);
+        assert_eq!(deserialize_from_yaml(&serialize_to_yaml(&project).unwrap()).unwrap(), project);
+
+        let err = deserialize_from_yaml(&yaml.replace("[status]", "[status, region]")).unwrap_err();
+        assert!(err.to_string().contains("dedupBy references unknown column 'region'"), "error was: {err}");
+    }
+
+    #[test]
+    fn round_trip_includes_and_parse_included_file() {
+        let yaml = filter_project_yaml("status != x").replace("spec:\n", "spec:\n  includes: [tables/a.yaml]\n");
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert_eq!(project.spec.includes, vec!["tables/a.yaml"]);
+        assert_eq!(deserialize_from_yaml(&serialize_to_yaml(&project).unwrap()).unwrap(), project);
+
+        let spec_lines: Vec<&str> = yaml.split_once("spec:\n").unwrap().1.lines().map(|l| l.get(2..).unwrap_or(l)).collect();
+        let included = deserialize_include_from_yaml(&spec_lines.join("\n")).unwrap();
+        assert_eq!(included.includes, vec!["tables/a.yaml"]);
+        assert_eq!(included.tables, project.spec.tables);
+    }
+
+    #[test]
+    fn deserialize_rejects_malformed_filters() {
+        for (filter, message) in [
+            ("status = active", "expected 'column == value' or 'column != value'"),
+            ("status ==", "expected a value after '=='"),
+            ("== active", "expected a single column name before '=='"),
+            ("status == \"active", "unterminated or malformed quoted value"),
+            ("status == on hold", "value 'on hold' must be a single word or a double-quoted string"),
+            ("region == north", "filter references unknown column 'region'"),
+        ] {
+            let err = deserialize_from_yaml(&filter_project_yaml(filter)).unwrap_err();
+            assert!(
+                matches!(err, ProjectSerializationError::DeserializeError(ref msg) if msg.contains(message)),
+                "filter {filter:?} gave: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn deserialize_requires_has_header_without_a_default() {
+        let err = deserialize_from_yaml(&defaults_project_yaml("")).unwrap_err();
+        assert!(matches!(err, ProjectSerializationError::DeserializeError(ref msg) if msg.contains("'inherits'")));
+    }
+
+    #[test]
+    fn serialize_keeps_the_default_encoding_off_inheriting_sources() {
+        let project =
+            deserialize_from_yaml(&encoding_project_yaml("  defaults:\n    characterEncoding: auto", "")).unwrap();
+        let yaml = serialize_to_yaml(&project).unwrap();
+        assert_eq!(yaml.matches("characterEncoding: auto").count(), 1, "yaml was:\n{yaml}");
+        assert_eq!(deserialize_from_yaml(&yaml).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_capture_identifier() {
+        let yaml = file_source_yaml("").replace(
+            "      columns: []",
+            "      columns:\n        - name: year\n          description: ''\n          columnIdentifier:\n            capture:\n              source: file\n              pattern: '_(\\d{4})_'\n          type: string",
+        );
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert_eq!(
+            project.spec.tables[0].columns[0].column_identifier,
+            ColumnIdentifier::Capture { source: "file".to_string(), pattern: r"_(\d{4})_".to_string(), group: 1 }
+        );
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert!(serialized.contains("capture:"), "yaml was:\n{serialized}");
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn serialize_omits_default_delimiter() {
+        let project = deserialize_from_yaml(&file_source_yaml("")).unwrap();
+        assert!(!serialize_to_yaml(&project).unwrap().contains("delimiter"));
+    }
+
+    #[test]
+    fn deserialize_column_empty_is_null() {
+        let yaml = file_source_yaml("").replace(
+            "      columns: []",
+            "      columns:\n        - name: a\n          description: ''\n          columnIdentifier: A\n          type: string\n        - name: b\n          description: ''\n          columnIdentifier: B\n          type: string\n          emptyIsNull: false",
+        );
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        let columns = &project.spec.tables[0].columns;
+        assert!(columns[0].empty_is_null);
+        assert!(!columns[1].empty_is_null);
+
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(serialized.matches("emptyIsNull").count(), 1, "yaml was:\n{serialized}");
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn deserialize_column_primary_key_and_unique() {
+        let yaml = file_source_yaml("").replace(
+            "      columns: []",
+            "      columns:\n        - name: a\n          description: ''\n          columnIdentifier: A\n          type: string\n          primaryKey: true\n        - name: b\n          description: ''\n          columnIdentifier: B\n          type: string\n          unique: true",
+        );
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        let columns = &project.spec.tables[0].columns;
+        assert!(columns[0].primary_key);
+        assert!(!columns[1].primary_key);
+        assert!(!columns[0].unique);
+        assert!(columns[1].unique);
+
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(serialized.matches("primaryKey").count(), 1, "yaml was:\n{serialized}");
+        assert_eq!(serialized.matches("unique").count(), 1, "yaml was:\n{serialized}");
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn deserialize_file_source_allow_special_files() {
+        let yaml = |extra: &str| format!(r#"
+apiVersion: project.dbloada.io/v1
+kind: DBLoadaProject
+metadata:
+  name: test
+spec:
+  tables:
+    - name: t
+      description: test
+      hasHeader: true
+      source:
+        type: file
+        filename: data/pipe.csv
+        characterEncoding: utf-8
+{extra}
+      columns: []
+"#);
+        let allow = |content: &str| match &deserialize_from_yaml(content).unwrap().spec.tables[0].source {
+            SourceSpec::File(fs) => fs.allow_special_files,
+            _ => panic!("expected File source"),
+        };
+        assert!(!allow(&yaml("")));
+        assert!(allow(&yaml("        allowSpecialFiles: true")));
+
+        let written = |content: &str| serialize_to_yaml(&deserialize_from_yaml(content).unwrap()).unwrap();
+        assert!(!written(&yaml("")).contains("allowSpecialFiles"));
+        assert!(written(&yaml("        allowSpecialFiles: true")).contains("allowSpecialFiles: true"));
+    }
+
+    #[test]
+    fn round_trip_http_source() {
+        let yaml = file_source_yaml("        timeoutSeconds: 10").replace(
+            "type: file\n        filename: data/t.csv",
+            "type: http\n        url: https://example.com/t.csv",
+        );
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        assert_eq!(project.spec.tables[0].source, SourceSpec::Http(HttpSourceSpec {
+            url: "https://example.com/t.csv".to_string(),
+            character_encoding: "utf-8".to_string(),
+            csv: Default::default(),
+            timeout_seconds: Some(10),
+        }));
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_file_source_sheet() {
+        let project = deserialize_from_yaml(&file_source_yaml("        sheet: Cities")).unwrap();
+        match &project.spec.tables[0].source {
+            SourceSpec::File(fs) => assert_eq!(fs.sheet.as_deref(), Some("Cities")),
+            _ => panic!("expected File source"),
+        }
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
+
+    #[test]
+    fn round_trip_file_source_glob() {
+        let project = deserialize_from_yaml(&file_source_yaml("        glob: true")).unwrap();
+        match &project.spec.tables[0].source {
+            SourceSpec::File(fs) => assert!(fs.glob),
+            _ => panic!("expected File source"),
+        }
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+        let plain = serialize_to_yaml(&deserialize_from_yaml(&file_source_yaml("")).unwrap()).unwrap();
+        assert!(!plain.contains("glob"), "yaml was:\n{plain}");
+    }
+
     #[test]
     fn round_trip_with_file_source() {
         let project = Project {
@@ -678,18 +1779,33 @@ spec:
                     name: "users".to_string(),
                     description: "User table".to_string(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::File(FileSourceSpec {
                         filename: "data/users.csv".to_string(),
                         character_encoding: "utf-8".to_string(),
+                        allow_special_files: false,
+                        glob: false,
+                        sheet: None,
+                        csv: Default::default(),
                     }),
                     columns: vec![ColumnSpec {
                         name: "name".to_string(),
                         description: "User name".to_string(),
                         column_identifier: ColumnIdentifier::Index(0),
-                        column_type: ColumnType::String,
+                        column_type: ColumnType::String { max_length: None },
+                        empty_is_null: true,
+                        primary_key: false,
+                        unique: false,
+                        ignore_header_case: false,
+                        aliases: vec![],
                     }],
                     relationships: vec![],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 }],
+                includes: vec![],
+                defaults: Default::default(),
             },
         };
         let yaml = serialize_to_yaml(&project).unwrap();
@@ -707,24 +1823,69 @@ spec:
                     name: "employees".to_string(),
                     description: "Employee table".to_string(),
                     has_header: true,
+                    keep_raw_line: false,
                     source: SourceSpec::Cmd(CmdSourceSpec {
                         command: "bash".to_string(),
                         args: vec!["scripts/gen.sh".to_string(), "$TEMP_CSV_PATH".to_string()],
                         stdout: false,
                         character_encoding: "utf-8".to_string(),
+                        csv: Default::default(),
+                        timeout_seconds: None,
+                        env: vec![],
+                        capture_stderr: false,
+                        retries: 0,
+                        retry_delay_seconds: 0,
+                        allowed_exit_codes: vec![0],
                     }),
                     columns: vec![ColumnSpec {
                         name: "name".to_string(),
                         description: "Name".to_string(),
                         column_identifier: ColumnIdentifier::Name("Name".to_string()),
-                        column_type: ColumnType::String,
+                        column_type: ColumnType::String { max_length: None },
+                        empty_is_null: true,
+                        primary_key: false,
+                        unique: false,
+                        ignore_header_case: false,
+                        aliases: vec![],
                     }],
                     relationships: vec![],
+                    filter: None,
+                    dedup_by: vec![],
+                    included_from: None,
                 }],
+                includes: vec![],
+                defaults: Default::default(),
             },
         };
         let yaml = serialize_to_yaml(&project).unwrap();
+        assert!(!yaml.contains("captureStderr"), "yaml was:\n{yaml}");
         let deserialized = deserialize_from_yaml(&yaml).unwrap();
         assert_eq!(project, deserialized);
     }
+
+    #[test]
+    fn deserialize_keeps_env_references_in_sources() {
+        let yaml = file_source_yaml("").replace("data/t.csv", "data/${REGION}/t.csv");
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        match &project.spec.tables[0].source {
+            SourceSpec::File(fs) => assert_eq!(fs.filename, "data/${REGION}/t.csv"),
+            _ => panic!("expected File source"),
+        }
+        assert!(serialize_to_yaml(&project).unwrap().contains("filename: data/${REGION}/t.csv"));
+    }
+
+    #[test]
+    fn round_trip_keeps_dollars_in_cmd_args_as_written() {
+        let yaml = file_source_yaml("").replace(
+            "type: file\n        filename: data/t.csv",
+            "type: cmd\n        command: sh\n        args: [\"-c\", \"echo $$$${HOME} $TEMP_CSV_PATH\"]",
+        );
+        let project = deserialize_from_yaml(&yaml).unwrap();
+        match &project.spec.tables[0].source {
+            SourceSpec::Cmd(cs) => assert_eq!(cs.args[1], "echo $$$${HOME} $TEMP_CSV_PATH"),
+            _ => panic!("expected Cmd source"),
+        }
+        let serialized = serialize_to_yaml(&project).unwrap();
+        assert_eq!(deserialize_from_yaml(&serialized).unwrap(), project);
+    }
 }
@@ -1,142 +1,292 @@
+use std::collections::HashMap;
+use std::future::Future;
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use async_trait::async_trait;
-use crate::models::{SourceSpec, Table, TableSpec};
+use tokio::sync::Semaphore;
+use crate::models::{CmdSourceSpec, SourceSpec, Table, TableSpec};
 use crate::traits::{Logger, CsvParser};
 use crate::traits::table_reader::{TableReader, TableReaderError};
+use crate::components::validate::validate_impl::validate_cmd_source;
+use super::encoding::decode_bytes;
+use super::env::expand_env;
 
+pub const DEFAULT_MAX_CMD_PROCESSES: usize = 2;
+pub const TEMP_CSV_PATH_PLACEHOLDER: &str = "$TEMP_CSV_PATH";
+pub const PROJECT_DIR_PLACEHOLDER: &str = "$PROJECT_DIR";
+pub const TABLE_NAME_PLACEHOLDER: &str = "$TABLE_NAME";
+
+/// Reads tables from the output of a command. At most `max_processes` commands
+/// run at the same time, no matter how many tables are read concurrently.
 pub struct CmdCsvTableReader {
     logger: Box<dyn Logger>,
     csv_parser: Box<dyn CsvParser>,
+    process_slots: Arc<Semaphore>,
 }
 
 impl CmdCsvTableReader {
-    pub fn new(logger: Box<dyn Logger>, csv_parser: Box<dyn CsvParser>) -> Self {
-        CmdCsvTableReader { logger, csv_parser }
+    pub fn new(logger: Box<dyn Logger>, csv_parser: Box<dyn CsvParser>, max_processes: usize) -> Self {
+        CmdCsvTableReader {
+            logger,
+            csv_parser,
+            process_slots: Arc::new(Semaphore::new(max_processes.max(1))),
+        }
     }
-}
-
-pub fn substitute_temp_path(args: &[String], path: &str) -> Vec<String> {
-    args.iter()
-        .map(|a| a.replace("$TEMP_CSV_PATH", path))
-        .collect()
-}
 
-fn decode_bytes(bytes: &[u8], encoding_label: &str) -> Result<String, String> {
-    let encoding = encoding_rs::Encoding::for_label(encoding_label.as_bytes())
-        .ok_or_else(|| format!("unsupported encoding: '{}'", encoding_label))?;
-    let (cow, _, had_errors) = encoding.decode(bytes);
-    if had_errors {
-        return Err(format!("encoding errors while decoding as '{}'", encoding_label));
+    async fn log_stderr(&self, table: &TableSpec, stderr: &[u8]) {
+        for line in String::from_utf8_lossy(stderr).lines() {
+            self.logger.debug(&format!("[{}] {}", table.name, line)).await;
+        }
     }
-    Ok(cow.into_owned())
-}
 
-#[async_trait]
-impl TableReader for CmdCsvTableReader {
-    fn name(&self) -> &str {
-        "cmd_csv"
-    }
+    /// Runs the command once, holding a process slot, and returns its decoded output.
+    async fn run_command(
+        &self,
+        table: &TableSpec,
+        cmd_source: &CmdSourceSpec,
+        project_dir: &Path,
+        env: &[(String, String)],
+        placeholders: &HashMap<&str, String>,
+    ) -> Result<String, CommandFailure> {
+        let _permit = self.process_slots.acquire().await.map_err(|e| {
+            CommandFailure::fatal(TableReaderError::ReadError { table_name: table.name.clone(), message: e.to_string() })
+        })?;
 
-    fn can_read(&self, table: &TableSpec) -> bool {
-        matches!(&table.source, SourceSpec::Cmd(_))
-    }
-
-    async fn read_table(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
-        let cmd_source = match &table.source {
-            SourceSpec::Cmd(cs) => cs,
-            SourceSpec::File(_) => {
-                return Err(TableReaderError::ReadError {
-                    table_name: table.name.clone(),
-                    message: "CmdCsvTableReader does not support file sources".to_string(),
-                });
-            }
-        };
-
-        let content = if cmd_source.stdout {
+        if cmd_source.stdout {
+            let args = substitute_placeholders(&cmd_source.args, placeholders);
             self.logger.info(&format!(
                 "running command (stdout mode): {} {:?}",
-                cmd_source.command, cmd_source.args
+                cmd_source.command, args
             )).await;
 
-            let output = tokio::process::Command::new(&cmd_source.command)
-                .args(&cmd_source.args)
-                .current_dir(project_dir)
-                .output()
-                .await
-                .map_err(|e| TableReaderError::ReadError {
-                    table_name: table.name.clone(),
-                    message: format!("failed to execute command '{}': {}", cmd_source.command, e),
-                })?;
+            let output = wait_for_command(
+                table,
+                cmd_source,
+                tokio::process::Command::new(&cmd_source.command)
+                    .args(&args)
+                    .envs(env.iter().map(|(k, v)| (k, v)))
+                    .current_dir(project_dir)
+                    .kill_on_drop(true)
+                    .output(),
+            ).await?;
 
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                return Err(TableReaderError::ReadError {
-                    table_name: table.name.clone(),
-                    message: format!(
-                        "command '{}' exited with status {}: {}",
-                        cmd_source.command,
-                        output.status,
-                        stderr.trim()
-                    ),
-                });
+            self.log_stderr(table, &output.stderr).await;
+            if !exit_allowed(output.status, &cmd_source.allowed_exit_codes) {
+                return Err(CommandFailure::retryable(exit_error(table, cmd_source, output.status, &output.stderr)));
             }
 
             decode_bytes(&output.stdout, &cmd_source.character_encoding).map_err(|msg| {
-                TableReaderError::ReadError {
+                CommandFailure::fatal(TableReaderError::ReadError {
                     table_name: table.name.clone(),
                     message: msg,
-                }
-            })?
+                })
+            })
         } else {
             let temp_dir = std::env::temp_dir();
             let temp_filename = format!("dbloada-{}.csv", uuid::Uuid::new_v4());
             let temp_path = temp_dir.join(&temp_filename);
             let temp_path_str = temp_path.display().to_string();
 
-            let args = substitute_temp_path(&cmd_source.args, &temp_path_str);
+            let mut placeholders = placeholders.clone();
+            placeholders.insert(TEMP_CSV_PATH_PLACEHOLDER, temp_path_str.clone());
+            let args = substitute_placeholders(&cmd_source.args, &placeholders);
 
             self.logger.info(&format!(
                 "running command (temp file mode): {} {:?} -> {}",
                 cmd_source.command, args, temp_path_str
             )).await;
 
-            let status = tokio::process::Command::new(&cmd_source.command)
-                .args(&args)
-                .current_dir(project_dir)
-                .stdout(std::process::Stdio::inherit())
-                .stderr(std::process::Stdio::inherit())
-                .status()
-                .await
-                .map_err(|e| TableReaderError::ReadError {
-                    table_name: table.name.clone(),
-                    message: format!("failed to execute command '{}': {}", cmd_source.command, e),
-                })?;
+            let stderr = if cmd_source.capture_stderr {
+                std::process::Stdio::piped()
+            } else {
+                std::process::Stdio::inherit()
+            };
+            let output = wait_for_command(
+                table,
+                cmd_source,
+                tokio::process::Command::new(&cmd_source.command)
+                    .args(&args)
+                    .envs(env.iter().map(|(k, v)| (k, v)))
+                    .current_dir(project_dir)
+                    .stdout(std::process::Stdio::inherit())
+                    .stderr(stderr)
+                    .kill_on_drop(true)
+                    .output(),
+            ).await;
+            if output.is_err() {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+            }
+            let output = output?;
 
-            if !status.success() {
-                return Err(TableReaderError::ReadError {
-                    table_name: table.name.clone(),
-                    message: format!(
-                        "command '{}' exited with status {}",
-                        cmd_source.command, status
-                    ),
-                });
+            self.log_stderr(table, &output.stderr).await;
+            if !exit_allowed(output.status, &cmd_source.allowed_exit_codes) {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                return Err(CommandFailure::retryable(exit_error(table, cmd_source, output.status, &output.stderr)));
             }
 
             let bytes = tokio::fs::read(&temp_path).await.map_err(|e| {
-                TableReaderError::ReadError {
+                CommandFailure::fatal(TableReaderError::ReadError {
                     table_name: table.name.clone(),
                     message: format!("failed to read temp file '{}': {}", temp_path_str, e),
-                }
+                })
             })?;
 
             let _ = tokio::fs::remove_file(&temp_path).await;
 
             decode_bytes(&bytes, &cmd_source.character_encoding).map_err(|msg| {
-                TableReaderError::ReadError {
+                CommandFailure::fatal(TableReaderError::ReadError {
                     table_name: table.name.clone(),
                     message: msg,
+                })
+            })
+        }
+    }
+}
+
+/// A failed run of a command. Only runs that could not start or exited non-zero are retried.
+struct CommandFailure {
+    error: TableReaderError,
+    retryable: bool,
+}
+
+impl CommandFailure {
+    fn retryable(error: TableReaderError) -> Self {
+        CommandFailure { error, retryable: true }
+    }
+
+    fn fatal(error: TableReaderError) -> Self {
+        CommandFailure { error, retryable: false }
+    }
+}
+
+/// Whether the command exited with one of the allowed codes. Commands killed by a signal never did.
+pub fn exit_allowed(status: std::process::ExitStatus, allowed_exit_codes: &[i32]) -> bool {
+    status.code().is_some_and(|code| allowed_exit_codes.contains(&code))
+}
+
+fn exit_error(
+    table: &TableSpec,
+    cmd_source: &CmdSourceSpec,
+    status: std::process::ExitStatus,
+    stderr: &[u8],
+) -> TableReaderError {
+    let stderr = String::from_utf8_lossy(stderr);
+    let outcome = match status.code() {
+        Some(code) => format!("exited with code {code}"),
+        None => format!("was terminated ({status})"),
+    };
+    let allowed: Vec<String> = cmd_source.allowed_exit_codes.iter().map(|c| c.to_string()).collect();
+    let mut message = format!(
+        "command '{}' {} (allowed exit codes: {})",
+        cmd_source.command,
+        outcome,
+        allowed.join(", ")
+    );
+    if !stderr.trim().is_empty() {
+        message.push_str(&format!(": {}", stderr.trim()));
+    }
+    TableReaderError::ReadError { table_name: table.name.clone(), message }
+}
+
+fn resolve_env(table: &TableSpec, cmd_source: &CmdSourceSpec) -> Result<Vec<(String, String)>, TableReaderError> {
+    cmd_source
+        .env
+        .iter()
+        .map(|(key, value)| Ok((key.clone(), expand_env(table, &format!("env '{key}'"), value)?)))
+        .collect()
+}
+
+/// The command source with `${VAR}` expanded in its command and args.
+fn resolve_command(table: &TableSpec, cmd_source: &CmdSourceSpec) -> Result<CmdSourceSpec, TableReaderError> {
+    Ok(CmdSourceSpec {
+        command: expand_env(table, "command", &cmd_source.command)?,
+        args: cmd_source.args.iter().map(|arg| expand_env(table, "args", arg)).collect::<Result<_, _>>()?,
+        ..cmd_source.clone()
+    })
+}
+
+/// Replaces every placeholder key, e.g. `$TABLE_NAME`, in each argument with its value.
+pub fn substitute_placeholders(args: &[String], placeholders: &HashMap<&str, String>) -> Vec<String> {
+    args.iter()
+        .map(|arg| {
+            placeholders
+                .iter()
+                .fold(arg.clone(), |acc, (placeholder, value)| acc.replace(placeholder, value))
+        })
+        .collect()
+}
+
+/// Awaits a command started with `kill_on_drop`, so dropping it on timeout kills the child.
+async fn wait_for_command<T>(
+    table: &TableSpec,
+    cmd_source: &CmdSourceSpec,
+    command: impl Future<Output = std::io::Result<T>>,
+) -> Result<T, CommandFailure> {
+    let started = Instant::now();
+    let result = match cmd_source.timeout_seconds {
+        Some(seconds) => tokio::time::timeout(Duration::from_secs(seconds), command)
+            .await
+            .map_err(|_| CommandFailure::fatal(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: format!(
+                    "command '{}' was killed after running for {:.1}s (timeoutSeconds: {})",
+                    cmd_source.command,
+                    started.elapsed().as_secs_f64(),
+                    seconds
+                ),
+            }))?,
+        None => command.await,
+    };
+    result.map_err(|e| CommandFailure::retryable(TableReaderError::ReadError {
+        table_name: table.name.clone(),
+        message: format!("failed to execute command '{}': {}", cmd_source.command, e),
+    }))
+}
+
+#[async_trait]
+impl TableReader for CmdCsvTableReader {
+    fn name(&self) -> &str {
+        "cmd_csv"
+    }
+
+    fn can_read(&self, table: &TableSpec) -> bool {
+        matches!(&table.source, SourceSpec::Cmd(_))
+    }
+
+    async fn read_table(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
+        let cmd_source = match &table.source {
+            SourceSpec::Cmd(cs) => cs,
+            SourceSpec::File(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => {
+                return Err(TableReaderError::ReadError {
+                    table_name: table.name.clone(),
+                    message: "CmdCsvTableReader only supports command sources".to_string(),
+                });
+            }
+        };
+
+        let cmd_source = &resolve_command(table, cmd_source)?;
+        let env = resolve_env(table, cmd_source)?;
+        let absolute_project_dir = std::path::absolute(project_dir).unwrap_or_else(|_| project_dir.to_path_buf());
+        let placeholders = HashMap::from([
+            (PROJECT_DIR_PLACEHOLDER, absolute_project_dir.display().to_string()),
+            (TABLE_NAME_PLACEHOLDER, table.name.clone()),
+        ]);
+
+        let mut attempt = 0;
+        let content = loop {
+            match self.run_command(table, cmd_source, project_dir, &env, &placeholders).await {
+                Ok(content) => break content,
+                Err(failure) if failure.retryable && attempt < cmd_source.retries => {
+                    attempt += 1;
+                    self.logger.warn(&format!(
+                        "{}; retrying in {}s (retry {} of {})",
+                        failure.error, cmd_source.retry_delay_seconds, attempt, cmd_source.retries
+                    )).await;
+                    tokio::time::sleep(Duration::from_secs(cmd_source.retry_delay_seconds)).await;
                 }
-            })?
+                Err(failure) => return Err(failure.error),
+            }
         };
 
         let result = self.csv_parser.parse(&content, table).await?;
@@ -151,6 +301,24 @@ impl TableReader for CmdCsvTableReader {
 
         Ok(result)
     }
+
+    async fn dry_read(&self, table: &TableSpec, _project_dir: &Path) -> Result<Table, TableReaderError> {
+        let SourceSpec::Cmd(cmd_source) = &table.source else {
+            return Err(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: "CmdCsvTableReader only supports command sources".to_string(),
+            });
+        };
+        let problems = validate_cmd_source(&table.name, cmd_source);
+        if !problems.is_empty() {
+            return Err(TableReaderError::ReadError { table_name: table.name.clone(), message: problems.join("; ") });
+        }
+        resolve_command(table, cmd_source)?;
+        resolve_env(table, cmd_source)?;
+
+        self.logger.info(&format!("dry run: not running command '{}' for table '{}'", cmd_source.command, table.name)).await;
+        Ok(Table::from_spec(table, vec![]))
+    }
 }
 
 #[cfg(test)]
@@ -158,27 +326,56 @@ mod tests {
     use super::*;
     use crate::models::CmdSourceSpec;
 
+    fn all_placeholders() -> HashMap<&'static str, String> {
+        HashMap::from([
+            (TEMP_CSV_PATH_PLACEHOLDER, "/tmp/dbloada-123.csv".to_string()),
+            (PROJECT_DIR_PLACEHOLDER, "/home/me/project".to_string()),
+            (TABLE_NAME_PLACEHOLDER, "employee".to_string()),
+        ])
+    }
+
     #[test]
-    fn substitute_temp_path_replaces_placeholder() {
+    fn substitute_placeholders_replaces_temp_csv_path() {
         let args = vec![
             "script.sh".to_string(),
             "$TEMP_CSV_PATH".to_string(),
         ];
-        let result = substitute_temp_path(&args, "/tmp/dbloada-123.csv");
+        let result = substitute_placeholders(&args, &all_placeholders());
         assert_eq!(result, vec!["script.sh", "/tmp/dbloada-123.csv"]);
     }
 
     #[test]
-    fn substitute_temp_path_no_placeholder() {
+    fn substitute_placeholders_replaces_project_dir() {
+        let args = vec!["$PROJECT_DIR/scripts/input.json".to_string()];
+        let result = substitute_placeholders(&args, &all_placeholders());
+        assert_eq!(result, vec!["/home/me/project/scripts/input.json"]);
+    }
+
+    #[test]
+    fn substitute_placeholders_replaces_table_name() {
+        let args = vec!["--table".to_string(), "$TABLE_NAME".to_string()];
+        let result = substitute_placeholders(&args, &all_placeholders());
+        assert_eq!(result, vec!["--table", "employee"]);
+    }
+
+    #[test]
+    fn substitute_placeholders_replaces_several_in_one_argument() {
+        let args = vec!["--out=$PROJECT_DIR/out/$TABLE_NAME.csv,$TEMP_CSV_PATH".to_string()];
+        let result = substitute_placeholders(&args, &all_placeholders());
+        assert_eq!(result, vec!["--out=/home/me/project/out/employee.csv,/tmp/dbloada-123.csv"]);
+    }
+
+    #[test]
+    fn substitute_placeholders_no_placeholder() {
         let args = vec!["script.sh".to_string(), "--flag".to_string()];
-        let result = substitute_temp_path(&args, "/tmp/dbloada-123.csv");
+        let result = substitute_placeholders(&args, &all_placeholders());
         assert_eq!(result, vec!["script.sh", "--flag"]);
     }
 
     #[test]
-    fn substitute_temp_path_empty_args() {
+    fn substitute_placeholders_empty_args() {
         let args: Vec<String> = vec![];
-        let result = substitute_temp_path(&args, "/tmp/dbloada-123.csv");
+        let result = substitute_placeholders(&args, &all_placeholders());
         assert!(result.is_empty());
     }
 
@@ -189,19 +386,31 @@ mod tests {
             Box::new(crate::components::csv_parser::CsvParserImpl::new(
                 Box::new(crate::components::test_helpers::TestLogger),
             )),
+            DEFAULT_MAX_CMD_PROCESSES,
         );
         let spec = TableSpec {
             name: "t".to_string(),
             description: String::new(),
             has_header: true,
+            keep_raw_line: false,
             source: SourceSpec::Cmd(CmdSourceSpec {
                 command: "bash".to_string(),
                 args: vec![],
                 stdout: true,
                 character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
             }),
             columns: vec![],
             relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
         };
         assert!(reader.can_read(&spec));
     }
@@ -213,18 +422,439 @@ mod tests {
             Box::new(crate::components::csv_parser::CsvParserImpl::new(
                 Box::new(crate::components::test_helpers::TestLogger),
             )),
+            DEFAULT_MAX_CMD_PROCESSES,
         );
         let spec = TableSpec {
             name: "t".to_string(),
             description: String::new(),
             has_header: true,
+            keep_raw_line: false,
             source: SourceSpec::File(crate::models::FileSourceSpec {
                 filename: "data/test.csv".to_string(),
                 character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
             }),
             columns: vec![],
             relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
         };
         assert!(!reader.can_read(&spec));
     }
+
+    #[tokio::test]
+    async fn read_table_runs_at_most_max_processes_commands_at_once() {
+        use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = CmdCsvTableReader::new(
+            Box::new(crate::components::test_helpers::TestLogger),
+            Box::new(crate::components::csv_parser::CsvParserImpl::new(
+                Box::new(crate::components::test_helpers::TestLogger),
+            )),
+            1,
+        );
+        // mkdir fails if another command holds the lock directory, so any
+        // overlap between two running commands makes a read fail
+        let spec = |name: &str| TableSpec {
+            name: name.to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Cmd(CmdSourceSpec {
+                command: "sh".to_string(),
+                args: vec![
+                    "-c".to_string(),
+                    format!("mkdir running || exit 3; sleep 0.1; rmdir running; printf 'Name\\n{name}\\n'"),
+                ],
+                stdout: true,
+                character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
+            }),
+            columns: vec![ColumnSpec {
+                name: "name".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("Name".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        };
+        let (a, b, c) = (spec("a"), spec("b"), spec("c"));
+
+        let (ra, rb, rc) = tokio::join!(
+            reader.read_table(&a, tmp.path()),
+            reader.read_table(&b, tmp.path()),
+            reader.read_table(&c, tmp.path()),
+        );
+        assert_eq!(ra.unwrap().cell(0, 0), Some("a"));
+        assert_eq!(rb.unwrap().cell(0, 0), Some("b"));
+        assert_eq!(rc.unwrap().cell(0, 0), Some("c"));
+    }
+
+    fn sleeping_spec(stdout: bool) -> TableSpec {
+        TableSpec {
+            name: "slow".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Cmd(CmdSourceSpec {
+                command: "sleep".to_string(),
+                args: vec!["30".to_string()],
+                stdout,
+                character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: Some(1),
+                env: vec![],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
+            }),
+            columns: vec![],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    async fn assert_times_out(spec: TableSpec) {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = CmdCsvTableReader::new(
+            Box::new(crate::components::test_helpers::TestLogger),
+            Box::new(crate::components::csv_parser::CsvParserImpl::new(
+                Box::new(crate::components::test_helpers::TestLogger),
+            )),
+            DEFAULT_MAX_CMD_PROCESSES,
+        );
+
+        let started = Instant::now();
+        let err = reader.read_table(&spec, tmp.path()).await.unwrap_err().to_string();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(err.contains("table 'slow'"), "error was: {}", err);
+        assert!(err.contains("was killed after running for 1."), "error was: {}", err);
+    }
+
+    #[tokio::test]
+    async fn dry_read_checks_spec_without_running_command() {
+        let reader = CmdCsvTableReader::new(
+            Box::new(crate::components::test_helpers::TestLogger),
+            Box::new(crate::components::csv_parser::CsvParserImpl::new(
+                Box::new(crate::components::test_helpers::TestLogger),
+            )),
+            DEFAULT_MAX_CMD_PROCESSES,
+        );
+
+        let started = Instant::now();
+        let table = reader.dry_read(&sleeping_spec(true), Path::new("/nonexistent")).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(table.num_rows(), 0);
+
+        // temp file mode without $TEMP_CSV_PATH in the args
+        let err = reader.dry_read(&sleeping_spec(false), Path::new("/nonexistent")).await.unwrap_err();
+        assert!(err.to_string().contains(TEMP_CSV_PATH_PLACEHOLDER), "error was: {err}");
+    }
+
+    #[tokio::test]
+    async fn read_table_kills_stdout_command_after_timeout() {
+        assert_times_out(sleeping_spec(true)).await;
+    }
+
+    #[tokio::test]
+    async fn read_table_kills_temp_file_command_after_timeout() {
+        assert_times_out(sleeping_spec(false)).await;
+    }
+
+    #[tokio::test]
+    async fn read_table_passes_interpolated_env_overriding_inherited() {
+        use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = CmdCsvTableReader::new(
+            Box::new(crate::components::test_helpers::TestLogger),
+            Box::new(crate::components::csv_parser::CsvParserImpl::new(
+                Box::new(crate::components::test_helpers::TestLogger),
+            )),
+            DEFAULT_MAX_CMD_PROCESSES,
+        );
+        let path = std::env::var("PATH").unwrap();
+        let spec = TableSpec {
+            name: "env".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Cmd(CmdSourceSpec {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), "printf 'Value\\n%s\\n%s\\n' \"$GREETING\" \"$HOME\"".to_string()],
+                stdout: true,
+                character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![
+                    ("GREETING".to_string(), "hello ${PATH}".to_string()),
+                    ("HOME".to_string(), "/from/env".to_string()),
+                ],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
+            }),
+            columns: vec![ColumnSpec {
+                name: "value".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("Value".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        };
+
+        let table = reader.read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some(format!("hello {path}").as_str()));
+        assert_eq!(table.cell(1, 0), Some("/from/env"));
+    }
+
+    #[tokio::test]
+    async fn read_table_substitutes_placeholders_in_stdout_mode() {
+        use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = CmdCsvTableReader::new(
+            Box::new(crate::components::test_helpers::TestLogger),
+            Box::new(crate::components::csv_parser::CsvParserImpl::new(
+                Box::new(crate::components::test_helpers::TestLogger),
+            )),
+            DEFAULT_MAX_CMD_PROCESSES,
+        );
+        let spec = TableSpec {
+            name: "placeholders".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Cmd(CmdSourceSpec {
+                command: "printf".to_string(),
+                args: vec!["Value\\n%s\\n%s\\n".to_string(), "$TABLE_NAME".to_string(), "$PROJECT_DIR".to_string()],
+                stdout: true,
+                character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
+            }),
+            columns: vec![ColumnSpec {
+                name: "value".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("Value".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        };
+
+        let table = reader.read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("placeholders"));
+        assert_eq!(table.cell(1, 0), Some(tmp.path().display().to_string().as_str()));
+    }
+
+    fn stderr_spec(stdout: bool, capture_stderr: bool, script: &str) -> TableSpec {
+        use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType};
+
+        TableSpec {
+            name: "noisy".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Cmd(CmdSourceSpec {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string(), "sh".to_string(), "$TEMP_CSV_PATH".to_string()],
+                stdout,
+                character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![],
+                capture_stderr,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
+            }),
+            columns: vec![ColumnSpec {
+                name: "name".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("Name".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    fn recording_reader(logger: &crate::components::test_helpers::RecordingLogger) -> CmdCsvTableReader {
+        CmdCsvTableReader::new(
+            Box::new(logger.clone()),
+            Box::new(crate::components::csv_parser::CsvParserImpl::new(
+                Box::new(crate::components::test_helpers::TestLogger),
+            )),
+            DEFAULT_MAX_CMD_PROCESSES,
+        )
+    }
+
+    #[tokio::test]
+    async fn read_table_logs_stderr_lines_on_success_in_stdout_mode() {
+        let tmp = tempfile::tempdir().unwrap();
+        let logger = crate::components::test_helpers::RecordingLogger::default();
+        let spec = stderr_spec(true, false, "echo 'starting' >&2; echo 'fetched 1 row' >&2; printf 'Name\\nAlice\\n'");
+
+        let table = recording_reader(&logger).read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("Alice"));
+        let debug = logger.messages_at("debug");
+        assert!(debug.contains(&"[noisy] starting".to_string()), "debug was: {debug:?}");
+        assert!(debug.contains(&"[noisy] fetched 1 row".to_string()), "debug was: {debug:?}");
+    }
+
+    #[tokio::test]
+    async fn read_table_captures_stderr_in_temp_file_mode_when_asked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let logger = crate::components::test_helpers::RecordingLogger::default();
+        let spec = stderr_spec(false, true, "echo 'writing' >&2; printf 'Name\\nBob\\n' > \"$1\"");
+
+        let table = recording_reader(&logger).read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("Bob"));
+        assert!(logger.messages_at("debug").contains(&"[noisy] writing".to_string()));
+    }
+
+    #[tokio::test]
+    async fn read_table_reports_captured_stderr_on_failure_in_temp_file_mode() {
+        let tmp = tempfile::tempdir().unwrap();
+        let logger = crate::components::test_helpers::RecordingLogger::default();
+        let spec = stderr_spec(false, true, "echo 'no credentials' >&2; exit 2");
+
+        let err = recording_reader(&logger).read_table(&spec, tmp.path()).await.unwrap_err().to_string();
+        assert!(err.ends_with(": no credentials"), "error was: {err}");
+    }
+
+    #[tokio::test]
+    async fn read_table_expands_env_in_args_when_run() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = recording_reader(&Default::default());
+        let script = "printf 'Name\\n%s\\n%s\\n' '${PATH}' '${DBLOADA_TEST_UNSET:-fallback}/$${HOME}'";
+        let table = reader.read_table(&stderr_spec(true, false, script), tmp.path()).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some(std::env::var("PATH").unwrap().as_str()));
+        assert_eq!(table.cell(1, 0), Some("fallback/${HOME}"));
+
+        let spec = stderr_spec(true, false, "printf 'Name\\n%s\\n' '${DBLOADA_TEST_UNSET}'");
+        let err = reader.read_table(&spec, tmp.path()).await.unwrap_err().to_string();
+        assert!(err.ends_with("args: environment variable 'DBLOADA_TEST_UNSET' is not set"), "error was: {err}");
+    }
+
+    fn with_retries(mut spec: TableSpec, retries: u32) -> TableSpec {
+        if let SourceSpec::Cmd(cs) = &mut spec.source {
+            cs.retries = retries;
+        }
+        spec
+    }
+
+    #[tokio::test]
+    async fn read_table_retries_failing_command_until_it_succeeds() {
+        let tmp = tempfile::tempdir().unwrap();
+        let logger = crate::components::test_helpers::RecordingLogger::default();
+        let script = "echo run >> attempts; [ $(wc -l < attempts) -ge 3 ] || exit 7; printf 'Name\\nAlice\\n'";
+        let spec = with_retries(stderr_spec(true, false, script), 2);
+
+        let table = recording_reader(&logger).read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("Alice"));
+        let warnings = logger.messages_at("warn");
+        assert_eq!(warnings.len(), 2, "warnings were: {warnings:?}");
+        let expected = "exited with code 7 (allowed exit codes: 0); retrying in 0s (retry 2 of 2)";
+        assert!(warnings[1].contains(expected), "warnings were: {warnings:?}");
+    }
+
+    #[tokio::test]
+    async fn read_table_gives_up_after_last_retry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let spec = with_retries(stderr_spec(false, true, "echo run >> attempts; exit 1"), 1);
+        let err = recording_reader(&Default::default()).read_table(&spec, tmp.path()).await.unwrap_err();
+        assert!(err.to_string().contains("exited with code 1"), "error was: {err}");
+        let attempts = tokio::fs::read_to_string(tmp.path().join("attempts")).await.unwrap();
+        assert_eq!(attempts.lines().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn read_table_does_not_retry_unparseable_output() {
+        let tmp = tempfile::tempdir().unwrap();
+        let spec = with_retries(stderr_spec(true, false, "echo run >> attempts; printf 'Other\\nAlice\\n'"), 3);
+        assert!(recording_reader(&Default::default()).read_table(&spec, tmp.path()).await.is_err());
+        let attempts = tokio::fs::read_to_string(tmp.path().join("attempts")).await.unwrap();
+        assert_eq!(attempts.lines().count(), 1);
+    }
+
+    fn with_allowed_exit_codes(mut spec: TableSpec, codes: Vec<i32>) -> TableSpec {
+        if let SourceSpec::Cmd(cs) = &mut spec.source {
+            cs.allowed_exit_codes = codes;
+        }
+        spec
+    }
+
+    #[tokio::test]
+    async fn read_table_accepts_allowed_exit_code_in_both_modes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = recording_reader(&Default::default());
+        let stdout = with_allowed_exit_codes(stderr_spec(true, false, "printf 'Name\\nAlice\\n'; exit 2"), vec![0, 2]);
+        assert_eq!(reader.read_table(&stdout, tmp.path()).await.unwrap().cell(0, 0), Some("Alice"));
+        let script = "printf 'Name\\nBob\\n' > \"$1\"; exit 2";
+        let temp_file = with_allowed_exit_codes(stderr_spec(false, true, script), vec![0, 2]);
+        assert_eq!(reader.read_table(&temp_file, tmp.path()).await.unwrap().cell(0, 0), Some("Bob"));
+    }
+
+    #[tokio::test]
+    async fn read_table_lists_allowed_exit_codes_on_other_codes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let reader = recording_reader(&Default::default());
+        for stdout in [true, false] {
+            let spec = with_allowed_exit_codes(stderr_spec(stdout, true, "exit 2"), vec![0, 1]);
+            let err = reader.read_table(&spec, tmp.path()).await.unwrap_err();
+            assert!(err.to_string().contains("exited with code 2 (allowed exit codes: 0, 1)"), "error was: {err}");
+        }
+    }
 }
@@ -1,14 +1,169 @@
-use std::path::Path;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
 use async_trait::async_trait;
-use crate::models::{SourceSpec, TableSpec};
-use crate::traits::{Logger, FileSystem, CsvParser};
+use crate::models::{CsvOptions, FileSourceSpec, SourceSpec, TableSpec};
+use crate::traits::{Logger, FileSystem, CsvParser, RowCallback};
 use crate::traits::table_reader::{TableReader, TableReaderError};
+use super::encoding::decode_bytes;
+use super::env::expand_env;
+use crate::components::csv_parser::csv_parser_impl::skip_lines;
 use crate::models::Table;
 
+fn is_gzip_filename(filename: &str) -> bool {
+    filename.to_lowercase().ends_with(".csv.gz")
+}
+
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decompressed = Vec::new();
+    flate2::read::GzDecoder::new(bytes)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| format!("failed to decompress gzip data: {}", e))?;
+    Ok(decompressed)
+}
+
+/// Where `path` points, following `..` and symlinks. A path that does not exist yet resolves through
+/// its nearest existing ancestor, as long as no `..` follows that ancestor; anything else is `None`.
+async fn resolve_path(file_system: &dyn FileSystem, path: &Path) -> Option<PathBuf> {
+    for ancestor in path.ancestors() {
+        let Ok(resolved) = file_system.canonicalize(ancestor).await else {
+            continue;
+        };
+        let rest = path.strip_prefix(ancestor).ok()?;
+        if !rest.components().all(|c| matches!(c, Component::Normal(_))) {
+            return None;
+        }
+        return Some(resolved.join(rest));
+    }
+    None
+}
+
+/// Refuses a source path that resolves outside the project directory, following `..` and symlinks,
+/// unless `allow_outside` is set. A path that cannot be resolved is refused as well.
+pub async fn check_inside_project(
+    file_system: &dyn FileSystem,
+    table: &TableSpec,
+    path: &Path,
+    project_dir: &Path,
+    allow_outside: bool,
+) -> Result<(), TableReaderError> {
+    if allow_outside {
+        return Ok(());
+    }
+    let refuse = |reason: &str| TableReaderError::ReadError {
+        table_name: table.name.clone(),
+        message: format!("'{}' {reason}; use --allow-outside-project to read it", path.display()),
+    };
+    let root = file_system.canonicalize(project_dir).await?;
+    match resolve_path(file_system, path).await {
+        Some(resolved) if resolved.starts_with(&root) => Ok(()),
+        Some(_) => Err(refuse("is outside the project directory")),
+        None => Err(refuse("cannot be resolved inside the project directory")),
+    }
+}
+
+/// The path of a file source when nothing is there. Glob patterns are left to the reader, which
+/// reports patterns that match no file. A path outside the project directory is refused first, so
+/// the preflight does not tell whether something exists there.
+pub async fn missing_file_source(
+    file_system: &dyn FileSystem,
+    table: &TableSpec,
+    project_dir: &Path,
+    allow_outside: bool,
+) -> Result<Option<PathBuf>, TableReaderError> {
+    match &table.source {
+        SourceSpec::File(fs) if !fs.glob => {
+            let path = project_dir.join(expand_env(table, "filename", &fs.filename)?);
+            check_inside_project(file_system, table, &path, project_dir, allow_outside).await?;
+            Ok((!file_system.exists(&path).await?).then_some(path))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Files matching `pattern` under `project_dir`, sorted by path.
+pub fn expand_glob(project_dir: &Path, pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let full = Path::new(&glob::Pattern::escape(&project_dir.to_string_lossy())).join(pattern);
+    let mut paths = glob::glob(&full.to_string_lossy())
+        .map_err(|e| format!("invalid glob pattern '{pattern}': {e}"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    paths.sort();
+    Ok(paths)
+}
+
+/// The header record of `content` and the byte offset where the data rows start.
+fn split_header(content: &str, csv_options: &CsvOptions) -> Result<(csv::StringRecord, usize), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(csv_options.delimiter_byte())
+        .comment(csv_options.comment_byte())
+        .has_headers(true)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    Ok((headers, reader.position().byte() as usize))
+}
+
+/// Joins the contents of several CSV files into one. With `has_header`, every file must start with
+/// the same header, which is kept once. Only the first file keeps its `skip_rows` lines, since
+/// the parser skips those once for the joined content.
+pub fn concat_csv_contents(
+    contents: &[(String, String)],
+    has_header: bool,
+    csv_options: &CsvOptions,
+) -> Result<String, String> {
+    let mut result = String::new();
+    let mut first_header: Option<(&str, csv::StringRecord)> = None;
+    for (i, (name, original)) in contents.iter().enumerate() {
+        let content = skip_lines(original, csv_options.skip_rows);
+        let mut body = if i == 0 { original.as_str() } else { content };
+        if has_header {
+            let (header, offset) = split_header(content, csv_options).map_err(|e| format!("{name}: {e}"))?;
+            match &first_header {
+                None => first_header = Some((name, header)),
+                Some((first_name, first)) => {
+                    if *first != header {
+                        return Err(format!(
+                            "header of {name} ({}) does not match header of {first_name} ({})",
+                            header.iter().collect::<Vec<_>>().join(", "),
+                            first.iter().collect::<Vec<_>>().join(", "),
+                        ));
+                    }
+                    body = &content[offset..];
+                }
+            }
+        }
+        if !result.is_empty() && !result.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str(body);
+    }
+    Ok(result)
+}
+
+/// Whether a source can be parsed straight from the open file: a single, uncompressed UTF-8
+/// file whose raw lines are not kept. Other sources are read and decoded whole first.
+pub fn can_stream(table: &TableSpec, file_source: &FileSourceSpec) -> bool {
+    !file_source.glob
+        && !table.keep_raw_line
+        && !is_gzip_filename(&file_source.filename)
+        && encoding_rs::Encoding::for_label(file_source.character_encoding.as_bytes()) == Some(encoding_rs::UTF_8)
+}
+
+fn file_source(table: &TableSpec) -> Result<&FileSourceSpec, TableReaderError> {
+    match &table.source {
+        SourceSpec::File(fs) => Ok(fs),
+        SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => Err(TableReaderError::ReadError {
+            table_name: table.name.clone(),
+            message: "CsvTableReader only supports file sources".to_string(),
+        }),
+    }
+}
+
 pub struct CsvTableReader {
     logger: Box<dyn Logger>,
     file_system: Box<dyn FileSystem>,
     csv_parser: Box<dyn CsvParser>,
+    allow_outside_project: bool,
 }
 
 impl CsvTableReader {
@@ -16,19 +171,90 @@ impl CsvTableReader {
         logger: Box<dyn Logger>,
         file_system: Box<dyn FileSystem>,
         csv_parser: Box<dyn CsvParser>,
+        allow_outside_project: bool,
     ) -> Self {
-        CsvTableReader { logger, file_system, csv_parser }
+        CsvTableReader { logger, file_system, csv_parser, allow_outside_project }
     }
-}
 
-fn decode_bytes(bytes: &[u8], encoding_label: &str) -> Result<String, String> {
-    let encoding = encoding_rs::Encoding::for_label(encoding_label.as_bytes())
-        .ok_or_else(|| format!("unsupported encoding: '{}'", encoding_label))?;
-    let (cow, _, had_errors) = encoding.decode(bytes);
-    if had_errors {
-        return Err(format!("encoding errors while decoding as '{}'", encoding_label));
+    /// The open file when the source can be streamed, otherwise `None`.
+    async fn open_stream(
+        &self,
+        table: &TableSpec,
+        project_dir: &Path,
+    ) -> Result<Option<Box<dyn Read + Send>>, TableReaderError> {
+        let file_source = file_source(table)?;
+        if !can_stream(table, file_source) {
+            return Ok(None);
+        }
+        let path = project_dir.join(expand_env(table, "filename", &file_source.filename)?);
+        self.check_path(table, file_source, &path, project_dir).await?;
+        self.logger.debug(&format!("streaming CSV file: {}", path.display())).await;
+        Ok(Some(self.file_system.open_read(&path).await?))
+    }
+
+    async fn check_path(
+        &self,
+        table: &TableSpec,
+        file_source: &FileSourceSpec,
+        path: &Path,
+        project_dir: &Path,
+    ) -> Result<(), TableReaderError> {
+        check_inside_project(self.file_system.as_ref(), table, path, project_dir, self.allow_outside_project).await?;
+        if !file_source.allow_special_files && self.file_system.is_special_file(path).await? {
+            return Err(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: format!(
+                    "'{}' is not a regular file; set allowSpecialFiles to read from it",
+                    path.display()
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    async fn read_content(&self, table: &TableSpec, project_dir: &Path) -> Result<String, TableReaderError> {
+        let file_source = file_source(table)?;
+        let filename = expand_env(table, "filename", &file_source.filename)?;
+        if !file_source.glob {
+            return self.read_file(table, file_source, &project_dir.join(&filename), project_dir).await;
+        }
+
+        let read_error = |message: String| TableReaderError::ReadError { table_name: table.name.clone(), message };
+        let paths = expand_glob(project_dir, &filename).map_err(read_error)?;
+        if paths.is_empty() {
+            return Err(read_error(format!("no files match '{}'", file_source.filename)));
+        }
+        self.logger.debug(&format!("glob '{}' matched {} file(s)", file_source.filename, paths.len())).await;
+        let mut contents = Vec::new();
+        for path in paths {
+            let content = self.read_file(table, file_source, &path, project_dir).await?;
+            contents.push((path.display().to_string(), content));
+        }
+        concat_csv_contents(&contents, table.has_header, &file_source.csv).map_err(read_error)
+    }
+
+    async fn read_file(
+        &self,
+        table: &TableSpec,
+        file_source: &FileSourceSpec,
+        path: &Path,
+        project_dir: &Path,
+    ) -> Result<String, TableReaderError> {
+        self.logger.debug(&format!("reading CSV file: {}", path.display())).await;
+        self.logger.debug(&format!("has_header: {}", table.has_header)).await;
+        self.check_path(table, file_source, path, project_dir).await?;
+
+        let read_error = |msg: String| TableReaderError::ReadError {
+            table_name: table.name.clone(),
+            message: format!("{}: {}", path.display(), msg),
+        };
+
+        let mut bytes = self.file_system.load_bytes(path).await?;
+        if is_gzip_filename(&file_source.filename) {
+            bytes = gunzip(&bytes).map_err(read_error)?;
+        }
+        decode_bytes(&bytes, &file_source.character_encoding).map_err(read_error)
     }
-    Ok(cow.into_owned())
 }
 
 #[async_trait]
@@ -37,41 +263,21 @@ impl TableReader for CsvTableReader {
         "csv"
     }
 
+    fn extensions(&self) -> &[&str] {
+        &[".csv", ".csv.gz"]
+    }
+
     fn can_read(&self, table: &TableSpec) -> bool {
         match &table.source {
-            SourceSpec::File(fs) => fs.filename.to_lowercase().ends_with(".csv"),
-            SourceSpec::Cmd(_) => false,
+            SourceSpec::File(fs) => {
+                fs.filename.to_lowercase().ends_with(".csv") || is_gzip_filename(&fs.filename)
+            }
+            SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => false,
         }
     }
 
     async fn read_table(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
-        let file_source = match &table.source {
-            SourceSpec::File(fs) => fs,
-            SourceSpec::Cmd(_) => {
-                return Err(TableReaderError::ReadError {
-                    table_name: table.name.clone(),
-                    message: "CsvTableReader does not support command sources".to_string(),
-                });
-            }
-        };
-
-        let path = project_dir.join(&file_source.filename);
-        self.logger.debug(&format!("reading CSV file: {}", path.display())).await;
-        self.logger.debug(&format!("has_header: {}", table.has_header)).await;
-
-        let encoding_lower = file_source.character_encoding.to_lowercase();
-        let content = if encoding_lower == "utf-8" || encoding_lower == "utf8" {
-            self.file_system.load(&path).await?
-        } else {
-            let bytes = self.file_system.load_bytes(&path).await?;
-            decode_bytes(&bytes, &file_source.character_encoding).map_err(|msg| {
-                TableReaderError::ReadError {
-                    table_name: table.name.clone(),
-                    message: msg,
-                }
-            })?
-        };
-
+        let content = self.read_content(table, project_dir).await?;
         let result = self.csv_parser.parse(&content, table).await?;
 
         self.logger.info(&format!(
@@ -84,6 +290,36 @@ impl TableReader for CsvTableReader {
 
         Ok(result)
     }
+
+    async fn read_rows(
+        &self,
+        table: &TableSpec,
+        project_dir: &Path,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, TableReaderError> {
+        if let Some(input) = self.open_stream(table, project_dir).await? {
+            return Ok(self.csv_parser.parse_rows_from_reader(input, table, on_row).await?);
+        }
+        let content = self.read_content(table, project_dir).await?;
+        Ok(self.csv_parser.parse_rows(&content, table, on_row).await?)
+    }
+
+    async fn dry_read(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
+        if let Some(input) = self.open_stream(table, project_dir).await? {
+            return Ok(self.csv_parser.parse_header_from_reader(input, table).await?);
+        }
+        let content = self.read_content(table, project_dir).await?;
+        Ok(self.csv_parser.parse_header(&content, table).await?)
+    }
+
+    async fn column_indices(&self, table: &TableSpec, project_dir: &Path) -> Result<Option<Vec<usize>>, TableReaderError> {
+        let content = self.read_content(table, project_dir).await?;
+        Ok(Some(self.csv_parser.column_indices(&content, table).await?))
+    }
+
+    async fn missing_file(&self, table: &TableSpec, project_dir: &Path) -> Result<Option<PathBuf>, TableReaderError> {
+        missing_file_source(self.file_system.as_ref(), table, project_dir, self.allow_outside_project).await
+    }
 }
 
 #[cfg(test)]
@@ -105,6 +341,7 @@ mod tests {
             Box::new(TestLogger),
             Box::new(InMemoryFileSystem::new(store)),
             Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+            false,
         )
     }
 
@@ -112,6 +349,10 @@ mod tests {
         SourceSpec::File(FileSourceSpec {
             filename: filename.to_string(),
             character_encoding: "utf-8".to_string(),
+            allow_special_files: false,
+            glob: false,
+            sheet: None,
+            csv: Default::default(),
         })
     }
 
@@ -120,9 +361,13 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             has_header: true,
+            keep_raw_line: false,
             source: file_source(filename),
             columns,
             relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
         }
     }
 
@@ -131,9 +376,13 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             has_header: false,
+            keep_raw_line: false,
             source: file_source(filename),
             columns,
             relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
         }
     }
 
@@ -142,7 +391,12 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             column_identifier: ColumnIdentifier::Name(header.to_string()),
-            column_type: ColumnType::String,
+            column_type: ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
         }
     }
 
@@ -151,7 +405,12 @@ mod tests {
             name: name.to_string(),
             description: String::new(),
             column_identifier: ColumnIdentifier::Index(index),
-            column_type: ColumnType::String,
+            column_type: ColumnType::String { max_length: None },
+            empty_is_null: true,
+            primary_key: false,
+            unique: false,
+            ignore_header_case: false,
+            aliases: vec![],
         }
     }
 
@@ -169,6 +428,13 @@ mod tests {
         assert!(reader.can_read(&spec));
     }
 
+    #[test]
+    fn can_read_gzipped_csv() {
+        let reader = make_reader(vec![]);
+        let spec = table_spec_with_header("t", "data/file.CSV.gz", vec![]);
+        assert!(reader.can_read(&spec));
+    }
+
     #[test]
     fn cannot_read_non_csv() {
         let reader = make_reader(vec![]);
@@ -183,14 +449,25 @@ mod tests {
             name: "t".to_string(),
             description: String::new(),
             has_header: true,
+            keep_raw_line: false,
             source: SourceSpec::Cmd(crate::models::CmdSourceSpec {
                 command: "bash".to_string(),
                 args: vec![],
                 stdout: true,
                 character_encoding: "utf-8".to_string(),
+                csv: Default::default(),
+                timeout_seconds: None,
+                env: vec![],
+                capture_stderr: false,
+                retries: 0,
+                retry_delay_seconds: 0,
+                allowed_exit_codes: vec![0],
             }),
             columns: vec![],
             relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
         };
         assert!(!reader.can_read(&spec));
     }
@@ -214,6 +491,22 @@ mod tests {
         assert_eq!(table.cell(1, 1), Some("Germany"));
     }
 
+    #[tokio::test]
+    async fn dry_read_resolves_header_without_rows() {
+        let reader = make_reader(vec![
+            ("/project/data/cities.csv", "Name,Country\nLondon,UK\nBerlin,Germany\n"),
+        ]);
+        let spec = table_spec_with_header("city", "data/cities.csv", vec![col_by_name("name", "Name")]);
+        let table = reader.dry_read(&spec, Path::new("/project")).await.unwrap();
+        assert_eq!(table.num_rows(), 0);
+        assert_eq!(table.num_columns(), 1);
+
+        let spec = table_spec_with_header("city", "data/cities.csv", vec![col_by_name("zip", "Zip")]);
+        assert!(reader.dry_read(&spec, Path::new("/project")).await.is_err());
+        let spec = table_spec_with_header("city", "data/missing.csv", vec![col_by_name("name", "Name")]);
+        assert!(reader.dry_read(&spec, Path::new("/project")).await.is_err());
+    }
+
     #[tokio::test]
     async fn read_table_without_headers_by_index() {
         let reader = make_reader(vec![
@@ -279,15 +572,318 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[cfg(unix)]
+    fn make_fifo(path: &Path) {
+        let status = std::process::Command::new("mkfifo").arg(path).status().unwrap();
+        assert!(status.success());
+    }
+
+    fn disk_reader() -> CsvTableReader {
+        disk_reader_with(false)
+    }
+
+    fn disk_reader_with(allow_outside_project: bool) -> CsvTableReader {
+        use crate::components::file_system::DiskFileSystem;
+        CsvTableReader::new(
+            Box::new(TestLogger),
+            Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+            Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+            allow_outside_project,
+        )
+    }
+
+    #[tokio::test]
+    async fn read_table_rejects_paths_outside_the_project_unless_allowed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project_dir = tmp.path().join("project");
+        tokio::fs::create_dir_all(project_dir.join("data")).await.unwrap();
+        tokio::fs::write(tmp.path().join("shared.csv"), "Name\nLondon\n").await.unwrap();
+        tokio::fs::write(project_dir.join("cities.csv"), "Name\nBerlin\n").await.unwrap();
+
+        let absolute = tmp.path().join("shared.csv").display().to_string();
+        for filename in ["../shared.csv", "data/../../shared.csv", absolute.as_str()] {
+            let spec = table_spec_with_header("city", filename, vec![col_by_name("name", "Name")]);
+            let err = disk_reader().read_table(&spec, &project_dir).await.unwrap_err();
+            assert!(err.to_string().contains("is outside the project directory"), "{filename}: {err}");
+            let table = disk_reader_with(true).read_table(&spec, &project_dir).await.unwrap();
+            assert_eq!(table.cell(0, 0), Some("London"));
+        }
+
+        let spec = table_spec_with_header("city", "data/../cities.csv", vec![col_by_name("name", "Name")]);
+        assert_eq!(disk_reader().read_table(&spec, &project_dir).await.unwrap().cell(0, 0), Some("Berlin"));
+        let err = disk_reader().read_table(&glob_spec("../shared*.csv"), &project_dir).await.unwrap_err();
+        assert!(err.to_string().contains("is outside the project directory"), "error was: {err}");
+    }
+
+    #[tokio::test]
+    async fn missing_file_refuses_paths_outside_the_project_before_checking_existence() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project_dir = tmp.path().join("project");
+        tokio::fs::create_dir_all(&project_dir).await.unwrap();
+        let spec = |filename: &str| table_spec_with_header("city", filename, vec![col_by_name("name", "Name")]);
+
+        let err = disk_reader().missing_file(&spec("../nowhere.csv"), &project_dir).await.unwrap_err();
+        assert!(err.to_string().contains("is outside the project directory"), "error was: {err}");
+        let err = disk_reader().missing_file(&spec("no-dir/../../nowhere.csv"), &project_dir).await.unwrap_err();
+        assert!(err.to_string().contains("cannot be resolved inside the project directory"), "error was: {err}");
+
+        let missing = disk_reader().missing_file(&spec("data/missing.csv"), &project_dir).await.unwrap();
+        assert_eq!(missing, Some(project_dir.join("data/missing.csv")));
+        let missing = disk_reader_with(true).missing_file(&spec("../nowhere.csv"), &project_dir).await.unwrap();
+        assert_eq!(missing, Some(project_dir.join("../nowhere.csv")));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn read_table_from_fifo_when_special_files_allowed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let fifo = tmp.path().join("cities.csv");
+        make_fifo(&fifo);
+
+        let writer_path = fifo.clone();
+        let writer = tokio::spawn(async move {
+            tokio::fs::write(writer_path, "Name\nLondon\nBerlin\n").await.unwrap();
+        });
+
+        let mut spec = table_spec_with_header("city", "cities.csv", vec![col_by_name("name", "Name")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.allow_special_files = true;
+        }
+        let table = disk_reader().read_table(&spec, tmp.path()).await.unwrap();
+        writer.await.unwrap();
+        assert_eq!(table.num_rows(), 2);
+        assert_eq!(table.cell(1, 0), Some("Berlin"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn read_table_rejects_fifo_by_default() {
+        let tmp = tempfile::tempdir().unwrap();
+        make_fifo(&tmp.path().join("cities.csv"));
+
+        let spec = table_spec_with_header("city", "cities.csv", vec![col_by_name("name", "Name")]);
+        let err = disk_reader().read_table(&spec, tmp.path()).await.unwrap_err().to_string();
+        assert!(err.contains("not a regular file"), "error was: {}", err);
+    }
+
+    #[tokio::test]
+    async fn read_table_decodes_declared_encoding() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("cities.csv"), b"Name\nS\xe3o Paulo\nZ\xfcrich\n").await.unwrap();
+
+        let mut spec = table_spec_with_header("city", "cities.csv", vec![col_by_name("name", "Name")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.character_encoding = "latin1".to_string();
+        }
+        let table = disk_reader().read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.cell(0, 0), Some("São Paulo"));
+        assert_eq!(table.cell(1, 0), Some("Zürich"));
+    }
+
+    #[tokio::test]
+    async fn read_table_reports_invalid_utf8() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("cities.csv"), b"Name\nS\xe3o Paulo\n").await.unwrap();
+
+        let spec = table_spec_with_header("city", "cities.csv", vec![col_by_name("name", "Name")]);
+        let err = disk_reader().read_table(&spec, tmp.path()).await.unwrap_err();
+        assert!(matches!(err, TableReaderError::ReadError { .. }));
+        assert!(err.to_string().contains("encoding errors while decoding as 'utf-8'"), "error was: {}", err);
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn read_table_decompresses_gzipped_csv_before_decoding() {
+        let tmp = tempfile::tempdir().unwrap();
+        let compressed = gzip(b"Name,Country\nS\xe3o Paulo,Brazil\nZ\xfcrich,Switzerland\n");
+        tokio::fs::write(tmp.path().join("cities.csv.gz"), compressed).await.unwrap();
+
+        let mut spec = table_spec_with_header(
+            "city",
+            "cities.csv.gz",
+            vec![col_by_name("name", "Name"), col_by_name("country", "Country")],
+        );
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.character_encoding = "latin1".to_string();
+        }
+        let table = disk_reader().read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.num_rows(), 2);
+        assert_eq!(table.cell(0, 0), Some("São Paulo"));
+        assert_eq!(table.cell(1, 1), Some("Switzerland"));
+    }
+
+    #[tokio::test]
+    async fn read_table_reports_corrupt_gzip() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("cities.csv.gz"), "Name\nLondon\n").await.unwrap();
+
+        let spec = table_spec_with_header("city", "cities.csv.gz", vec![col_by_name("name", "Name")]);
+        let err = disk_reader().read_table(&spec, tmp.path()).await.unwrap_err();
+        assert!(err.to_string().contains("failed to decompress gzip data"), "error was: {}", err);
+    }
+
+    fn glob_spec(pattern: &str) -> TableSpec {
+        let mut spec = table_spec_with_header("sales", pattern, vec![col_by_name("day", "Day"), col_by_name("amount", "Amount")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.glob = true;
+        }
+        spec
+    }
+
+    #[tokio::test]
+    async fn missing_file_names_absent_sources_but_not_globs() {
+        let reader = make_reader(vec![("/project/data/cities.csv", "Name\nLondon\n")]);
+        let present = table_spec_with_header("city", "data/cities.csv", vec![]);
+        assert_eq!(reader.missing_file(&present, Path::new("/project")).await.unwrap(), None);
+        let absent = table_spec_with_header("city", "data/towns.csv", vec![]);
+        let missing = reader.missing_file(&absent, Path::new("/project")).await.unwrap();
+        assert_eq!(missing, Some(PathBuf::from("/project/data/towns.csv")));
+        assert_eq!(reader.missing_file(&glob_spec("data/none-*.csv"), Path::new("/project")).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn read_table_concatenates_glob_matches_in_sorted_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data = tmp.path().join("data");
+        tokio::fs::create_dir(&data).await.unwrap();
+        tokio::fs::write(data.join("sales-2024-03.csv"), "Day,Amount\n2024-03-01,30\n").await.unwrap();
+        tokio::fs::write(data.join("sales-2024-01.csv"), "Day,Amount\n2024-01-01,10\n2024-01-02,11").await.unwrap();
+        tokio::fs::write(data.join("sales-2024-02.csv"), "Day,Amount\n2024-02-01,20\n").await.unwrap();
+        tokio::fs::write(data.join("other.csv"), "Day,Amount\nnope,0\n").await.unwrap();
+
+        let table = disk_reader().read_table(&glob_spec("data/sales-2024-*.csv"), tmp.path()).await.unwrap();
+        let days: Vec<_> = (0..table.num_rows()).map(|r| table.cell(r, 0).unwrap()).collect();
+        assert_eq!(days, vec!["2024-01-01", "2024-01-02", "2024-02-01", "2024-03-01"]);
+        assert_eq!(table.cell(3, 1), Some("30"));
+    }
+
+    #[tokio::test]
+    async fn read_table_rejects_glob_matches_with_different_headers() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("sales-1.csv"), "Day,Amount\n2024-01-01,10\n").await.unwrap();
+        tokio::fs::write(tmp.path().join("sales-2.csv"), "Amount,Day\n20,2024-02-01\n").await.unwrap();
+
+        let err = disk_reader().read_table(&glob_spec("sales-*.csv"), tmp.path()).await.unwrap_err();
+        assert!(matches!(err, TableReaderError::ReadError { .. }));
+        assert!(err.to_string().contains("does not match header"), "error was: {}", err);
+    }
+
+    #[tokio::test]
+    async fn read_table_errors_when_glob_matches_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = disk_reader().read_table(&glob_spec("sales-*.csv"), tmp.path()).await.unwrap_err();
+        assert!(err.to_string().contains("no files match 'sales-*.csv'"), "error was: {}", err);
+    }
+
+    #[tokio::test]
+    async fn read_table_skips_comment_lines_in_every_glob_match() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("sales-1.csv"), "# day one\nDay,Amount\n2024-01-01,10\n").await.unwrap();
+        tokio::fs::write(tmp.path().join("sales-2.csv"), "# day two\nDay,Amount\n# void\n2024-01-02,20\n").await.unwrap();
+
+        let mut spec = glob_spec("sales-*.csv");
+        spec.source.csv_options_mut().comment = Some('#');
+        let table = disk_reader().read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.rows, vec![vec!["2024-01-01", "10"], vec!["2024-01-02", "20"]]);
+    }
+
     #[test]
-    fn decode_bytes_utf8() {
-        let result = decode_bytes(b"hello", "utf-8").unwrap();
-        assert_eq!(result, "hello");
+    fn concat_csv_contents_keeps_skipped_rows_of_first_file_only() {
+        let options = CsvOptions { skip_rows: 1, ..Default::default() };
+        let contents = vec![
+            ("a".to_string(), "report a\nDay\n1\n".to_string()),
+            ("b".to_string(), "report b\nDay\n2\n".to_string()),
+        ];
+        assert_eq!(concat_csv_contents(&contents, true, &options).unwrap(), "report a\nDay\n1\n2\n");
     }
 
     #[test]
-    fn decode_bytes_unknown_encoding_errors() {
-        let result = decode_bytes(b"hello", "unknown-encoding");
-        assert!(result.is_err());
+    fn concat_csv_contents_without_header_keeps_every_line() {
+        let contents = vec![("a".to_string(), "1\n2".to_string()), ("b".to_string(), "3\n".to_string())];
+        assert_eq!(concat_csv_contents(&contents, false, &CsvOptions::default()).unwrap(), "1\n2\n3\n");
+    }
+
+    #[test]
+    fn can_stream_only_plain_utf8_files() {
+        let spec = |filename: &str, encoding: &str| {
+            let mut spec = table_spec_with_header("t", filename, vec![]);
+            if let SourceSpec::File(fs) = &mut spec.source {
+                fs.character_encoding = encoding.to_string();
+            }
+            spec
+        };
+        let streams = |spec: &TableSpec| can_stream(spec, super::file_source(spec).unwrap());
+        assert!(streams(&spec("data/t.csv", "utf-8")));
+        assert!(streams(&spec("data/t.csv", "UTF8")));
+        assert!(!streams(&spec("data/t.csv", "latin1")));
+        assert!(!streams(&spec("data/t.csv", "auto")));
+        assert!(!streams(&spec("data/t.csv.gz", "utf-8")));
+        let mut raw = spec("data/t.csv", "utf-8");
+        raw.keep_raw_line = true;
+        assert!(!streams(&raw));
+    }
+
+    #[tokio::test]
+    async fn read_rows_streams_the_same_rows_as_read_table() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut content = String::from("\u{feff}Name,Country\n");
+        for i in 0..5000 {
+            content.push_str(&format!("city {i},\"country, {i}\"\n"));
+        }
+        tokio::fs::write(tmp.path().join("cities.csv"), &content).await.unwrap();
+
+        let spec = table_spec_with_header("city", "cities.csv", vec![col_by_name("country", "Country"), col_by_name("name", "Name")]);
+        let table = disk_reader().read_table(&spec, tmp.path()).await.unwrap();
+        let mut rows = Vec::new();
+        let count = disk_reader().read_rows(&spec, tmp.path(), &mut |row| rows.push(row)).await.unwrap();
+        assert_eq!(count, 5000);
+        assert_eq!(rows, table.rows);
+        assert_eq!(rows[4999], vec!["country, 4999", "city 4999"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn read_rows_hands_out_rows_before_the_file_is_complete() {
+        use std::io::Write;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let fifo = tmp.path().join("cities.csv");
+        make_fifo(&fifo);
+
+        // the writer only finishes the file once the first row has been handed out,
+        // which cannot happen if the whole file is read before parsing
+        let (first_row_tx, first_row_rx) = mpsc::channel::<()>();
+        let writer_path = fifo.clone();
+        let writer = std::thread::spawn(move || {
+            let mut file = std::fs::OpenOptions::new().write(true).open(writer_path).unwrap();
+            file.write_all(b"Name\nLondon\n").unwrap();
+            file.flush().unwrap();
+            let streamed = first_row_rx.recv_timeout(Duration::from_secs(5)).is_ok();
+            file.write_all(b"Berlin\n").unwrap();
+            streamed
+        });
+
+        let mut spec = table_spec_with_header("city", "cities.csv", vec![col_by_name("name", "Name")]);
+        if let SourceSpec::File(fs) = &mut spec.source {
+            fs.allow_special_files = true;
+        }
+        let mut rows = Vec::new();
+        disk_reader()
+            .read_rows(&spec, tmp.path(), &mut |row| {
+                let _ = first_row_tx.send(());
+                rows.push(row);
+            })
+            .await
+            .unwrap();
+        assert!(writer.join().unwrap(), "the first row was not handed out before the file was complete");
+        assert_eq!(rows, vec![vec!["London"], vec!["Berlin"]]);
     }
 }
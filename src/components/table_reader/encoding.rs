@@ -0,0 +1,70 @@
+use encoding_rs::Encoding;
+use crate::models::AUTO_CHARACTER_ENCODING;
+
+/// Guesses the encoding of `bytes`: a byte order mark wins, then UTF-8 if the
+/// bytes are valid UTF-8, otherwise windows-1252.
+pub fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        encoding_rs::UTF_8
+    } else {
+        encoding_rs::WINDOWS_1252
+    }
+}
+
+pub fn decode_bytes(bytes: &[u8], encoding_label: &str) -> Result<String, String> {
+    let encoding = if encoding_label.eq_ignore_ascii_case(AUTO_CHARACTER_ENCODING) {
+        detect_encoding(bytes)
+    } else {
+        Encoding::for_label(encoding_label.as_bytes())
+            .ok_or_else(|| format!("unsupported encoding: '{}'", encoding_label))?
+    };
+    let (cow, _, had_errors) = encoding.decode(bytes);
+    if had_errors {
+        return Err(format!("encoding errors while decoding as '{}'", encoding_label));
+    }
+    Ok(cow.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_bytes_utf8() {
+        let result = decode_bytes(b"hello", "utf-8").unwrap();
+        assert_eq!(result, "hello");
+    }
+
+    #[test]
+    fn decode_bytes_latin1() {
+        let result = decode_bytes(b"Z\xfcrich", "latin1").unwrap();
+        assert_eq!(result, "Zürich");
+    }
+
+    #[test]
+    fn decode_bytes_invalid_utf8_errors() {
+        let result = decode_bytes(b"Z\xfcrich", "utf-8");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn decode_bytes_auto_detects_utf8_and_windows_1252() {
+        assert_eq!(decode_bytes("Zürich".as_bytes(), "auto").unwrap(), "Zürich");
+        assert_eq!(decode_bytes(b"Z\xfcrich \x80", "auto").unwrap(), "Zürich €");
+    }
+
+    #[test]
+    fn decode_bytes_auto_honours_bom() {
+        assert_eq!(detect_encoding(b"\xff\xfeZ\x00"), encoding_rs::UTF_16LE);
+        assert_eq!(decode_bytes(b"\xef\xbb\xbfhi", "AUTO").unwrap(), "hi");
+    }
+
+    #[test]
+    fn decode_bytes_unknown_encoding_errors() {
+        let result = decode_bytes(b"hello", "unknown-encoding");
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use crate::models::TableSpec;
+use crate::traits::table_reader::TableReaderError;
+
+/// Replaces every `${VAR}` in `value` with the result of `lookup`. Unset variables are an error
+/// so that a missing secret fails the read instead of opening the source with an empty value.
+/// `${VAR:-default}` falls back to `default` when `VAR` is unset or empty, and `$$` is a literal `$`.
+pub fn interpolate_env(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(after) = after.strip_prefix('$') {
+            result.push('$');
+            rest = after;
+            continue;
+        }
+        let Some(after) = after.strip_prefix('{') else {
+            result.push('$');
+            rest = after;
+            continue;
+        };
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unterminated '${{' in '{value}'"))?;
+        let resolved = match after[..end].split_once(":-") {
+            Some((name, default)) => lookup(name).filter(|v| !v.is_empty()).unwrap_or_else(|| default.to_string()),
+            None => {
+                let name = &after[..end];
+                lookup(name).ok_or_else(|| format!("environment variable '{name}' is not set"))?
+            }
+        };
+        result.push_str(&resolved);
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Expands `${VAR}` in one source field from the process environment. The project keeps the
+/// reference as written, so this runs when the source is opened rather than when it is parsed.
+pub fn expand_env(table: &TableSpec, field: &str, value: &str) -> Result<String, TableReaderError> {
+    interpolate_env(value, |name| std::env::var(name).ok()).map_err(|msg| TableReaderError::ReadError {
+        table_name: table.name.clone(),
+        message: format!("{field}: {msg}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolate_env_replaces_variables() {
+        let lookup = |name: &str| match name {
+            "USER" => Some("alice".to_string()),
+            "TOKEN" => Some("s3cret".to_string()),
+            _ => None,
+        };
+        assert_eq!(interpolate_env("${USER}:${TOKEN}@db", lookup), Ok("alice:s3cret@db".to_string()));
+        assert_eq!(interpolate_env("plain $USER", lookup), Ok("plain $USER".to_string()));
+    }
+
+    #[test]
+    fn interpolate_env_rejects_unset_and_unterminated() {
+        let lookup = |_: &str| None;
+        assert_eq!(interpolate_env("${MISSING}", lookup), Err("environment variable 'MISSING' is not set".to_string()));
+        assert!(interpolate_env("${OPEN", lookup).unwrap_err().contains("unterminated"));
+    }
+
+    #[test]
+    fn interpolate_env_supports_defaults_and_escapes() {
+        let lookup = |name: &str| match name {
+            "REGION" => Some("eu".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        assert_eq!(interpolate_env("${REGION:-us}/${MISSING:-us}/${EMPTY:-x}", lookup), Ok("eu/us/x".to_string()));
+        assert_eq!(interpolate_env("cost $$5 and $${REGION}", lookup), Ok("cost $5 and ${REGION}".to_string()));
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::models::{HttpSourceSpec, SourceSpec, Table, TableSpec};
+use crate::traits::{Logger, CsvParser, RowCallback};
+use crate::traits::table_reader::{TableReader, TableReaderError};
+use super::encoding::decode_bytes;
+use super::env::expand_env;
+
+/// Reads CSV tables from the body of a GET request.
+pub struct HttpCsvTableReader {
+    logger: Box<dyn Logger>,
+    csv_parser: Box<dyn CsvParser>,
+    client: reqwest::Client,
+}
+
+impl HttpCsvTableReader {
+    pub fn new(logger: Box<dyn Logger>, csv_parser: Box<dyn CsvParser>) -> Self {
+        HttpCsvTableReader { logger, csv_parser, client: reqwest::Client::new() }
+    }
+
+    async fn fetch(&self, table: &TableSpec, http_source: &HttpSourceSpec) -> Result<String, TableReaderError> {
+        let read_error = |message: String| TableReaderError::ReadError {
+            table_name: table.name.clone(),
+            message: format!("GET {}: {}", http_source.url, message),
+        };
+        self.logger.debug(&format!("fetching CSV from: {}", http_source.url)).await;
+
+        let url = expand_env(table, "url", &http_source.url)?;
+        let mut request = self.client.get(&url);
+        if let Some(seconds) = http_source.timeout_seconds {
+            request = request.timeout(Duration::from_secs(seconds));
+        }
+        let response = request.send().await.map_err(|e| read_error(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(read_error(format!("server responded with status {}", status.as_u16())));
+        }
+        let bytes = response.bytes().await.map_err(|e| read_error(e.to_string()))?;
+        decode_bytes(&bytes, &http_source.character_encoding).map_err(read_error)
+    }
+
+    async fn read_content(&self, table: &TableSpec) -> Result<String, TableReaderError> {
+        match &table.source {
+            SourceSpec::Http(hs) => self.fetch(table, hs).await,
+            SourceSpec::File(_) | SourceSpec::Cmd(_) | SourceSpec::Stdin(_) => Err(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: "HttpCsvTableReader only supports http sources".to_string(),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl TableReader for HttpCsvTableReader {
+    fn name(&self) -> &str {
+        "http_csv"
+    }
+
+    fn can_read(&self, table: &TableSpec) -> bool {
+        matches!(&table.source, SourceSpec::Http(_))
+    }
+
+    async fn read_table(&self, table: &TableSpec, _project_dir: &Path) -> Result<Table, TableReaderError> {
+        let content = self.read_content(table).await?;
+        let result = self.csv_parser.parse(&content, table).await?;
+
+        self.logger.info(&format!(
+            "read table '{}' using reader '{}': {} rows, {} columns",
+            table.name,
+            self.name(),
+            result.num_rows(),
+            result.num_columns(),
+        )).await;
+
+        Ok(result)
+    }
+
+    async fn read_rows(
+        &self,
+        table: &TableSpec,
+        _project_dir: &Path,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, TableReaderError> {
+        let content = self.read_content(table).await?;
+        Ok(self.csv_parser.parse_rows(&content, table, on_row).await?)
+    }
+
+    async fn column_indices(&self, table: &TableSpec, _project_dir: &Path) -> Result<Option<Vec<usize>>, TableReaderError> {
+        let content = self.read_content(table).await?;
+        Ok(Some(self.csv_parser.column_indices(&content, table).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use crate::components::csv_parser::CsvParserImpl;
+    use crate::components::test_helpers::TestLogger;
+    use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType};
+
+    /// Answers a single request on a local port with `status` and `body`, returning the URL to fetch.
+    async fn serve_once(status: &'static str, body: &'static [u8]) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let head = format!("HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+            socket.write_all(head.as_bytes()).await.unwrap();
+            socket.write_all(body).await.unwrap();
+        });
+        format!("http://{addr}/cities.csv")
+    }
+
+    fn reader() -> HttpCsvTableReader {
+        HttpCsvTableReader::new(Box::new(TestLogger), Box::new(CsvParserImpl::new(Box::new(TestLogger))))
+    }
+
+    fn http_table(url: &str, character_encoding: &str) -> TableSpec {
+        TableSpec {
+            name: "city".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Http(HttpSourceSpec {
+                url: url.to_string(),
+                character_encoding: character_encoding.to_string(),
+                csv: Default::default(),
+                timeout_seconds: Some(5),
+            }),
+            columns: vec![ColumnSpec {
+                name: "name".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("Name".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn read_table_parses_decoded_response_body() {
+        let url = serve_once("200 OK", b"Country,Name\nBrazil,S\xe3o Paulo\nSwitzerland,Z\xfcrich\n").await;
+        let table = reader().read_table(&http_table(&url, "latin1"), Path::new(".")).await.unwrap();
+        assert_eq!(table.num_rows(), 2);
+        assert_eq!(table.cell(0, 0), Some("São Paulo"));
+        assert_eq!(table.cell(1, 0), Some("Zürich"));
+    }
+
+    #[tokio::test]
+    async fn read_table_reports_non_success_status() {
+        let url = serve_once("404 Not Found", b"missing").await;
+        let err = reader().read_table(&http_table(&url, "utf-8"), Path::new(".")).await.unwrap_err();
+        assert!(matches!(err, TableReaderError::ReadError { .. }));
+        assert!(err.to_string().contains("status 404"), "error was: {err}");
+    }
+}
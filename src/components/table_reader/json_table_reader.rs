@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use serde_json::Value;
+use crate::models::{ColumnIdentifier, SourceSpec, Table, TableSpec};
+use crate::traits::{Logger, FileSystem};
+use crate::traits::table_reader::{TableReader, TableReaderError};
+use crate::components::csv_parser::csv_parser_impl::{dedup_message, dedup_rows, filter_rows};
+use super::csv_table_reader::{check_inside_project, missing_file_source};
+use super::env::expand_env;
+use super::encoding::decode_bytes;
+
+pub struct JsonTableReader {
+    logger: Box<dyn Logger>,
+    file_system: Box<dyn FileSystem>,
+    allow_outside_project: bool,
+}
+
+impl JsonTableReader {
+    pub fn new(logger: Box<dyn Logger>, file_system: Box<dyn FileSystem>, allow_outside_project: bool) -> Self {
+        JsonTableReader { logger, file_system, allow_outside_project }
+    }
+}
+
+fn json_value_to_cell(value: &Value) -> Result<String, String> {
+    match value {
+        Value::Null => Ok(String::new()),
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::String(s) => Ok(s.clone()),
+        Value::Array(_) | Value::Object(_) => Err("nested arrays and objects are not supported".to_string()),
+    }
+}
+
+/// Converts a JSON array of flat objects into rows ordered like the table's
+/// columns. A key that is missing from an object yields an empty cell.
+pub fn json_to_rows(content: &str, table: &TableSpec) -> Result<Vec<Vec<String>>, String> {
+    let keys = table
+        .columns
+        .iter()
+        .map(|col| match &col.column_identifier {
+            ColumnIdentifier::Name(name) => Ok(name.as_str()),
+            ColumnIdentifier::Index(_) => Err(format!(
+                "column '{}' uses an index identifier but JSON sources require named columns",
+                col.name
+            )),
+            ColumnIdentifier::Capture { .. } => Err(format!(
+                "column '{}' uses a capture identifier, which JSON sources do not support",
+                col.name
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("invalid JSON: {e}"))?;
+    let items = value.as_array().ok_or("expected a JSON array of objects")?;
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(row_index, item)| {
+            let object = item
+                .as_object()
+                .ok_or_else(|| format!("element {row_index} is not an object"))?;
+            keys.iter()
+                .map(|key| match object.get(*key) {
+                    None => Ok(String::new()),
+                    Some(value) => json_value_to_cell(value)
+                        .map_err(|e| format!("element {row_index}, key '{key}': {e}")),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[async_trait]
+impl TableReader for JsonTableReader {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[".json"]
+    }
+
+    fn can_read(&self, table: &TableSpec) -> bool {
+        match &table.source {
+            SourceSpec::File(fs) => fs.filename.to_lowercase().ends_with(".json"),
+            SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => false,
+        }
+    }
+
+    async fn read_table(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
+        let file_source = match &table.source {
+            SourceSpec::File(fs) => fs,
+            SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => {
+                return Err(TableReaderError::ReadError {
+                    table_name: table.name.clone(),
+                    message: "JsonTableReader only supports file sources".to_string(),
+                });
+            }
+        };
+
+        let path = project_dir.join(expand_env(table, "filename", &file_source.filename)?);
+        self.logger.debug(&format!("reading JSON file: {}", path.display())).await;
+
+        check_inside_project(self.file_system.as_ref(), table, &path, project_dir, self.allow_outside_project).await?;
+        if !file_source.allow_special_files && self.file_system.is_special_file(&path).await? {
+            return Err(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: format!(
+                    "'{}' is not a regular file; set allowSpecialFiles to read from it",
+                    path.display()
+                ),
+            });
+        }
+
+        let bytes = self.file_system.load_bytes(&path).await?;
+        let to_read_error = |message: String| TableReaderError::ReadError {
+            table_name: table.name.clone(),
+            message: format!("{}: {}", path.display(), message),
+        };
+        let content = decode_bytes(&bytes, &file_source.character_encoding).map_err(to_read_error)?;
+        let mut rows = json_to_rows(&content, table).map_err(to_read_error)?;
+        filter_rows(table, &mut rows)?;
+        let duplicates = dedup_rows(table, &mut rows)?;
+        if duplicates > 0 {
+            self.logger.info(&dedup_message(table, duplicates)).await;
+        }
+        if let Some(limit) = table.source.csv_options().limit {
+            rows.truncate(limit);
+        }
+
+        let result = Table::from_spec(table, rows);
+
+        self.logger.info(&format!(
+            "read table '{}' using reader '{}': {} rows, {} columns",
+            table.name,
+            self.name(),
+            result.num_rows(),
+            result.num_columns(),
+        )).await;
+
+        Ok(result)
+    }
+
+    async fn missing_file(&self, table: &TableSpec, project_dir: &Path) -> Result<Option<PathBuf>, TableReaderError> {
+        missing_file_source(self.file_system.as_ref(), table, project_dir, self.allow_outside_project).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::test_helpers::{InMemoryFileSystem, TestLogger, column_spec};
+    use crate::models::{ColumnSpec, ColumnType, FileSourceSpec};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    fn make_reader(files: Vec<(&str, &str)>) -> JsonTableReader {
+        let map = files
+            .into_iter()
+            .map(|(path, content)| (std::path::PathBuf::from(path), content.to_string()))
+            .collect();
+        JsonTableReader::new(
+            Box::new(TestLogger),
+            Box::new(InMemoryFileSystem::new(Arc::new(Mutex::new(map)))),
+            false,
+        )
+    }
+
+    fn column(name: &str, identifier: ColumnIdentifier) -> ColumnSpec {
+        column_spec(name, identifier, ColumnType::String { max_length: None })
+    }
+
+    fn table_spec(filename: &str, columns: Vec<ColumnSpec>) -> TableSpec {
+        TableSpec {
+            name: "city".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: filename.to_string(),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns,
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    fn city_columns() -> Vec<ColumnSpec> {
+        vec![
+            column("population", ColumnIdentifier::Name("Population".to_string())),
+            column("name", ColumnIdentifier::Name("Name".to_string())),
+        ]
+    }
+
+    #[test]
+    fn can_read_json_extension_only() {
+        let reader = make_reader(vec![]);
+        assert!(reader.can_read(&table_spec("data/cities.JSON", vec![])));
+        assert!(!reader.can_read(&table_spec("data/cities.csv", vec![])));
+    }
+
+    #[tokio::test]
+    async fn read_table_maps_keys_in_column_order() {
+        let reader = make_reader(vec![(
+            "/project/data/cities.json",
+            r#"[{"Name": "London", "Population": 8982000, "Capital": true}, {"Name": "Berlin", "Population": null}]"#,
+        )]);
+        let table = reader
+            .read_table(&table_spec("data/cities.json", city_columns()), Path::new("/project"))
+            .await
+            .unwrap();
+        assert_eq!(table.headers(), &["population", "name"]);
+        assert_eq!(table.row(0), Some(["8982000".to_string(), "London".to_string()].as_slice()));
+        assert_eq!(table.row(1), Some(["".to_string(), "Berlin".to_string()].as_slice()));
+    }
+
+    #[test]
+    fn json_to_rows_missing_key_is_empty() {
+        let rows = json_to_rows(r#"[{"Name": "London"}]"#, &table_spec("c.json", city_columns())).unwrap();
+        assert_eq!(rows, vec![vec!["".to_string(), "London".to_string()]]);
+    }
+
+    #[test]
+    fn json_to_rows_rejects_nested_objects() {
+        let err = json_to_rows(
+            r#"[{"Name": {"en": "London"}, "Population": 1}]"#,
+            &table_spec("c.json", city_columns()),
+        )
+        .unwrap_err();
+        assert_eq!(err, "element 0, key 'Name': nested arrays and objects are not supported");
+    }
+
+    #[test]
+    fn json_to_rows_rejects_index_identifiers() {
+        let spec = table_spec("c.json", vec![column("name", ColumnIdentifier::Index(0))]);
+        let err = json_to_rows("[]", &spec).unwrap_err();
+        assert!(err.contains("JSON sources require named columns"), "error was: {err}");
+    }
+
+    #[test]
+    fn json_to_rows_rejects_non_array() {
+        let err = json_to_rows(r#"{"Name": "London"}"#, &table_spec("c.json", city_columns())).unwrap_err();
+        assert_eq!(err, "expected a JSON array of objects");
+    }
+}
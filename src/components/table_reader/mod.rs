@@ -1,5 +1,17 @@
+pub mod encoding;
+pub mod env;
 pub mod csv_table_reader;
 pub mod cmd_csv_table_reader;
+pub mod json_table_reader;
+pub mod http_csv_table_reader;
+pub mod parquet_table_reader;
+pub mod xlsx_table_reader;
+pub mod stdin_csv_table_reader;
 
 pub use csv_table_reader::CsvTableReader;
-pub use cmd_csv_table_reader::CmdCsvTableReader;
+pub use cmd_csv_table_reader::{CmdCsvTableReader, DEFAULT_MAX_CMD_PROCESSES};
+pub use json_table_reader::JsonTableReader;
+pub use http_csv_table_reader::HttpCsvTableReader;
+pub use parquet_table_reader::ParquetTableReader;
+pub use xlsx_table_reader::XlsxTableReader;
+pub use stdin_csv_table_reader::StdinCsvTableReader;
@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use async_trait::async_trait;
+use bytes::Bytes;
+use parquet::arrow::ProjectionMask;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use crate::components::csv_parser::csv_parser_impl::{dedup_message, dedup_rows, filter_rows};
+use crate::models::{ColumnIdentifier, SourceSpec, Table, TableSpec};
+use super::csv_table_reader::{check_inside_project, missing_file_source};
+use super::env::expand_env;
+use crate::traits::{Logger, FileSystem};
+use crate::traits::table_reader::{TableReader, TableReaderError};
+
+pub struct ParquetTableReader {
+    logger: Box<dyn Logger>,
+    file_system: Box<dyn FileSystem>,
+    allow_outside_project: bool,
+}
+
+impl ParquetTableReader {
+    pub fn new(logger: Box<dyn Logger>, file_system: Box<dyn FileSystem>, allow_outside_project: bool) -> Self {
+        ParquetTableReader { logger, file_system, allow_outside_project }
+    }
+}
+
+/// Reads the columns a table names from a Parquet file into rows ordered like the
+/// table's columns. Nulls become empty cells, other values their display form.
+pub fn parquet_to_rows(content: Bytes, table: &TableSpec) -> Result<Vec<Vec<String>>, String> {
+    let names = table
+        .columns
+        .iter()
+        .map(|col| match &col.column_identifier {
+            ColumnIdentifier::Name(name) => Ok(name.as_str()),
+            ColumnIdentifier::Index(_) => Err(format!(
+                "column '{}' uses an index identifier but Parquet sources require named columns",
+                col.name
+            )),
+            ColumnIdentifier::Capture { .. } => Err(format!(
+                "column '{}' uses a capture identifier, which Parquet sources do not support",
+                col.name
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut builder = ParquetRecordBatchReaderBuilder::try_new(content).map_err(|e| format!("invalid Parquet file: {e}"))?;
+    let schema = builder.schema().clone();
+    let mut roots = Vec::with_capacity(names.len());
+    for name in &names {
+        let index = schema
+            .index_of(name)
+            .map_err(|_| format!("column '{name}' not found in the Parquet schema"))?;
+        let data_type = schema.field(index).data_type();
+        if data_type.is_nested() {
+            return Err(format!("column '{name}' has nested type {data_type}, which is not supported"));
+        }
+        roots.push(index);
+    }
+    let mask = ProjectionMask::roots(builder.parquet_schema(), roots);
+    builder = builder.with_projection(mask);
+    // with a filter or dedup, the limit counts the rows kept and is applied by the caller
+    if let Some(limit) = table.source.csv_options().limit
+        && table.filter.is_none()
+        && table.dedup_by.is_empty()
+    {
+        builder = builder.with_limit(limit);
+    }
+    let reader = builder.build().map_err(|e| e.to_string())?;
+
+    let options = FormatOptions::default();
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|e| e.to_string())?;
+        let formatters = names
+            .iter()
+            .map(|name| {
+                let array = batch.column_by_name(name).ok_or_else(|| format!("column '{name}' missing from batch"))?;
+                ArrayFormatter::try_new(array.as_ref(), &options).map_err(|e| format!("column '{name}': {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for row in 0..batch.num_rows() {
+            rows.push(formatters.iter().map(|f| f.value(row).to_string()).collect());
+        }
+    }
+    Ok(rows)
+}
+
+#[async_trait]
+impl TableReader for ParquetTableReader {
+    fn name(&self) -> &str {
+        "parquet"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[".parquet"]
+    }
+
+    fn can_read(&self, table: &TableSpec) -> bool {
+        match &table.source {
+            SourceSpec::File(fs) => fs.filename.to_lowercase().ends_with(".parquet"),
+            SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => false,
+        }
+    }
+
+    async fn read_table(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
+        let file_source = match &table.source {
+            SourceSpec::File(fs) => fs,
+            SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => {
+                return Err(TableReaderError::ReadError {
+                    table_name: table.name.clone(),
+                    message: "ParquetTableReader only supports file sources".to_string(),
+                });
+            }
+        };
+
+        let path = project_dir.join(expand_env(table, "filename", &file_source.filename)?);
+        self.logger.debug(&format!("reading Parquet file: {}", path.display())).await;
+
+        check_inside_project(self.file_system.as_ref(), table, &path, project_dir, self.allow_outside_project).await?;
+        if !file_source.allow_special_files && self.file_system.is_special_file(&path).await? {
+            return Err(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: format!(
+                    "'{}' is not a regular file; set allowSpecialFiles to read from it",
+                    path.display()
+                ),
+            });
+        }
+
+        let bytes = self.file_system.load_bytes(&path).await?;
+        let mut rows = parquet_to_rows(Bytes::from(bytes), table).map_err(|message| TableReaderError::ReadError {
+            table_name: table.name.clone(),
+            message: format!("{}: {}", path.display(), message),
+        })?;
+        filter_rows(table, &mut rows)?;
+        let duplicates = dedup_rows(table, &mut rows)?;
+        if duplicates > 0 {
+            self.logger.info(&dedup_message(table, duplicates)).await;
+        }
+        if let Some(limit) = table.source.csv_options().limit {
+            rows.truncate(limit);
+        }
+
+        let result = Table::from_spec(table, rows);
+
+        self.logger.info(&format!(
+            "read table '{}' using reader '{}': {} rows, {} columns",
+            table.name,
+            self.name(),
+            result.num_rows(),
+            result.num_columns(),
+        )).await;
+
+        Ok(result)
+    }
+
+    async fn missing_file(&self, table: &TableSpec, project_dir: &Path) -> Result<Option<PathBuf>, TableReaderError> {
+        missing_file_source(self.file_system.as_ref(), table, project_dir, self.allow_outside_project).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+    use parquet::arrow::ArrowWriter;
+    use crate::components::file_system::DiskFileSystem;
+    use crate::components::test_helpers::{TestLogger, column_spec};
+    use crate::models::{ColumnSpec, ColumnType, FileSourceSpec};
+
+    fn make_reader() -> ParquetTableReader {
+        ParquetTableReader::new(Box::new(TestLogger), Box::new(DiskFileSystem::new(Box::new(TestLogger))), false)
+    }
+
+    fn column(name: &str, identifier: ColumnIdentifier) -> ColumnSpec {
+        column_spec(name, identifier, ColumnType::String { max_length: None })
+    }
+
+    fn table_spec(filename: &str, columns: Vec<ColumnSpec>) -> TableSpec {
+        TableSpec {
+            name: "city".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: filename.to_string(),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns,
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    fn cities_parquet() -> Vec<u8> {
+        let batch = RecordBatch::try_from_iter([
+            ("Name", Arc::new(StringArray::from(vec!["London", "Berlin"])) as ArrayRef),
+            ("Population", Arc::new(Int64Array::from(vec![Some(8982000), None])) as ArrayRef),
+        ])
+        .unwrap();
+        let mut out = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut out, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        out
+    }
+
+    #[test]
+    fn can_read_parquet_extension_only() {
+        let reader = make_reader();
+        assert!(reader.can_read(&table_spec("data/cities.PARQUET", vec![])));
+        assert!(!reader.can_read(&table_spec("data/cities.csv", vec![])));
+    }
+
+    #[tokio::test]
+    async fn read_table_maps_columns_in_spec_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("cities.parquet"), cities_parquet()).await.unwrap();
+        let columns = vec![
+            column("population", ColumnIdentifier::Name("Population".to_string())),
+            column("name", ColumnIdentifier::Name("Name".to_string())),
+        ];
+        let table = make_reader().read_table(&table_spec("cities.parquet", columns), tmp.path()).await.unwrap();
+        assert_eq!(table.headers(), &["population", "name"]);
+        assert_eq!(table.row(0), Some(["8982000".to_string(), "London".to_string()].as_slice()));
+        assert_eq!(table.row(1), Some(["".to_string(), "Berlin".to_string()].as_slice()));
+    }
+
+    #[tokio::test]
+    async fn read_table_rejects_traversal_outside_the_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project_dir = tmp.path().join("project");
+        tokio::fs::create_dir(&project_dir).await.unwrap();
+        tokio::fs::write(tmp.path().join("cities.parquet"), cities_parquet()).await.unwrap();
+        let spec = table_spec("../cities.parquet", vec![column("name", ColumnIdentifier::Name("Name".to_string()))]);
+        let err = make_reader().read_table(&spec, &project_dir).await.unwrap_err();
+        assert!(err.to_string().contains("is outside the project directory"), "error was: {err}");
+    }
+
+    #[test]
+    fn parquet_to_rows_rejects_index_identifiers() {
+        let spec = table_spec("c.parquet", vec![column("name", ColumnIdentifier::Index(0))]);
+        let err = parquet_to_rows(Bytes::from(cities_parquet()), &spec).unwrap_err();
+        assert!(err.contains("Parquet sources require named columns"), "error was: {err}");
+    }
+
+    #[test]
+    fn parquet_to_rows_reports_unknown_column() {
+        let spec = table_spec("c.parquet", vec![column("country", ColumnIdentifier::Name("Country".to_string()))]);
+        let err = parquet_to_rows(Bytes::from(cities_parquet()), &spec).unwrap_err();
+        assert_eq!(err, "column 'Country' not found in the Parquet schema");
+    }
+}
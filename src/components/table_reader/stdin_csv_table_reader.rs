@@ -0,0 +1,152 @@
+use std::io::Read;
+use std::path::Path;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use crate::models::{SourceSpec, StdinSourceSpec, Table, TableSpec};
+use crate::traits::{Logger, CsvParser, RowCallback};
+use crate::traits::table_reader::{TableReader, TableReaderError};
+use super::encoding::decode_bytes;
+
+/// Reads a CSV table from standard input. The input can only be read once.
+pub struct StdinCsvTableReader {
+    logger: Box<dyn Logger>,
+    csv_parser: Box<dyn CsvParser>,
+    input: Mutex<Option<Box<dyn Read + Send>>>,
+}
+
+impl StdinCsvTableReader {
+    pub fn new(logger: Box<dyn Logger>, csv_parser: Box<dyn CsvParser>) -> Self {
+        Self::with_input(logger, csv_parser, Box::new(std::io::stdin()))
+    }
+
+    /// Reads from `input` instead of standard input.
+    pub fn with_input(logger: Box<dyn Logger>, csv_parser: Box<dyn CsvParser>, input: Box<dyn Read + Send>) -> Self {
+        StdinCsvTableReader { logger, csv_parser, input: Mutex::new(Some(input)) }
+    }
+
+    async fn read_stdin(&self, table: &TableSpec, stdin_source: &StdinSourceSpec) -> Result<String, TableReaderError> {
+        let read_error = |message: String| TableReaderError::ReadError {
+            table_name: table.name.clone(),
+            message: format!("standard input: {message}"),
+        };
+        let input = self.input.lock().unwrap().take().ok_or_else(|| read_error("already read by another table".to_string()))?;
+        self.logger.debug(&format!("reading table '{}' from standard input", table.name)).await;
+
+        let bytes = tokio::task::spawn_blocking(move || {
+            let mut bytes = Vec::new();
+            let mut input = input;
+            input.read_to_end(&mut bytes).map(|_| bytes)
+        })
+        .await
+        .map_err(|e| read_error(e.to_string()))?
+        .map_err(|e| read_error(e.to_string()))?;
+        decode_bytes(&bytes, &stdin_source.character_encoding).map_err(read_error)
+    }
+
+    async fn read_content(&self, table: &TableSpec) -> Result<String, TableReaderError> {
+        match &table.source {
+            SourceSpec::Stdin(ss) => self.read_stdin(table, ss).await,
+            SourceSpec::File(_) | SourceSpec::Cmd(_) | SourceSpec::Http(_) => Err(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: "StdinCsvTableReader only supports stdin sources".to_string(),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl TableReader for StdinCsvTableReader {
+    fn name(&self) -> &str {
+        "stdin_csv"
+    }
+
+    fn can_read(&self, table: &TableSpec) -> bool {
+        matches!(&table.source, SourceSpec::Stdin(_))
+    }
+
+    async fn read_table(&self, table: &TableSpec, _project_dir: &Path) -> Result<Table, TableReaderError> {
+        let content = self.read_content(table).await?;
+        let result = self.csv_parser.parse(&content, table).await?;
+
+        self.logger.info(&format!(
+            "read table '{}' using reader '{}': {} rows, {} columns",
+            table.name,
+            self.name(),
+            result.num_rows(),
+            result.num_columns(),
+        )).await;
+
+        Ok(result)
+    }
+
+    async fn read_rows(
+        &self,
+        table: &TableSpec,
+        _project_dir: &Path,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, TableReaderError> {
+        let content = self.read_content(table).await?;
+        Ok(self.csv_parser.parse_rows(&content, table, on_row).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::components::csv_parser::CsvParserImpl;
+    use crate::components::test_helpers::TestLogger;
+    use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType};
+
+    fn reader(input: &'static [u8]) -> StdinCsvTableReader {
+        StdinCsvTableReader::with_input(
+            Box::new(TestLogger),
+            Box::new(CsvParserImpl::new(Box::new(TestLogger))),
+            Box::new(Cursor::new(input)),
+        )
+    }
+
+    fn stdin_table(character_encoding: &str) -> TableSpec {
+        TableSpec {
+            name: "employee".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::Stdin(StdinSourceSpec {
+                character_encoding: character_encoding.to_string(),
+                csv: Default::default(),
+            }),
+            columns: vec![ColumnSpec {
+                name: "name".to_string(),
+                description: String::new(),
+                column_identifier: ColumnIdentifier::Name("Name".to_string()),
+                column_type: ColumnType::String { max_length: None },
+                empty_is_null: true,
+                primary_key: false,
+                unique: false,
+                ignore_header_case: false,
+                aliases: vec![],
+            }],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn read_table_parses_decoded_input() {
+        let reader = reader(b"Office,Name\nStar Tower,J\xfcrgen\n");
+        let table = reader.read_table(&stdin_table("latin1"), Path::new(".")).await.unwrap();
+        assert_eq!(table.num_rows(), 1);
+        assert_eq!(table.cell(0, 0), Some("Jürgen"));
+    }
+
+    #[tokio::test]
+    async fn input_can_only_be_read_once() {
+        let reader = reader(b"Name\nAlice\n");
+        reader.read_table(&stdin_table("utf-8"), Path::new(".")).await.unwrap();
+        let err = reader.read_table(&stdin_table("utf-8"), Path::new(".")).await.unwrap_err();
+        assert!(err.to_string().contains("already read"), "error was: {err}");
+    }
+}
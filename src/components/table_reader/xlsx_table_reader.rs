@@ -0,0 +1,273 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use calamine::{Data, Reader, open_workbook_auto_from_rs};
+use crate::components::csv_parser::csv_parser_impl::{
+    apply_captures, build_header_map, compile_captures, compile_filter, dedup_message, dedup_rows, matched_aliases,
+    passes_filter, resolve_column_indices,
+};
+use crate::models::{SourceSpec, Table, TableSpec};
+use super::csv_table_reader::{check_inside_project, missing_file_source};
+use super::env::expand_env;
+use crate::traits::{Logger, FileSystem};
+use crate::traits::table_reader::{TableReader, TableReaderError};
+
+pub struct XlsxTableReader {
+    logger: Box<dyn Logger>,
+    file_system: Box<dyn FileSystem>,
+    allow_outside_project: bool,
+}
+
+impl XlsxTableReader {
+    pub fn new(logger: Box<dyn Logger>, file_system: Box<dyn FileSystem>, allow_outside_project: bool) -> Self {
+        XlsxTableReader { logger, file_system, allow_outside_project }
+    }
+}
+
+/// The text of a cell. Numbers are written in plain decimal, never in scientific notation,
+/// and dates without a time of day as just the date.
+pub fn cell_to_string(cell: &Data) -> Result<String, String> {
+    match cell {
+        Data::Empty => Ok(String::new()),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => Ok(s.clone()),
+        Data::Int(i) => Ok(i.to_string()),
+        Data::Float(f) => Ok(f.to_string()),
+        Data::Bool(b) => Ok(b.to_string()),
+        Data::DateTime(dt) => Ok(match dt.as_datetime() {
+            Some(datetime) if dt.is_datetime() && dt.as_f64().fract() == 0.0 => datetime.date().to_string(),
+            Some(datetime) if dt.is_datetime() => datetime.to_string(),
+            _ => dt.to_string(),
+        }),
+        Data::Error(e) => Err(format!("cell holds the error {e}")),
+    }
+}
+
+/// Every row of `sheet`, or of the first sheet when `None`, as text cells.
+pub fn read_sheet(content: Vec<u8>, sheet: Option<&str>) -> Result<Vec<Vec<String>>, String> {
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(content)).map_err(|e| format!("invalid workbook: {e}"))?;
+    let range = match sheet {
+        Some(name) => {
+            let names = workbook.sheet_names();
+            if !names.iter().any(|n| n == name) {
+                return Err(format!("sheet '{}' not found; the workbook has: {}", name, names.join(", ")));
+            }
+            workbook.worksheet_range(name)
+        }
+        None => workbook.worksheet_range_at(0).ok_or("the workbook has no sheets")?,
+    }
+    .map_err(|e| e.to_string())?;
+
+    range
+        .rows()
+        .enumerate()
+        .map(|(row_index, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(col_index, cell)| {
+                    cell_to_string(cell).map_err(|e| format!("row {}, column {}: {}", row_index + 1, col_index + 1, e))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[async_trait]
+impl TableReader for XlsxTableReader {
+    fn name(&self) -> &str {
+        "xlsx"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[".xlsx", ".xls"]
+    }
+
+    fn can_read(&self, table: &TableSpec) -> bool {
+        match &table.source {
+            SourceSpec::File(fs) => {
+                let filename = fs.filename.to_lowercase();
+                filename.ends_with(".xlsx") || filename.ends_with(".xls")
+            }
+            SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => false,
+        }
+    }
+
+    async fn read_table(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
+        let file_source = match &table.source {
+            SourceSpec::File(fs) => fs,
+            SourceSpec::Cmd(_) | SourceSpec::Http(_) | SourceSpec::Stdin(_) => {
+                return Err(TableReaderError::ReadError {
+                    table_name: table.name.clone(),
+                    message: "XlsxTableReader only supports file sources".to_string(),
+                });
+            }
+        };
+
+        let path = project_dir.join(expand_env(table, "filename", &file_source.filename)?);
+        self.logger.debug(&format!("reading workbook: {}", path.display())).await;
+
+        check_inside_project(self.file_system.as_ref(), table, &path, project_dir, self.allow_outside_project).await?;
+        if !file_source.allow_special_files && self.file_system.is_special_file(&path).await? {
+            return Err(TableReaderError::ReadError {
+                table_name: table.name.clone(),
+                message: format!(
+                    "'{}' is not a regular file; set allowSpecialFiles to read from it",
+                    path.display()
+                ),
+            });
+        }
+
+        let bytes = self.file_system.load_bytes(&path).await?;
+        let mut cells = read_sheet(bytes, file_source.sheet.as_deref()).map_err(|message| TableReaderError::ReadError {
+            table_name: table.name.clone(),
+            message: format!("{}: {}", path.display(), message),
+        })?;
+        cells.drain(..file_source.csv.skip_rows.min(cells.len()));
+
+        let header_map = if table.has_header && !cells.is_empty() {
+            let header = csv::StringRecord::from(cells.remove(0));
+            Some(build_header_map(table, &header)?)
+        } else {
+            table.has_header.then(Default::default)
+        };
+        let indices = resolve_column_indices(table, &header_map)?;
+        for (column, alias) in matched_aliases(table, &header_map) {
+            self.logger.debug(&format!("column '{column}' matched header alias '{alias}'")).await;
+        }
+        let captures = compile_captures(table)?;
+        let filter = compile_filter(table)?;
+        let mut rows: Vec<Vec<String>> = cells
+            .iter()
+            .map(|cells| {
+                let mut row: Vec<String> = indices.iter().map(|&i| cells.get(i).cloned().unwrap_or_default()).collect();
+                apply_captures(&mut row, &captures);
+                row
+            })
+            .filter(|row| passes_filter(row, filter))
+            .collect();
+        let duplicates = dedup_rows(table, &mut rows)?;
+        if duplicates > 0 {
+            self.logger.info(&dedup_message(table, duplicates)).await;
+        }
+        if let Some(limit) = file_source.csv.limit {
+            rows.truncate(limit);
+        }
+
+        let result = Table::from_spec(table, rows);
+
+        self.logger.info(&format!(
+            "read table '{}' using reader '{}': {} rows, {} columns",
+            table.name,
+            self.name(),
+            result.num_rows(),
+            result.num_columns(),
+        )).await;
+
+        Ok(result)
+    }
+
+    async fn missing_file(&self, table: &TableSpec, project_dir: &Path) -> Result<Option<PathBuf>, TableReaderError> {
+        missing_file_source(self.file_system.as_ref(), table, project_dir, self.allow_outside_project).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_xlsxwriter::Workbook;
+    use crate::components::file_system::DiskFileSystem;
+    use crate::components::test_helpers::{TestLogger, column_spec};
+    use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType, FileSourceSpec};
+
+    fn make_reader() -> XlsxTableReader {
+        XlsxTableReader::new(Box::new(TestLogger), Box::new(DiskFileSystem::new(Box::new(TestLogger))), false)
+    }
+
+    fn column(name: &str, identifier: ColumnIdentifier) -> ColumnSpec {
+        column_spec(name, identifier, ColumnType::String { max_length: None })
+    }
+
+    fn table_spec(filename: &str, sheet: Option<&str>, has_header: bool, columns: Vec<ColumnSpec>) -> TableSpec {
+        TableSpec {
+            name: "city".to_string(),
+            description: String::new(),
+            has_header,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: filename.to_string(),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: sheet.map(str::to_string),
+                csv: Default::default(),
+            }),
+            columns,
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    /// A workbook with a `Notes` sheet first and the city data on a `Cities` sheet.
+    fn cities_workbook(dir: &Path) {
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().set_name("Notes").unwrap().write_string(0, 0, "exported by finance").unwrap();
+        let sheet = workbook.add_worksheet().set_name("Cities").unwrap();
+        sheet.write_string(0, 0, "Name").unwrap();
+        sheet.write_string(0, 1, "Population").unwrap();
+        sheet.write_string(1, 0, "London").unwrap();
+        sheet.write_number(1, 1, 8982000.0).unwrap();
+        sheet.write_string(2, 0, "Tiny").unwrap();
+        sheet.write_number(2, 1, 1e21).unwrap();
+        workbook.save(dir.join("cities.xlsx")).unwrap();
+    }
+
+    #[test]
+    fn can_read_spreadsheet_extensions() {
+        let reader = make_reader();
+        assert!(reader.can_read(&table_spec("data/cities.XLSX", None, true, vec![])));
+        assert!(reader.can_read(&table_spec("data/cities.xls", None, true, vec![])));
+        assert!(!reader.can_read(&table_spec("data/cities.csv", None, true, vec![])));
+    }
+
+    #[test]
+    fn cell_to_string_writes_numbers_without_exponent() {
+        assert_eq!(cell_to_string(&Data::Float(8982000.0)).unwrap(), "8982000");
+        assert_eq!(cell_to_string(&Data::Float(1e21)).unwrap(), "1000000000000000000000");
+        assert_eq!(cell_to_string(&Data::Float(0.0000125)).unwrap(), "0.0000125");
+    }
+
+    #[tokio::test]
+    async fn read_table_resolves_named_columns_on_selected_sheet() {
+        let tmp = tempfile::tempdir().unwrap();
+        cities_workbook(tmp.path());
+        let columns = vec![
+            column("population", ColumnIdentifier::Name("Population".to_string())),
+            column("name", ColumnIdentifier::Name("Name".to_string())),
+        ];
+        let spec = table_spec("cities.xlsx", Some("Cities"), true, columns);
+        let table = make_reader().read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.headers(), &["population", "name"]);
+        assert_eq!(table.row(0), Some(["8982000".to_string(), "London".to_string()].as_slice()));
+        assert_eq!(table.cell(1, 0), Some("1000000000000000000000"));
+    }
+
+    #[tokio::test]
+    async fn read_table_reads_first_sheet_by_index_without_header() {
+        let tmp = tempfile::tempdir().unwrap();
+        cities_workbook(tmp.path());
+        let spec = table_spec("cities.xlsx", None, false, vec![column("note", ColumnIdentifier::Index(0))]);
+        let table = make_reader().read_table(&spec, tmp.path()).await.unwrap();
+        assert_eq!(table.num_rows(), 1);
+        assert_eq!(table.cell(0, 0), Some("exported by finance"));
+    }
+
+    #[tokio::test]
+    async fn read_table_reports_unknown_sheet() {
+        let tmp = tempfile::tempdir().unwrap();
+        cities_workbook(tmp.path());
+        let spec = table_spec("cities.xlsx", Some("Towns"), true, vec![]);
+        let err = make_reader().read_table(&spec, tmp.path()).await.unwrap_err();
+        assert!(err.to_string().contains("sheet 'Towns' not found; the workbook has: Notes, Cities"), "error was: {err}");
+    }
+}
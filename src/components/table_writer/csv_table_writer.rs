@@ -0,0 +1,128 @@
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use crate::models::{Table, TableSpec};
+use crate::traits::{FileSystem, Logger, TableWriter, TableWriterError};
+
+pub fn table_to_csv(table: &Table, header: bool) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if header {
+        writer.write_record(&table.columns).map_err(|e| e.to_string())?;
+    }
+    for row in &table.rows {
+        writer.write_record(row).map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+/// Writes each table to `<out_dir>/<table name>.csv`, replacing an existing file.
+pub struct CsvTableWriter {
+    logger: Box<dyn Logger>,
+    file_system: Box<dyn FileSystem>,
+    out_dir: PathBuf,
+    header: bool,
+}
+
+impl CsvTableWriter {
+    pub fn new(logger: Box<dyn Logger>, file_system: Box<dyn FileSystem>, out_dir: &Path, header: bool) -> Self {
+        CsvTableWriter { logger, file_system, out_dir: out_dir.to_path_buf(), header }
+    }
+}
+
+#[async_trait]
+impl TableWriter for CsvTableWriter {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    async fn write(&self, table: &Table, _spec: &TableSpec) -> Result<(), TableWriterError> {
+        let write_error = |message: String| TableWriterError::WriteError {
+            table_name: table.name.clone(),
+            message,
+        };
+        let path = self.out_dir.join(format!("{}.csv", table.name));
+        self.logger.debug(&format!("writing table '{}' to: {}", table.name, path.display())).await;
+
+        let content = table_to_csv(table, self.header).map_err(write_error)?;
+        self.file_system.save(&content, &path).await.map_err(|e| write_error(e.to_string()))?;
+
+        self.logger.info(&format!("wrote table '{}' to {}: {} rows", table.name, path.display(), table.num_rows())).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use crate::components::test_helpers::{InMemoryFileSystem, TestLogger};
+    use crate::models::{ColumnType, FileSourceSpec, SourceSpec};
+
+    fn city_table() -> Table {
+        Table::new(
+            "city".to_string(),
+            vec!["name".to_string(), "note".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
+            vec![
+                vec!["London".to_string(), "big, old".to_string()],
+                vec!["Berlin".to_string(), "says \"hi\"".to_string()],
+            ],
+        )
+    }
+
+    fn city_spec() -> TableSpec {
+        TableSpec {
+            name: "city".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: "data/cities.csv".to_string(),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns: vec![],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    #[test]
+    fn table_to_csv_quotes_and_escapes() {
+        assert_eq!(
+            table_to_csv(&city_table(), true).unwrap(),
+            "name,note\nLondon,\"big, old\"\nBerlin,\"says \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn table_to_csv_without_header() {
+        assert_eq!(
+            table_to_csv(&city_table(), false).unwrap(),
+            "London,\"big, old\"\nBerlin,\"says \"\"hi\"\"\"\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_saves_table_under_its_name() {
+        let store = Arc::new(Mutex::new(HashMap::new()));
+        let writer = CsvTableWriter::new(
+            Box::new(TestLogger),
+            Box::new(InMemoryFileSystem::new(store.clone())),
+            Path::new("/out"),
+            true,
+        );
+        writer.write(&city_table(), &city_spec()).await.unwrap();
+
+        let store = store.lock().await;
+        let content = store.get(Path::new("/out/city.csv")).unwrap();
+        assert!(content.starts_with("name,note\nLondon,"), "content was: {content}");
+    }
+}
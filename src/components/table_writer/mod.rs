@@ -0,0 +1,7 @@
+pub mod sqlite_table_writer;
+pub mod csv_table_writer;
+pub mod postgres_table_writer;
+
+pub use sqlite_table_writer::SqliteTableWriter;
+pub use csv_table_writer::CsvTableWriter;
+pub use postgres_table_writer::PostgresTableWriter;
@@ -0,0 +1,370 @@
+use async_trait::async_trait;
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, NoTls};
+use crate::components::ddl::ddl_impl::{CellValue, cell_value, relationship_to_ddl, table_to_ddl};
+use crate::components::engine::engine_impl::tables_in_dependency_order;
+use crate::components::validate::validate_impl::is_key_column;
+use crate::models::{ColumnSpec, ColumnType, Dialect, LoadedProject, Table, TableSpec};
+use crate::traits::{Logger, TableWriter, TableWriterError};
+
+/// Postgres accepts at most this many parameters in one statement.
+const MAX_PARAMETERS: usize = 65535;
+const MAX_BATCH_ROWS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostgresValue {
+    Text(Option<String>),
+    Int64(Option<i64>),
+    Bool(Option<bool>),
+}
+
+impl PostgresValue {
+    fn as_sql(&self) -> &(dyn ToSql + Sync) {
+        match self {
+            PostgresValue::Text(v) => v,
+            PostgresValue::Int64(v) => v,
+            PostgresValue::Bool(v) => v,
+        }
+    }
+}
+
+/// A cell as a parameter; NULL keeps the type of its column.
+pub fn cell_to_postgres_value(cell: &str, column: &ColumnSpec) -> Result<PostgresValue, String> {
+    Ok(match (cell_value(cell, Some(&column.column_type), column.empty_is_null)?, &column.column_type) {
+        (CellValue::Null, ColumnType::String { .. }) => PostgresValue::Text(None),
+        (CellValue::Null, ColumnType::Int64) => PostgresValue::Int64(None),
+        (CellValue::Null, ColumnType::Bool) => PostgresValue::Bool(None),
+        (CellValue::Text(s), _) => PostgresValue::Text(Some(s)),
+        (CellValue::Int64(i), _) => PostgresValue::Int64(Some(i)),
+        (CellValue::Bool(b), _) => PostgresValue::Bool(Some(b)),
+    })
+}
+
+/// The foreign key of every relationship between two loaded tables, as `(table, statement)`.
+/// Skipped relationships and targets that are neither unique nor the primary key get none,
+/// since Postgres could not enforce them.
+pub fn foreign_key_statements(loaded_project: &LoadedProject) -> Vec<(String, String)> {
+    let specs = &loaded_project.project.spec.tables;
+    let loaded = |name: &str| loaded_project.tables.iter().any(|t| t.name == name);
+    let mut statements = Vec::new();
+    for table in specs.iter().filter(|t| loaded(&t.name)) {
+        for rel in &table.relationships {
+            let skipped = loaded_project.report.skipped_relationships.contains(&format!("{}.{}", table.name, rel.name));
+            let Some(target) = specs.iter().find(|t| t.name == rel.target_table && loaded(&t.name)) else {
+                continue;
+            };
+            if !skipped && is_key_column(target, &rel.target_column) {
+                statements.push((table.name.clone(), relationship_to_ddl(table, rel, Dialect::Postgres)));
+            }
+        }
+    }
+    statements
+}
+
+/// Rows per `INSERT` for a table with `columns` columns, staying under the parameter limit.
+pub fn batch_rows(columns: usize) -> usize {
+    (MAX_PARAMETERS / columns.max(1)).clamp(1, MAX_BATCH_ROWS)
+}
+
+/// An `INSERT` of `rows` rows with numbered parameters, row after row.
+pub fn insert_statement(spec: &TableSpec, rows: usize) -> String {
+    let dialect = Dialect::Postgres;
+    let columns: Vec<String> = spec.columns.iter().map(|c| dialect.quote_identifier(&c.name)).collect();
+    let values: Vec<String> = (0..rows)
+        .map(|row| {
+            let placeholders: Vec<String> =
+                (1..=spec.columns.len()).map(|i| format!("${}", row * spec.columns.len() + i)).collect();
+            format!("({})", placeholders.join(", "))
+        })
+        .collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        dialect.quote_identifier(&spec.name),
+        columns.join(", "),
+        values.join(", "),
+    )
+}
+
+/// The `DROP` of every loaded table, tables that point at others first so that no drop needs `CASCADE`.
+pub fn drop_statements(loaded_project: &LoadedProject) -> Vec<(String, String)> {
+    tables_in_dependency_order(loaded_project)
+        .iter()
+        .rev()
+        .map(|t| (t.name.clone(), format!("DROP TABLE IF EXISTS {}", Dialect::Postgres.quote_identifier(&t.name))))
+        .collect()
+}
+
+/// Writes tables into a Postgres database in one transaction, committed once the foreign keys
+/// are added, so a failure anywhere leaves the database as it was. Existing tables with the
+/// same names are dropped and recreated so that repeated loads replace the data; a view or
+/// foreign key outside the project that depends on one of them fails the load instead.
+pub struct PostgresTableWriter {
+    logger: Box<dyn Logger>,
+    url: String,
+    client: Mutex<Option<Client>>,
+}
+
+impl PostgresTableWriter {
+    pub fn new(logger: Box<dyn Logger>, url: &str) -> Self {
+        PostgresTableWriter { logger, url: url.to_string(), client: Mutex::new(None) }
+    }
+
+    async fn connect(&self) -> Result<Client, TableWriterError> {
+        let (client, connection) = tokio_postgres::connect(&self.url, NoTls)
+            .await
+            .map_err(|e| TableWriterError::ConnectError(e.to_string()))?;
+        tokio::spawn(async move {
+            // errors surface on the client's next query
+            let _ = connection.await;
+        });
+        Ok(client)
+    }
+
+    /// The connection shared by all writes, opened on first use.
+    async fn client(&self) -> Result<MappedMutexGuard<'_, Client>, TableWriterError> {
+        let mut client = self.client.lock().await;
+        if client.is_none() {
+            *client = Some(self.connect().await?);
+        }
+        Ok(MutexGuard::map(client, |client| client.as_mut().expect("connected above")))
+    }
+
+    /// Rolls back the transaction of the load after an error; the error itself is what gets reported.
+    async fn rollback<T>(client: &Client, result: Result<T, TableWriterError>) -> Result<T, TableWriterError> {
+        if result.is_err() {
+            let _ = client.batch_execute("ROLLBACK").await;
+        }
+        result
+    }
+
+    async fn write_rows(client: &Client, table: &Table, spec: &TableSpec) -> Result<(), String> {
+        client.batch_execute(&table_to_ddl(spec, Dialect::Postgres)).await.map_err(|e| e.to_string())?;
+
+        let batch_size = batch_rows(spec.columns.len());
+        for (batch_index, batch) in table.rows.chunks(batch_size).enumerate() {
+            let mut values = Vec::with_capacity(batch.len() * spec.columns.len());
+            for (offset, row) in batch.iter().enumerate() {
+                for (i, col) in spec.columns.iter().enumerate() {
+                    let cell = row.get(i).map(|s| s.as_str()).unwrap_or("");
+                    let row_number = batch_index * batch_size + offset + 1;
+                    values.push(
+                        cell_to_postgres_value(cell, col)
+                            .map_err(|e| format!("row {}, column '{}': {}", row_number, col.name, e))?,
+                    );
+                }
+            }
+            let params: Vec<&(dyn ToSql + Sync)> = values.iter().map(PostgresValue::as_sql).collect();
+            client.execute(&insert_statement(spec, batch.len()), &params).await.map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TableWriter for PostgresTableWriter {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    async fn start(&self, loaded_project: &LoadedProject) -> Result<(), TableWriterError> {
+        let client = self.client().await?;
+        client.batch_execute("BEGIN").await.map_err(|e| TableWriterError::ConnectError(e.to_string()))?;
+        for (table_name, statement) in drop_statements(loaded_project) {
+            let dropped = client.batch_execute(&statement).await.map_err(|e| TableWriterError::WriteError {
+                table_name,
+                message: format!("failed to drop the existing table: {e}"),
+            });
+            Self::rollback(&client, dropped).await?;
+        }
+        Ok(())
+    }
+
+    async fn write(&self, table: &Table, spec: &TableSpec) -> Result<(), TableWriterError> {
+        self.logger.debug(&format!("writing table '{}' to postgres", table.name)).await;
+
+        let client = self.client().await?;
+        let written = Self::write_rows(&client, table, spec).await.map_err(|message| TableWriterError::WriteError {
+            table_name: table.name.clone(),
+            message,
+        });
+        Self::rollback(&client, written).await?;
+
+        self.logger.info(&format!("wrote table '{}' to postgres: {} rows", table.name, table.num_rows())).await;
+        Ok(())
+    }
+
+    async fn finish(&self, loaded_project: &LoadedProject) -> Result<(), TableWriterError> {
+        let statements = foreign_key_statements(loaded_project);
+        let client = self.client().await?;
+        for (table_name, statement) in &statements {
+            let added = client.batch_execute(statement).await.map_err(|e| TableWriterError::WriteError {
+                table_name: table_name.clone(),
+                message: format!("failed to add foreign key: {e}"),
+            });
+            Self::rollback(&client, added).await?;
+        }
+        if !statements.is_empty() {
+            self.logger.info(&format!("added {} foreign key(s) in postgres", statements.len())).await;
+        }
+        let committed =
+            client.batch_execute("COMMIT").await.map_err(|e| TableWriterError::CommitError(e.to_string()));
+        Self::rollback(&client, committed).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::test_helpers::{TestLogger, column_spec};
+    use crate::models::{ColumnIdentifier, FileSourceSpec, SourceSpec};
+
+    fn column(name: &str, column_type: ColumnType) -> ColumnSpec {
+        column_spec(name, ColumnIdentifier::Name(name.to_string()), column_type)
+    }
+
+    fn city_spec() -> TableSpec {
+        TableSpec {
+            name: "city".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: "data/cities.csv".to_string(),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns: vec![
+                column("name", ColumnType::String { max_length: None }),
+                column("population", ColumnType::Int64),
+            ],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    #[test]
+    fn cell_to_postgres_value_types_cells() {
+        let mut name = column("name", ColumnType::String { max_length: None });
+        assert_eq!(cell_to_postgres_value("", &name).unwrap(), PostgresValue::Text(None));
+        name.empty_is_null = false;
+        assert_eq!(cell_to_postgres_value("", &name).unwrap(), PostgresValue::Text(Some(String::new())));
+        let population = column("population", ColumnType::Int64);
+        assert_eq!(cell_to_postgres_value("42", &population).unwrap(), PostgresValue::Int64(Some(42)));
+        assert_eq!(cell_to_postgres_value("", &population).unwrap(), PostgresValue::Int64(None));
+        assert_eq!(cell_to_postgres_value("many", &population).unwrap_err(), "value 'many' is not a valid int64");
+        let capital = column("capital", ColumnType::Bool);
+        assert_eq!(cell_to_postgres_value("yes", &capital).unwrap(), PostgresValue::Bool(Some(true)));
+    }
+
+    #[test]
+    fn insert_statement_numbers_parameters_across_rows() {
+        assert_eq!(
+            insert_statement(&city_spec(), 2),
+            "INSERT INTO \"city\" (\"name\", \"population\") VALUES ($1, $2), ($3, $4)"
+        );
+    }
+
+    #[test]
+    fn batch_rows_stays_under_parameter_limit() {
+        assert_eq!(batch_rows(2), MAX_BATCH_ROWS);
+        assert_eq!(batch_rows(100), 655);
+        assert_eq!(batch_rows(0), MAX_BATCH_ROWS);
+    }
+
+    #[test]
+    fn foreign_key_statements_cover_keyed_relationships_between_loaded_tables() {
+        use crate::models::{LoadReport, OnDelete, Project, ProjectSpec, RelationshipSpec};
+
+        let relationship = |name: &str, target_table: &str, target_column: &str| RelationshipSpec {
+            name: name.to_string(),
+            description: String::new(),
+            source_column: "name".to_string(),
+            target_table: target_table.to_string(),
+            target_column: target_column.to_string(),
+            on_delete: OnDelete::default(),
+        };
+        let mut city = city_spec();
+        city.columns[0].primary_key = true;
+        let mut office = city_spec();
+        office.name = "office".to_string();
+        office.relationships = vec![
+            relationship("located_in", "city", "name"),
+            relationship("sized_like", "city", "population"),
+            relationship("skipped", "city", "name"),
+            relationship("unloaded", "country", "name"),
+        ];
+        let mut country = city_spec();
+        country.name = "country".to_string();
+        let loaded_project = LoadedProject {
+            project: Project {
+                name: "test".to_string(),
+                api_version: "v1".to_string(),
+                spec: ProjectSpec {
+                    tables: vec![city.clone(), office.clone(), country],
+                    includes: vec![],
+                    defaults: Default::default(),
+                },
+            },
+            tables: vec![Table::from_spec(&city, vec![]), Table::from_spec(&office, vec![])],
+            report: LoadReport { skipped_relationships: vec!["office.skipped".to_string()], ..Default::default() },
+        };
+
+        let statements = foreign_key_statements(&loaded_project);
+        assert_eq!(statements.len(), 1, "statements were: {statements:?}");
+        assert_eq!(statements[0].0, "office");
+        assert!(statements[0].1.contains("ADD CONSTRAINT \"located_in\""), "statement was: {}", statements[0].1);
+    }
+
+    #[test]
+    fn drop_statements_drop_referencing_tables_first_without_cascade() {
+        use crate::models::{LoadReport, OnDelete, Project, ProjectSpec, RelationshipSpec};
+
+        let mut city = city_spec();
+        city.columns[0].primary_key = true;
+        let mut office = city_spec();
+        office.name = "office".to_string();
+        office.relationships = vec![RelationshipSpec {
+            name: "located_in".to_string(),
+            description: String::new(),
+            source_column: "name".to_string(),
+            target_table: "city".to_string(),
+            target_column: "name".to_string(),
+            on_delete: OnDelete::default(),
+        }];
+        let loaded_project = LoadedProject {
+            project: Project {
+                name: "test".to_string(),
+                api_version: "v1".to_string(),
+                spec: ProjectSpec {
+                    tables: vec![office.clone(), city.clone()],
+                    includes: vec![],
+                    defaults: Default::default(),
+                },
+            },
+            tables: vec![Table::from_spec(&office, vec![]), Table::from_spec(&city, vec![])],
+            report: LoadReport::default(),
+        };
+
+        assert_eq!(
+            drop_statements(&loaded_project),
+            vec![
+                ("office".to_string(), "DROP TABLE IF EXISTS \"office\"".to_string()),
+                ("city".to_string(), "DROP TABLE IF EXISTS \"city\"".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn write_reports_connection_failure() {
+        let writer = PostgresTableWriter::new(Box::new(TestLogger), "postgres://dbloada@127.0.0.1:1/dbloada");
+        let table = Table::from_spec(&city_spec(), vec![]);
+        let err = writer.write(&table, &city_spec()).await.unwrap_err();
+        assert!(matches!(err, TableWriterError::ConnectError(_)), "error was: {err}");
+    }
+}
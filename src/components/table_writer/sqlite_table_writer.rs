@@ -0,0 +1,250 @@
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use rusqlite::types::Value;
+use crate::components::ddl::ddl_impl::{CellValue, cell_value, table_to_ddl};
+use crate::models::{ColumnSpec, Dialect, Table, TableSpec};
+use crate::traits::{Logger, TableWriter, TableWriterError};
+
+pub fn cell_to_sql_value(cell: &str, column: &ColumnSpec) -> Result<Value, String> {
+    Ok(match cell_value(cell, Some(&column.column_type), column.empty_is_null)? {
+        CellValue::Null => Value::Null,
+        CellValue::Text(s) => Value::Text(s),
+        CellValue::Int64(i) => Value::Integer(i),
+        CellValue::Bool(b) => Value::Integer(b as i64),
+    })
+}
+
+pub fn insert_statement(spec: &TableSpec) -> String {
+    let dialect = Dialect::Sqlite;
+    let columns: Vec<String> = spec.columns.iter().map(|c| dialect.quote_identifier(&c.name)).collect();
+    let placeholders: Vec<String> = (1..=spec.columns.len()).map(|i| format!("?{i}")).collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        dialect.quote_identifier(&spec.name),
+        columns.join(", "),
+        placeholders.join(", "),
+    )
+}
+
+/// Writes tables into an SQLite database file. An existing table with the same
+/// name is dropped and recreated so that repeated loads replace the data.
+pub struct SqliteTableWriter {
+    logger: Box<dyn Logger>,
+    path: PathBuf,
+}
+
+impl SqliteTableWriter {
+    pub fn new(logger: Box<dyn Logger>, path: &Path) -> Self {
+        SqliteTableWriter { logger, path: path.to_path_buf() }
+    }
+}
+
+fn write_blocking(path: &Path, table: &Table, spec: &TableSpec) -> Result<(), String> {
+    let mut conn = rusqlite::Connection::open(path).map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let drop = format!("DROP TABLE IF EXISTS {}", Dialect::Sqlite.quote_identifier(&spec.name));
+    tx.execute(&drop, []).map_err(|e| e.to_string())?;
+    tx.execute_batch(&table_to_ddl(spec, Dialect::Sqlite)).map_err(|e| e.to_string())?;
+
+    {
+        let mut stmt = tx.prepare(&insert_statement(spec)).map_err(|e| e.to_string())?;
+        for (row_index, row) in table.rows.iter().enumerate() {
+            let values = spec
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, col)| {
+                    let cell = row.get(i).map(|s| s.as_str()).unwrap_or("");
+                    cell_to_sql_value(cell, col)
+                        .map_err(|e| format!("row {}, column '{}': {}", row_index + 1, col.name, e))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            stmt.execute(rusqlite::params_from_iter(values)).map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[async_trait]
+impl TableWriter for SqliteTableWriter {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn write(&self, table: &Table, spec: &TableSpec) -> Result<(), TableWriterError> {
+        self.logger.debug(&format!(
+            "writing table '{}' to sqlite database: {}",
+            table.name,
+            self.path.display()
+        )).await;
+
+        let (path, owned_table, owned_spec) = (self.path.clone(), table.clone(), spec.clone());
+        tokio::task::spawn_blocking(move || write_blocking(&path, &owned_table, &owned_spec))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result)
+            .map_err(|message| TableWriterError::WriteError { table_name: table.name.clone(), message })?;
+
+        self.logger.info(&format!(
+            "wrote table '{}' to sqlite database: {} rows",
+            table.name,
+            table.num_rows(),
+        )).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::test_helpers::{TestLogger, column_spec};
+    use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType, FileSourceSpec, SourceSpec};
+
+    fn column(name: &str, column_type: ColumnType) -> ColumnSpec {
+        column_spec(name, ColumnIdentifier::Name(name.to_string()), column_type)
+    }
+
+    fn city_spec() -> TableSpec {
+        TableSpec {
+            name: "city".to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: "data/cities.csv".to_string(),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns: vec![
+                column("name", ColumnType::String { max_length: None }),
+                column("population", ColumnType::Int64),
+            ],
+            relationships: vec![],
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    fn city_table(rows: Vec<Vec<&str>>) -> Table {
+        Table::new(
+            "city".to_string(),
+            vec!["name".to_string(), "population".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
+            rows.into_iter()
+                .map(|r| r.into_iter().map(String::from).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn cell_to_sql_value_binds_by_type() {
+        let string_col = column("s", ColumnType::String { max_length: None });
+        let int_col = column("i", ColumnType::Int64);
+        let bool_col = column("b", ColumnType::Bool);
+        assert_eq!(cell_to_sql_value("42", &string_col), Ok(Value::Text("42".to_string())));
+        assert_eq!(cell_to_sql_value("42", &int_col), Ok(Value::Integer(42)));
+        assert!(cell_to_sql_value("abc", &int_col).is_err());
+        assert_eq!(cell_to_sql_value("Yes", &bool_col), Ok(Value::Integer(1)));
+        assert_eq!(cell_to_sql_value("false", &bool_col), Ok(Value::Integer(0)));
+        assert!(cell_to_sql_value("maybe", &bool_col).is_err());
+    }
+
+    #[test]
+    fn cell_to_sql_value_empty_int64_is_null() {
+        assert_eq!(cell_to_sql_value("", &column("i", ColumnType::Int64)), Ok(Value::Null));
+    }
+
+    #[test]
+    fn cell_to_sql_value_empty_string_kept_when_empty_is_null_disabled() {
+        let mut col = column("s", ColumnType::String { max_length: None });
+        assert_eq!(cell_to_sql_value("", &col), Ok(Value::Null));
+        col.empty_is_null = false;
+        assert_eq!(cell_to_sql_value("", &col), Ok(Value::Text(String::new())));
+    }
+
+    #[test]
+    fn insert_statement_uses_numbered_placeholders() {
+        assert_eq!(
+            insert_statement(&city_spec()),
+            "INSERT INTO \"city\" (\"name\", \"population\") VALUES (?1, ?2)"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_creates_table_and_inserts_rows() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join("out.db");
+        let writer = SqliteTableWriter::new(Box::new(TestLogger), &db_path);
+
+        let table = city_table(vec![vec!["London", "8982000"], vec!["Berlin", "3645000"]]);
+        writer.write(&table, &city_spec()).await.unwrap();
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let rows: Vec<(String, i64, String)> = conn
+            .prepare("SELECT name, population, typeof(population) FROM city ORDER BY population")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(rows, vec![
+            ("Berlin".to_string(), 3645000, "integer".to_string()),
+            ("London".to_string(), 8982000, "integer".to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn write_replaces_existing_table() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join("out.db");
+        let writer = SqliteTableWriter::new(Box::new(TestLogger), &db_path);
+
+        writer.write(&city_table(vec![vec!["London", "1"]]), &city_spec()).await.unwrap();
+        writer.write(&city_table(vec![vec!["Berlin", "2"]]), &city_spec()).await.unwrap();
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM city", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn write_rolls_back_on_invalid_int64() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join("out.db");
+        let writer = SqliteTableWriter::new(Box::new(TestLogger), &db_path);
+
+        let table = city_table(vec![vec!["London", "1"], vec!["Berlin", "many"]]);
+        let err = writer.write(&table, &city_spec()).await.unwrap_err();
+        assert!(err.to_string().contains("row 2"), "error was: {}", err);
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let exists: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'city'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(exists, 0);
+    }
+
+    #[tokio::test]
+    async fn write_honors_empty_is_null_per_column() {
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join("out.db");
+        let writer = SqliteTableWriter::new(Box::new(TestLogger), &db_path);
+
+        let mut spec = city_spec();
+        spec.columns[0].empty_is_null = false;
+        writer.write(&city_table(vec![vec!["", ""]]), &spec).await.unwrap();
+
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        let (name, population): (Option<String>, Option<i64>) = conn
+            .query_row("SELECT name, population FROM city", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!(name, Some(String::new()));
+        assert_eq!(population, None);
+    }
+}
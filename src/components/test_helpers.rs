@@ -3,8 +3,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use async_trait::async_trait;
-use crate::models::Project;
-use crate::traits::{Logger, FileSystem, FileSystemError, ProjectIO, ProjectIOError};
+use crate::models::{ColumnIdentifier, ColumnSpec, ColumnType, Project, Table, TableSpec};
+use crate::traits::{Logger, FileSystem, FileSystemError, ProjectIO, ProjectIOError, TableReader, TableReaderError};
+use crate::components::project_io::yaml_project_io::normalize;
 
 pub struct TestLogger;
 
@@ -17,6 +18,47 @@ impl Logger for TestLogger {
     async fn trace(&self, _msg: &str) {}
 }
 
+/// Collects every message as `"<level>: <msg>"` for assertions on log output.
+#[derive(Clone, Default)]
+pub struct RecordingLogger {
+    pub messages: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl RecordingLogger {
+    fn record(&self, level: &str, msg: &str) {
+        self.messages.lock().unwrap().push(format!("{level}: {msg}"));
+    }
+
+    pub fn messages_at(&self, level: &str) -> Vec<String> {
+        let prefix = format!("{level}: ");
+        self.messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|m| m.strip_prefix(&prefix).map(String::from))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Logger for RecordingLogger {
+    async fn error(&self, msg: &str) {
+        self.record("error", msg);
+    }
+    async fn warn(&self, msg: &str) {
+        self.record("warn", msg);
+    }
+    async fn info(&self, msg: &str) {
+        self.record("info", msg);
+    }
+    async fn debug(&self, msg: &str) {
+        self.record("debug", msg);
+    }
+    async fn trace(&self, msg: &str) {
+        self.record("trace", msg);
+    }
+}
+
 pub struct InMemoryFileSystem {
     store: Arc<Mutex<HashMap<PathBuf, String>>>,
 }
@@ -51,17 +93,52 @@ impl FileSystem for InMemoryFileSystem {
         Ok(content.into_bytes())
     }
 
+    async fn open_read(&self, path: &Path) -> Result<Box<dyn std::io::Read + Send>, FileSystemError> {
+        let bytes = self.load_bytes(path).await?;
+        Ok(Box::new(std::io::Cursor::new(bytes)))
+    }
+
     async fn ensure_dir(&self, _path: &Path) -> Result<(), FileSystemError> {
         Ok(())
     }
+
+    async fn is_special_file(&self, path: &Path) -> Result<bool, FileSystemError> {
+        self.load(path).await.map(|_| false)
+    }
+
+    async fn exists(&self, path: &Path) -> Result<bool, FileSystemError> {
+        Ok(self.store.lock().await.contains_key(path))
+    }
+
+    /// Folds `.` and `..` without following links; directories exist when a stored file is below them.
+    async fn canonicalize(&self, path: &Path) -> Result<PathBuf, FileSystemError> {
+        let normalized = normalize(path);
+        if self.store.lock().await.keys().any(|key| key.starts_with(&normalized)) {
+            return Ok(normalized);
+        }
+        Err(FileSystemError::ReadError {
+            path: path.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::NotFound, "not found in memory store"),
+        })
+    }
 }
 
-pub struct InMemoryProjectIO;
+/// Saves nowhere and loads the project it was given, if any.
+#[derive(Default)]
+pub struct InMemoryProjectIO {
+    project: Option<Project>,
+}
+
+impl InMemoryProjectIO {
+    pub fn with_project(project: Project) -> Self {
+        InMemoryProjectIO { project: Some(project) }
+    }
+}
 
 #[async_trait]
 impl ProjectIO for InMemoryProjectIO {
     async fn load(&self, _path: &Path) -> Result<Project, ProjectIOError> {
-        unimplemented!("not needed in test")
+        Ok(self.project.clone().expect("no project given to InMemoryProjectIO"))
     }
 
     async fn save(&self, _project: &Project, _path: &Path) -> Result<(), ProjectIOError> {
@@ -69,12 +146,89 @@ impl ProjectIO for InMemoryProjectIO {
     }
 }
 
+type TablePredicate = Box<dyn Fn(&TableSpec) -> bool + Send + Sync>;
+
+/// Returns a canned `Table` for every table spec its predicate accepts.
+pub struct MockTableReader {
+    predicate: TablePredicate,
+    table: Table,
+}
+
+impl MockTableReader {
+    pub fn new(predicate: impl Fn(&TableSpec) -> bool + Send + Sync + 'static, table: Table) -> Self {
+        MockTableReader { predicate: Box::new(predicate), table }
+    }
+
+    /// Reads the table spec with the same name as `table`.
+    pub fn for_table(table: Table) -> Self {
+        let name = table.name.clone();
+        Self::new(move |spec| spec.name == name, table)
+    }
+}
+
+#[async_trait]
+impl TableReader for MockTableReader {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn can_read(&self, table: &TableSpec) -> bool {
+        (self.predicate)(table)
+    }
+
+    async fn read_table(&self, _table: &TableSpec, _project_dir: &Path) -> Result<Table, TableReaderError> {
+        Ok(self.table.clone())
+    }
+}
+
+#[derive(Default)]
+pub struct MockTableReaderBuilder {
+    readers: Vec<Box<dyn TableReader>>,
+}
+
+impl MockTableReaderBuilder {
+    pub fn table(mut self, name: &str, columns: &[&str], rows: &[&[&str]]) -> Self {
+        let table = Table::new(
+            name.to_string(),
+            columns.iter().map(|c| c.to_string()).collect(),
+            vec![ColumnType::String { max_length: None }; columns.len()],
+            rows.iter().map(|r| r.iter().map(|c| c.to_string()).collect()).collect(),
+        );
+        self.readers.push(Box::new(MockTableReader::for_table(table)));
+        self
+    }
+
+    pub fn reader(mut self, reader: MockTableReader) -> Self {
+        self.readers.push(Box::new(reader));
+        self
+    }
+
+    pub fn build(self) -> Vec<Box<dyn TableReader>> {
+        self.readers
+    }
+}
+
+/// A column read through `column_identifier`, with the defaults of a column written without options.
+pub fn column_spec(name: &str, column_identifier: ColumnIdentifier, column_type: ColumnType) -> ColumnSpec {
+    ColumnSpec {
+        name: name.to_string(),
+        description: String::new(),
+        column_identifier,
+        column_type,
+        empty_is_null: true,
+        primary_key: false,
+        unique: false,
+        ignore_header_case: false,
+        aliases: vec![],
+    }
+}
+
 pub fn mock_logger() -> Box<dyn Logger> {
     Box::new(TestLogger)
 }
 
 pub fn mock_project_io() -> Box<dyn ProjectIO> {
-    Box::new(InMemoryProjectIO)
+    Box::new(InMemoryProjectIO::default())
 }
 
 pub fn mock_file_system() -> Box<dyn FileSystem> {
@@ -0,0 +1,3 @@
+pub mod validate_impl;
+
+pub use validate_impl::ValidateImpl;
@@ -0,0 +1,518 @@
+use std::collections::HashSet;
+use std::path::Path;
+use async_trait::async_trait;
+use crate::components::load::project_file_path;
+use crate::components::table_reader::cmd_csv_table_reader::TEMP_CSV_PATH_PLACEHOLDER;
+use crate::components::table_reader::env::interpolate_env;
+use crate::models::{CmdSourceSpec, ColumnIdentifier, ColumnSpec, OnDelete, Project, SourceSpec, TableSpec};
+use crate::traits::{Logger, ProjectIO, Validate, ValidateError};
+
+fn duplicates<'a>(names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut seen = HashSet::new();
+    let mut dups = Vec::new();
+    for name in names {
+        if !seen.insert(name) && !dups.contains(&name) {
+            dups.push(name);
+        }
+    }
+    dups
+}
+
+fn has_column(table: &TableSpec, column: &str) -> bool {
+    table.columns.iter().any(|c| c.name == column)
+}
+
+fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Checks that a cmd source is well-formed without running it.
+pub fn validate_cmd_source(table_name: &str, cmd: &CmdSourceSpec) -> Vec<String> {
+    let mut problems = Vec::new();
+    if cmd.command.trim().is_empty() {
+        problems.push(format!("table '{table_name}': cmd source has an empty command"));
+    }
+    if !cmd.stdout && !cmd.args.iter().any(|a| a.contains(TEMP_CSV_PATH_PLACEHOLDER)) {
+        problems.push(format!(
+            "table '{table_name}': cmd source with stdout: false must pass {TEMP_CSV_PATH_PLACEHOLDER} in its args"
+        ));
+    }
+    if cmd.allowed_exit_codes.is_empty() {
+        problems.push(format!("table '{table_name}': cmd source has an empty allowedExitCodes list"));
+    }
+    for (key, value) in &cmd.env {
+        if !is_valid_env_key(key) {
+            problems.push(format!("table '{table_name}': env key '{key}' is not a valid variable name"));
+        }
+        // only the syntax is checked here; whether the variables are set is up to the load
+        if let Err(msg) = interpolate_env(value, |_| Some(String::new())) {
+            problems.push(format!("table '{table_name}': env '{key}': {msg}"));
+        }
+    }
+    problems
+}
+
+pub fn validate_capture(table: &TableSpec, col: &ColumnSpec) -> Vec<String> {
+    let ColumnIdentifier::Capture { source, pattern, group } = &col.column_identifier else {
+        return vec![];
+    };
+    let mut problems = Vec::new();
+    let prefix = format!("table '{}': column '{}'", table.name, col.name);
+    match table.columns.iter().find(|c| &c.name == source) {
+        None => problems.push(format!("{prefix} captures from unknown column '{source}'")),
+        Some(c) if matches!(c.column_identifier, ColumnIdentifier::Capture { .. }) => {
+            problems.push(format!("{prefix} captures from '{source}', which is itself a capture column"))
+        }
+        Some(_) => {}
+    }
+    match regex::Regex::new(pattern) {
+        Err(e) => problems.push(format!("{prefix} has an invalid pattern: {e}")),
+        Ok(re) if *group >= re.captures_len() => problems.push(format!(
+            "{prefix} uses capture group {group} but the pattern has only {}",
+            re.captures_len() - 1
+        )),
+        Ok(_) => {}
+    }
+    problems
+}
+
+/// The source fields that may reference environment variables as `${VAR}`.
+fn env_fields(source: &SourceSpec) -> Vec<(&str, &str)> {
+    match source {
+        SourceSpec::File(fs) => vec![("filename", fs.filename.as_str())],
+        SourceSpec::Cmd(cmd) => std::iter::once(("command", cmd.command.as_str()))
+            .chain(cmd.args.iter().map(|arg| ("args", arg.as_str())))
+            .collect(),
+        SourceSpec::Http(http) => vec![("url", http.url.as_str())],
+        SourceSpec::Stdin(_) => vec![],
+    }
+}
+
+pub fn validate_project(project: &Project) -> Vec<String> {
+    let mut problems = Vec::new();
+    let tables = &project.spec.tables;
+
+    for name in duplicates(tables.iter().map(|t| t.name.as_str())) {
+        problems.push(format!("duplicate table name '{name}'"));
+    }
+
+    for table in tables {
+        for name in duplicates(table.columns.iter().map(|c| c.name.as_str())) {
+            problems.push(format!("table '{}': duplicate column name '{}'", table.name, name));
+        }
+
+        match &table.source {
+            SourceSpec::Cmd(cmd) => problems.extend(validate_cmd_source(&table.name, cmd)),
+            SourceSpec::Http(http)
+                if !http.url.starts_with("${")
+                    && !http.url.starts_with("http://")
+                    && !http.url.starts_with("https://") =>
+            {
+                problems.push(format!(
+                    "table '{}': http source url '{}' must start with http:// or https://",
+                    table.name, http.url
+                ));
+            }
+            _ => {}
+        }
+        for (field, value) in env_fields(&table.source) {
+            // as for cmd env values, only the syntax is checked; the variables are read when the source is opened
+            if let Err(msg) = interpolate_env(value, |_| Some(String::new())) {
+                problems.push(format!("table '{}': {field}: {msg}", table.name));
+            }
+        }
+
+        for col in &table.columns {
+            problems.extend(validate_capture(table, col));
+            if col.primary_key && col.empty_is_null {
+                problems.push(format!(
+                    "table '{}': primary key column '{}' has emptyIsNull true; key columns cannot be null",
+                    table.name, col.name
+                ));
+            }
+        }
+
+        for rel in &table.relationships {
+            match table.columns.iter().find(|c| c.name == rel.source_column) {
+                None => problems.push(format!(
+                    "table '{}': relationship '{}' source column '{}' does not exist",
+                    table.name, rel.name, rel.source_column
+                )),
+                Some(source) if rel.on_delete == OnDelete::SetNull && !source.empty_is_null => problems.push(format!(
+                    "table '{}': relationship '{}' uses onDelete 'set null' but column '{}' has emptyIsNull false",
+                    table.name, rel.name, rel.source_column
+                )),
+                Some(_) => {}
+            }
+            if rel.is_ambiguous_self_reference(&table.name) {
+                problems.push(format!(
+                    "table '{}': relationship '{}' references its own source column",
+                    table.name, rel.name
+                ));
+            }
+            match tables.iter().find(|t| t.name == rel.target_table) {
+                None => problems.push(format!(
+                    "table '{}': relationship '{}' target table '{}' does not exist",
+                    table.name, rel.name, rel.target_table
+                )),
+                Some(target) if !has_column(target, &rel.target_column) => problems.push(format!(
+                    "table '{}': relationship '{}' target column '{}' does not exist on table '{}'",
+                    table.name, rel.name, rel.target_column, target.name
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+
+    problems
+}
+
+/// Whether `column` of `target` is unique or, on its own, the primary key of `target`.
+pub fn is_key_column(target: &TableSpec, column: &str) -> bool {
+    if target.columns.iter().any(|c| c.name == column && c.unique) {
+        return true;
+    }
+    let key: Vec<&str> = target.columns.iter().filter(|c| c.primary_key).map(|c| c.name.as_str()).collect();
+    key == [column]
+}
+
+/// Relationships whose target column is neither unique nor, on its own, the primary key
+/// of the target table, so the database cannot guarantee it identifies a single row.
+pub fn unkeyed_relationship_targets(project: &Project) -> Vec<String> {
+    let tables = &project.spec.tables;
+    let mut warnings = Vec::new();
+    for table in tables {
+        for rel in &table.relationships {
+            let Some(target) = tables.iter().find(|t| t.name == rel.target_table) else { continue };
+            if !target.columns.iter().any(|c| c.name == rel.target_column) {
+                continue;
+            }
+            if !is_key_column(target, &rel.target_column) {
+                warnings.push(format!(
+                    "table '{}': relationship '{}' targets '{}.{}', which is neither a primary key nor unique",
+                    table.name, rel.name, target.name, rel.target_column
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+pub struct ValidateImpl {
+    logger: Box<dyn Logger>,
+    project_io: Box<dyn ProjectIO>,
+}
+
+impl ValidateImpl {
+    pub fn new(logger: Box<dyn Logger>, project_io: Box<dyn ProjectIO>) -> Self {
+        ValidateImpl { logger, project_io }
+    }
+}
+
+#[async_trait]
+impl Validate for ValidateImpl {
+    async fn validate(&self, path: &Path) -> Result<Vec<String>, ValidateError> {
+        let metadata = tokio::fs::metadata(path).await;
+        if metadata.is_err() || !metadata.unwrap().is_dir() {
+            return Err(ValidateError::DirectoryNotFound(path.display().to_string()));
+        }
+
+        let file_path = project_file_path(path).await;
+        if tokio::fs::metadata(&file_path).await.is_err() {
+            return Err(ValidateError::ProjectFileNotFound(file_path.display().to_string()));
+        }
+
+        let project = self.project_io.load(&file_path).await?;
+        let problems = validate_project(&project);
+        for warning in unkeyed_relationship_targets(&project) {
+            self.logger.warn(&warning).await;
+        }
+        self.logger.info(&format!(
+            "validated project '{}': {} problem(s) found",
+            project.name,
+            problems.len()
+        )).await;
+        Ok(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::test_helpers::column_spec;
+    use crate::components::init::init_impl::example_project;
+    use crate::models::{
+        ColumnIdentifier, ColumnSpec, ColumnType, FileSourceSpec, HttpSourceSpec, ProjectSpec, RelationshipSpec,
+        SourceSpec, PROJECT_API_VERSION,
+    };
+
+    fn column(name: &str) -> ColumnSpec {
+        column_spec(name, ColumnIdentifier::Name(name.to_string()), ColumnType::String { max_length: None })
+    }
+
+    fn table(name: &str, columns: Vec<ColumnSpec>, relationships: Vec<RelationshipSpec>) -> TableSpec {
+        TableSpec {
+            name: name.to_string(),
+            description: String::new(),
+            has_header: true,
+            keep_raw_line: false,
+            source: SourceSpec::File(FileSourceSpec {
+                filename: format!("data/{name}.csv"),
+                character_encoding: "utf-8".to_string(),
+                allow_special_files: false,
+                glob: false,
+                sheet: None,
+                csv: Default::default(),
+            }),
+            columns,
+            relationships,
+            filter: None,
+            dedup_by: vec![],
+            included_from: None,
+        }
+    }
+
+    fn relationship(name: &str, source_column: &str, target_table: &str, target_column: &str) -> RelationshipSpec {
+        RelationshipSpec {
+            name: name.to_string(),
+            description: String::new(),
+            source_column: source_column.to_string(),
+            target_table: target_table.to_string(),
+            target_column: target_column.to_string(),
+            on_delete: Default::default(),
+        }
+    }
+
+    fn project(tables: Vec<TableSpec>) -> Project {
+        Project {
+            name: "test".to_string(),
+            api_version: PROJECT_API_VERSION.to_string(),
+            spec: ProjectSpec { tables, includes: vec![], defaults: Default::default() },
+        }
+    }
+
+    #[test]
+    fn example_project_is_valid() {
+        assert!(validate_project(&example_project("test")).is_empty());
+    }
+
+    #[test]
+    fn reports_duplicate_table_names() {
+        let problems = validate_project(&project(vec![
+            table("city", vec![column("name")], vec![]),
+            table("city", vec![column("name")], vec![]),
+        ]));
+        assert_eq!(problems, vec!["duplicate table name 'city'"]);
+    }
+
+    #[test]
+    fn reports_duplicate_column_names() {
+        let problems = validate_project(&project(vec![
+            table("city", vec![column("name"), column("name")], vec![]),
+        ]));
+        assert_eq!(problems, vec!["table 'city': duplicate column name 'name'"]);
+    }
+
+    fn capture(name: &str, source: &str, pattern: &str, group: usize) -> ColumnSpec {
+        ColumnSpec {
+            column_identifier: ColumnIdentifier::Capture {
+                source: source.to_string(),
+                pattern: pattern.to_string(),
+                group,
+            },
+            ..column(name)
+        }
+    }
+
+    #[test]
+    fn reports_capture_problems() {
+        let problems = validate_project(&project(vec![table(
+            "file",
+            vec![
+                column("path"),
+                capture("year", "path", r"(\d{4})", 1),
+                capture("month", "path", r"(\d{4})-(\d{2})", 3),
+                capture("day", "year", "(", 1),
+                capture("ext", "filename", r"\.(\w+)$", 1),
+            ],
+            vec![],
+        )]));
+        assert_eq!(problems.len(), 4, "problems were: {problems:?}");
+        assert_eq!(problems[0], "table 'file': column 'month' uses capture group 3 but the pattern has only 2");
+        assert_eq!(problems[1], "table 'file': column 'day' captures from 'year', which is itself a capture column");
+        assert!(problems[2].starts_with("table 'file': column 'day' has an invalid pattern"));
+        assert_eq!(problems[3], "table 'file': column 'ext' captures from unknown column 'filename'");
+    }
+
+    #[test]
+    fn reports_all_relationship_problems() {
+        let problems = validate_project(&project(vec![
+            table("country", vec![column("name")], vec![]),
+            table("city", vec![column("name")], vec![
+                relationship("in_country", "country", "country", "code"),
+                relationship("in_region", "name", "region", "name"),
+            ]),
+        ]));
+        assert_eq!(problems, vec![
+            "table 'city': relationship 'in_country' source column 'country' does not exist",
+            "table 'city': relationship 'in_country' target column 'code' does not exist on table 'country'",
+            "table 'city': relationship 'in_region' target table 'region' does not exist",
+        ]);
+    }
+
+    fn cmd_source(args: &[&str], stdout: bool) -> CmdSourceSpec {
+        CmdSourceSpec {
+            // a command that cannot exist, so any attempt to run it would fail loudly
+            command: "/nonexistent/generate.sh".to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            stdout,
+            character_encoding: "utf-8".to_string(),
+            csv: Default::default(),
+            timeout_seconds: None,
+            env: vec![("API_TOKEN".to_string(), "${SECRET_THAT_IS_NOT_SET}".to_string())],
+            capture_stderr: false,
+            retries: 0,
+            retry_delay_seconds: 0,
+            allowed_exit_codes: vec![0],
+        }
+    }
+
+    #[test]
+    fn reports_set_null_on_column_that_is_never_null() {
+        let mut rel = relationship("in_country", "country", "country", "name");
+        rel.on_delete = OnDelete::SetNull;
+        let mut city = table("city", vec![column("name"), column("country")], vec![rel]);
+        city.columns[1].empty_is_null = false;
+        let problems = validate_project(&project(vec![table("country", vec![column("name")], vec![]), city]));
+        assert_eq!(problems, vec![
+            "table 'city': relationship 'in_country' uses onDelete 'set null' but column 'country' has emptyIsNull false",
+        ]);
+    }
+
+    #[test]
+    fn reports_nullable_primary_key_column() {
+        let mut city = table("city", vec![column("name"), column("country")], vec![]);
+        city.columns[0].primary_key = true;
+        city.columns[1].primary_key = true;
+        city.columns[1].empty_is_null = false;
+        assert_eq!(validate_project(&project(vec![city])), vec![
+            "table 'city': primary key column 'name' has emptyIsNull true; key columns cannot be null",
+        ]);
+    }
+
+    #[test]
+    fn warns_about_relationship_targets_that_are_not_a_primary_key() {
+        let mut country = table("country", vec![column("code"), column("name")], vec![]);
+        let city = table("city", vec![column("country"), column("country_name")], vec![
+            relationship("in_country", "country", "country", "code"),
+            relationship("named_country", "country_name", "country", "name"),
+        ]);
+        country.columns[0].primary_key = true;
+        assert_eq!(unkeyed_relationship_targets(&project(vec![country.clone(), city.clone()])), vec![
+            "table 'city': relationship 'named_country' targets 'country.name', which is neither a primary key nor unique",
+        ]);
+
+        country.columns[1].primary_key = true;
+        assert_eq!(unkeyed_relationship_targets(&project(vec![country.clone(), city.clone()])).len(), 2);
+
+        country.columns[0].unique = true;
+        country.columns[1].unique = true;
+        assert!(unkeyed_relationship_targets(&project(vec![country, city])).is_empty());
+    }
+
+    #[test]
+    fn well_formed_cmd_source_passes() {
+        assert!(validate_cmd_source("employee", &cmd_source(&["--out", "$TEMP_CSV_PATH"], false)).is_empty());
+        assert!(validate_cmd_source("employee", &cmd_source(&[], true)).is_empty());
+    }
+
+    #[test]
+    fn temp_file_cmd_source_without_placeholder_fails() {
+        let problems = validate_cmd_source("employee", &cmd_source(&["--out", "employees.csv"], false));
+        assert_eq!(problems, vec![
+            "table 'employee': cmd source with stdout: false must pass $TEMP_CSV_PATH in its args",
+        ]);
+    }
+
+    #[test]
+    fn cmd_source_with_empty_command_and_bad_env_fails() {
+        let mut cmd = cmd_source(&[], true);
+        cmd.command = "  ".to_string();
+        cmd.env = vec![
+            ("1ST".to_string(), "x".to_string()),
+            ("OK".to_string(), "${UNCLOSED".to_string()),
+        ];
+        assert_eq!(validate_cmd_source("employee", &cmd), vec![
+            "table 'employee': cmd source has an empty command",
+            "table 'employee': env key '1ST' is not a valid variable name",
+            "table 'employee': env 'OK': unterminated '${' in '${UNCLOSED'",
+        ]);
+    }
+
+    #[test]
+    fn validate_project_checks_cmd_sources() {
+        let mut employee = table("employee", vec![column("name")], vec![]);
+        employee.source = SourceSpec::Cmd(cmd_source(&[], false));
+        let problems = validate_project(&project(vec![employee]));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("$TEMP_CSV_PATH"));
+    }
+
+    #[test]
+    fn validate_project_rejects_http_source_without_http_scheme() {
+        let mut city = table("city", vec![column("name")], vec![]);
+        city.source = SourceSpec::Http(HttpSourceSpec {
+            url: "ftp://example.com/cities.csv".to_string(),
+            character_encoding: "utf-8".to_string(),
+            csv: Default::default(),
+            timeout_seconds: None,
+        });
+        assert_eq!(validate_project(&project(vec![city])), vec![
+            "table 'city': http source url 'ftp://example.com/cities.csv' must start with http:// or https://",
+        ]);
+    }
+
+    #[test]
+    fn validate_project_checks_env_references_in_sources_without_reading_them() {
+        let mut city = table("city", vec![column("name")], vec![]);
+        city.source = SourceSpec::Http(HttpSourceSpec {
+            url: "${CITY_API}/cities.csv".to_string(),
+            character_encoding: "utf-8".to_string(),
+            csv: Default::default(),
+            timeout_seconds: None,
+        });
+        let mut employee = table("employee", vec![column("name")], vec![]);
+        employee.source = SourceSpec::Cmd(cmd_source(&["${UNSET_VAR}", "${UNCLOSED"], true));
+        assert_eq!(validate_project(&project(vec![city, employee])), vec![
+            "table 'employee': args: unterminated '${' in '${UNCLOSED'",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn validate_does_not_read_table_sources() {
+        use crate::components::file_system::DiskFileSystem;
+        use crate::components::project_io::YamlProjectIO;
+        use crate::components::project_serialization::YamlProjectSerialization;
+        use crate::components::test_helpers::TestLogger;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let project_io = || -> Box<dyn ProjectIO> {
+            Box::new(YamlProjectIO::new(
+                Box::new(TestLogger),
+                Box::new(DiskFileSystem::new(Box::new(TestLogger))),
+                Box::new(YamlProjectSerialization::new(Box::new(TestLogger))),
+            ))
+        };
+        // none of the example data files exist in the temp directory
+        project_io()
+            .save(&example_project("test"), &project_file_path(tmp.path()).await)
+            .await
+            .unwrap();
+
+        let validate = ValidateImpl::new(Box::new(TestLogger), project_io());
+        let problems = validate.validate(tmp.path()).await.unwrap();
+        assert!(problems.is_empty(), "unexpected problems: {:?}", problems);
+    }
+}
@@ -0,0 +1,20 @@
+//! Loads the tables described by a `dbloada.yaml` project. Build the components
+//! through [`ComponentAssembler`] and drive them via the [`traits::Engine`]:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), dbloada::traits::LoadError> {
+//! use dbloada::{ComponentAssembler, models::LoadOptions};
+//!
+//! let engine = ComponentAssembler::new().engine();
+//! let loaded = engine.load_project(std::path::Path::new("."), &LoadOptions::default()).await?;
+//! println!("{} tables", loaded.tables.len());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod traits;
+pub mod models;
+pub mod components;
+pub mod component_assembler;
+
+pub use component_assembler::ComponentAssembler;
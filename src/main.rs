@@ -1,20 +1,111 @@
-mod traits;
-mod models;
-mod components;
-mod component_assembler;
-
 use std::path::PathBuf;
 use std::process;
-use clap::{Parser, Subcommand};
-use component_assembler::ComponentAssembler;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use dbloada::{ComponentAssembler, models, traits};
 
 #[derive(Parser)]
 #[command(name = "dbloada", version = env!("CARGO_PKG_VERSION"))]
 struct Cli {
+    /// Maximum number of cmd source processes running at the same time
+    #[arg(long, global = true)]
+    max_cmd_processes: Option<usize>,
+
+    /// Let file sources read paths outside the project directory, e.g. shared absolute paths
+    #[arg(long, global = true)]
+    allow_outside_project: bool,
+
+    /// Log more: -v for debug, -vv for trace. Overrides RUST_LOG
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only log errors. Overrides RUST_LOG
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum BoolDisplayArg {
+    Raw,
+    Checkmark,
+    YesNo,
+}
+
+impl From<BoolDisplayArg> for models::BoolDisplay {
+    fn from(arg: BoolDisplayArg) -> Self {
+        match arg {
+            BoolDisplayArg::Raw => models::BoolDisplay::Raw,
+            BoolDisplayArg::Checkmark => models::BoolDisplay::Checkmark,
+            BoolDisplayArg::YesNo => models::BoolDisplay::YesNo,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DialectArg {
+    Postgres,
+    Sqlite,
+    Mysql,
+}
+
+impl From<DialectArg> for models::Dialect {
+    fn from(arg: DialectArg) -> Self {
+        match arg {
+            DialectArg::Postgres => models::Dialect::Postgres,
+            DialectArg::Sqlite => models::Dialect::Sqlite,
+            DialectArg::Mysql => models::Dialect::Mysql,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InitTemplateArg {
+    Full,
+    Minimal,
+}
+
+impl From<InitTemplateArg> for models::InitTemplate {
+    fn from(arg: InitTemplateArg) -> Self {
+        match arg {
+            InitTemplateArg::Full => models::InitTemplate::Full,
+            InitTemplateArg::Minimal => models::InitTemplate::Minimal,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ProjectFormatArg {
+    Yaml,
+    Json,
+}
+
+impl From<ProjectFormatArg> for models::ProjectFormat {
+    fn from(arg: ProjectFormatArg) -> Self {
+        match arg {
+            ProjectFormatArg::Yaml => models::ProjectFormat::Yaml,
+            ProjectFormatArg::Json => models::ProjectFormat::Json,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormatArg {
+    Text,
+    Html,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DiagramFormatArg {
+    Mermaid,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormatArg {
+    Csv,
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Initialize a new dbloada project in the given directory
@@ -30,39 +121,477 @@ enum Commands {
         /// Force initialization even if the directory is not empty
         #[arg(short, long)]
         force: bool,
+
+        /// Replace an existing dbloada.yaml or dbloada.json; --force alone never does
+        #[arg(long)]
+        overwrite_project: bool,
+
+        /// full scaffolds example tables, data and scripts; minimal writes only a project file without tables
+        #[arg(long, value_enum, default_value = "full")]
+        template: InitTemplateArg,
+
+        /// Write the project as dbloada.yaml or dbloada.json
+        #[arg(long, value_enum, default_value = "yaml")]
+        format: ProjectFormatArg,
+    },
+    /// Append a table reading a CSV file to an existing project
+    AddTable {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+
+        /// Name of the new table
+        #[arg(short, long)]
+        name: String,
+
+        /// CSV file to read, relative to the project directory
+        #[arg(short, long)]
+        source: String,
+
+        /// String column to add, read by header name or, with --no-header, by position (repeatable)
+        #[arg(long = "column", value_name = "NAME")]
+        columns: Vec<String>,
+
+        /// The source file has no header row
+        #[arg(long)]
+        no_header: bool,
+
+        /// Replace an existing table of the same name
+        #[arg(short, long)]
+        force: bool,
     },
     /// Load a dbloada project from the given directory
     Load {
         /// Directory containing the dbloada.yaml project file
         #[arg(short, long, default_value = ".")]
         dir: PathBuf,
+
+        /// Write the loaded tables into this SQLite database file
+        #[arg(long)]
+        sqlite: Option<PathBuf>,
+
+        /// Write the loaded tables into Postgres; without a value the DATABASE_URL variable is used
+        #[arg(long, value_name = "URL", num_args = 0..=1, conflicts_with = "sqlite")]
+        postgres_url: Option<Option<String>>,
+
+        /// Write the loaded tables into the database of this connection string (postgres:// or sqlite://)
+        #[arg(long, value_name = "URL", conflicts_with_all = ["sqlite", "postgres_url"])]
+        db_url: Option<String>,
+
+        /// Write the fully resolved project spec to this file before reading any data
+        #[arg(long)]
+        dump_spec: Option<PathBuf>,
+
+        /// Print how long each table took to read, slowest first
+        #[arg(long)]
+        timings: bool,
+
+        /// Print how many rows of each table have a blank cell
+        #[arg(long)]
+        null_report: bool,
+
+        /// How to show the values of bool columns
+        #[arg(long, value_enum, default_value = "raw")]
+        bool_display: BoolDisplayArg,
+
+        /// How to print the loaded tables; html prints only the tables, one <table> each
+        #[arg(long, value_enum, default_value = "text")]
+        format: OutputFormatArg,
+
+        /// Cut printed text cells wider than this, ending them with …; 0 prints cells in full
+        #[arg(long, default_value_t = 60)]
+        max_column_width: usize,
+
+        /// Skip the integrity check and load ordering for this relationship (repeatable)
+        #[arg(long = "skip-relationship", value_name = "NAME")]
+        skip_relationships: Vec<String>,
+
+        /// Only read this table (repeatable); all tables are read by default
+        #[arg(long = "table", value_name = "NAME")]
+        tables: Vec<String>,
+
+        /// Load a .tar.gz bundle holding dbloada.yaml and its data files instead of a directory
+        #[arg(long, conflicts_with = "dir")]
+        bundle: Option<PathBuf>,
+
+        /// Only check that every source is reachable and its columns resolve, without reading rows
+        #[arg(long, conflicts_with_all = ["sqlite", "postgres_url", "db_url"])]
+        dry_run: bool,
+
+        /// Fail if the load would write anything to disk (temp file cmd sources, spec dumps, writers)
+        #[arg(long, conflicts_with_all = ["sqlite", "postgres_url", "db_url", "bundle"])]
+        no_side_effects: bool,
+
+        /// Read at most this many rows of every table
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// When a table fails, still print the tables read before it, then report the error
+        #[arg(long)]
+        partial: bool,
+
+        /// Check relationships row by row and unique columns for duplicates, failing with every violation;
+        /// skipped with --limit
+        #[arg(long, visible_aliases = ["check-integrity", "check-constraints"])]
+        check_relationships: bool,
+
+        /// Read at most this many tables at the same time [default: number of CPUs]
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// Read this table's CSV from standard input instead of its source
+        #[arg(long, value_name = "NAME")]
+        stdin_table: Option<String>,
+    },
+    /// Load a project and print per-column statistics of every table
+    Describe {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+    },
+    /// Print an overview of a project's tables without reading any data
+    #[command(visible_alias = "status")]
+    Info {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
     },
+    /// Check a dbloada project for mistakes without reading any data
+    Validate {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+    },
+    /// Rewrite dbloada.yaml in canonical form
+    Fmt {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+
+        /// Check that the project deserializes back to the same model after re-serializing
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Write the CREATE TABLE statements for every table to an SQL file
+    Ddl {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+
+        /// SQL file to write
+        #[arg(short, long, default_value = "schema.sql")]
+        out: PathBuf,
+
+        /// SQL dialect of the generated statements
+        #[arg(long, value_enum, default_value = "postgres")]
+        dialect: DialectArg,
+    },
+    /// Print a diagram of the project's tables and relationships
+    Diagram {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+
+        /// Diagram language to print
+        #[arg(long, value_enum, default_value = "mermaid")]
+        format: DiagramFormatArg,
+    },
+    /// Load a project and write its tables, as shaped by the spec, to files
+    Export {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+
+        /// Directory to write one file per table into
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Format of the written files
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormatArg,
+
+        /// Leave out the header row
+        #[arg(long)]
+        no_header: bool,
+    },
+    /// Show which source field each column of every table reads
+    Explain {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+
+        /// Print the mapping as JSON for tools
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print an example dbloada.yaml to copy and adapt
+    Example,
+    /// Print which components are wired together, the log level and relevant environment variables
+    Doctor,
+    /// Run all cmd sources once and rewrite the project to read their output from static CSV files
+    Freeze {
+        /// Directory containing the dbloada.yaml project file
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+    },
+}
+
+fn print_tables(tables: &[models::Table], format: OutputFormatArg, options: &models::TableStringOptions) {
+    for table in tables {
+        match format {
+            OutputFormatArg::Text => print!("{}", models::table_to_string_with_opts(table, &table.column_types, options)),
+            OutputFormatArg::Html => print!("{}", models::table_to_html(table)),
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
-    let assembler = ComponentAssembler::new();
+    let mut assembler = ComponentAssembler::new();
+    if let Some(max_cmd_processes) = cli.max_cmd_processes {
+        assembler = assembler.with_max_cmd_processes(max_cmd_processes);
+    }
+    if cli.allow_outside_project {
+        assembler = assembler.with_allow_outside_project(true);
+    }
+    assembler = assembler.with_log_flags(cli.verbose, cli.quiet);
     let engine = assembler.engine();
 
     match cli.command {
-        Commands::Init { dir, name, force } => {
-            if let Err(e) = engine.init_project_dir(&dir, name.as_deref(), force).await {
+        Commands::Init { dir, name, force, overwrite_project, template, format } => {
+            let result = engine
+                .init_project_dir(&dir, name.as_deref(), force, overwrite_project, template.into(), format.into())
+                .await;
+            if let Err(e) = result {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::AddTable { dir, name, source, columns, no_header, force } => {
+            if let Err(e) = engine.add_table(&dir, &name, &source, !no_header, &columns, force).await {
                 eprintln!("Error: {e}");
                 process::exit(1);
             }
+            println!("added table '{name}'");
         }
-        Commands::Load { dir } => {
-            let loaded_project = match engine.load_project(&dir).await {
+        Commands::Load { dir, sqlite, postgres_url, db_url, dump_spec, timings, null_report, bool_display, format, max_column_width, skip_relationships, tables, bundle, dry_run, no_side_effects, limit, partial, check_relationships, jobs, stdin_table } => {
+            let options = models::LoadOptions {
+                dump_spec, skip_relationships, tables, dry_run, no_side_effects, limit, partial, check_relationships,
+                jobs, stdin_table,
+            };
+            let postgres_url = match postgres_url {
+                Some(None) => match std::env::var("DATABASE_URL") {
+                    Ok(url) => Some(url),
+                    Err(_) => {
+                        eprintln!("Error: --postgres-url needs a URL when DATABASE_URL is not set");
+                        process::exit(1);
+                    }
+                },
+                Some(Some(url)) => Some(url),
+                None => None,
+            };
+            let database = db_url.map(|url| match models::parse_connection_string(&url) {
+                Ok(connection) => connection,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            });
+            let writer = match (sqlite, postgres_url, database) {
+                (Some(sqlite_path), _, _) => Some(assembler.sqlite_table_writer(&sqlite_path)),
+                (None, Some(url), _) => Some(assembler.postgres_table_writer(&url)),
+                (None, None, Some(connection)) => match assembler.database_table_writer(&connection) {
+                    Some(writer) => Some(writer),
+                    None => {
+                        eprintln!("Error: --db-url: loading into MySQL is not supported yet");
+                        process::exit(1);
+                    }
+                },
+                (None, None, None) => None,
+            };
+            let table_options = models::TableStringOptions {
+                bool_display: bool_display.into(),
+                max_column_width: (max_column_width > 0).then_some(max_column_width),
+            };
+            let loaded = match &bundle {
+                Some(bundle) => engine.load_bundle(bundle, &options).await,
+                None => engine.load_project(&dir, &options).await,
+            };
+            let loaded_project = match loaded {
+                Ok(loaded_project) => loaded_project,
+                Err(traits::LoadError::Partial { tables, error }) => {
+                    print_tables(&tables, format, &table_options);
+                    eprintln!("Error: {error}");
+                    process::exit(1);
+                }
+                Err(traits::LoadError::DanglingReferences(dangling)) => {
+                    for d in &dangling {
+                        eprintln!("{}: row {}: '{}' not found ({})", d.table, d.row, d.value, d.relationship);
+                    }
+                    eprintln!("Error: {} dangling reference(s)", dangling.len());
+                    process::exit(1);
+                }
+                Err(traits::LoadError::DuplicateValues(duplicates)) => {
+                    for d in &duplicates {
+                        let rows: Vec<String> = d.rows.iter().map(|r| r.to_string()).collect();
+                        eprintln!("{}.{}: '{}' appears in rows {}", d.table, d.column, d.value, rows.join(", "));
+                    }
+                    eprintln!("Error: {} duplicate value(s) in unique columns", duplicates.len());
+                    process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            };
+            if dry_run {
+                println!("dry run: {} table(s) checked, no rows read", loaded_project.tables.len());
+                return;
+            }
+            if let OutputFormatArg::Text = format {
+                println!("{:#?}", loaded_project.project);
+            }
+            print_tables(&loaded_project.tables, format, &table_options);
+            for relationship in &loaded_project.report.skipped_relationships {
+                eprintln!("note: skipped relationship {relationship}");
+            }
+            if timings {
+                println!("table timings:");
+                print!("{}", models::timings_to_string(&loaded_project.report));
+            }
+            if null_report {
+                println!("blank cells:");
+                print!("{}", models::blanks_to_string(&loaded_project.report));
+            }
+            if let Some(writer) = writer
+                && let Err(e) = engine.write_tables(&loaded_project, writer.as_ref()).await
+            {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+        }
+        Commands::Describe { dir } => {
+            let loaded_project = match engine.load_project(&dir, &models::LoadOptions::default()).await {
                 Ok(loaded_project) => loaded_project,
                 Err(e) => {
                     eprintln!("Error: {e}");
                     process::exit(1);
                 }
             };
-            println!("{:#?}", loaded_project.project);
             for table in &loaded_project.tables {
-                print!("{}", models::table_to_string(table));
+                print!("{}", models::describe_table(table));
+            }
+        }
+        Commands::Info { dir } => {
+            match engine.project_info(&dir).await {
+                Ok(project) => print!("{}", models::project_summary(&project)),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Validate { dir } => {
+            let problems = match engine.validate_project(&dir).await {
+                Ok(problems) => problems,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            };
+            if problems.is_empty() {
+                println!("project is valid");
+            } else {
+                for problem in &problems {
+                    eprintln!("- {problem}");
+                }
+                eprintln!("Error: {} problem(s) found", problems.len());
+                process::exit(1);
+            }
+        }
+        Commands::Fmt { dir, verify } => {
+            match engine.format_project(&dir, verify).await {
+                Ok(true) => println!("formatted dbloada.yaml"),
+                Ok(false) => println!("dbloada.yaml is already formatted"),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Ddl { dir, out, dialect } => {
+            match engine.write_ddl(&dir, &out, dialect.into()).await {
+                Ok(count) => println!("wrote {} table(s) to {}", count, out.display()),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Diagram { dir, format } => {
+            let diagram = match format {
+                DiagramFormatArg::Mermaid => engine.mermaid_diagram(&dir).await,
+            };
+            match diagram {
+                Ok(diagram) => print!("{diagram}"),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Export { dir, out, format, no_header } => {
+            let loaded_project = match engine.load_project(&dir, &models::LoadOptions::default()).await {
+                Ok(loaded_project) => loaded_project,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            };
+            let writer = match format {
+                ExportFormatArg::Csv => assembler.csv_table_writer(&out, !no_header),
+            };
+            if let Err(e) = engine.write_tables(&loaded_project, writer.as_ref()).await {
+                eprintln!("Error: {e}");
+                process::exit(1);
+            }
+            println!("exported {} table(s) to {}", loaded_project.tables.len(), out.display());
+        }
+        Commands::Explain { dir, json } => {
+            let mappings = match engine.explain_project(&dir).await {
+                Ok(mappings) => mappings,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            };
+            if json {
+                println!("{}", models::mappings_to_json(&mappings));
+            } else {
+                print!("{}", models::mappings_to_string(&mappings));
+            }
+        }
+        Commands::Doctor => print!("{}", assembler.doctor_report()),
+        Commands::Example => {
+            match engine.example_project_yaml() {
+                Ok(yaml) => print!("{yaml}"),
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            }
+        }
+        Commands::Freeze { dir } => {
+            let frozen = match engine.freeze_project(&dir).await {
+                Ok(frozen) => frozen,
+                Err(e) => {
+                    eprintln!("Error: {e}");
+                    process::exit(1);
+                }
+            };
+            for table_name in frozen {
+                println!("froze table '{table_name}'");
             }
         }
     }
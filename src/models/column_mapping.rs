@@ -0,0 +1,68 @@
+use serde_json::{Map, Value, json};
+use super::project::ColumnIdentifier;
+
+/// Where an output column reads its value from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMapping {
+    pub output_column: String,
+    pub identifier: ColumnIdentifier,
+    /// Field index in the source rows; `None` when the reader cannot tell without reading the data.
+    pub resolved_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableMapping {
+    pub table_name: String,
+    pub columns: Vec<ColumnMapping>,
+}
+
+fn identifier_to_json(identifier: &ColumnIdentifier) -> Value {
+    match identifier {
+        ColumnIdentifier::Index(i) => json!({ "kind": "index", "value": i }),
+        ColumnIdentifier::Name(name) => json!({ "kind": "name", "value": name }),
+        ColumnIdentifier::Capture { source, pattern, group } => json!({
+            "kind": "capture",
+            "value": { "source": source, "pattern": pattern, "group": group },
+        }),
+    }
+}
+
+fn identifier_to_string(identifier: &ColumnIdentifier) -> String {
+    match identifier {
+        ColumnIdentifier::Index(i) => format!("index {i}"),
+        ColumnIdentifier::Name(name) => format!("header '{name}'"),
+        ColumnIdentifier::Capture { source, pattern, group } => format!("group {group} of '{pattern}' on {source}"),
+    }
+}
+
+/// A JSON object with an array of column mappings per table name.
+pub fn mappings_to_json(mappings: &[TableMapping]) -> String {
+    let tables: Map<String, Value> = mappings
+        .iter()
+        .map(|table| {
+            let columns = table
+                .columns
+                .iter()
+                .map(|c| json!({
+                    "output_column": c.output_column,
+                    "identifier": identifier_to_json(&c.identifier),
+                    "resolved_index": c.resolved_index,
+                }))
+                .collect();
+            (table.table_name.clone(), Value::Array(columns))
+        })
+        .collect();
+    serde_json::to_string_pretty(&Value::Object(tables)).unwrap_or_default()
+}
+
+pub fn mappings_to_string(mappings: &[TableMapping]) -> String {
+    let mut out = String::new();
+    for table in mappings {
+        out.push_str(&format!("{}:\n", table.table_name));
+        for c in &table.columns {
+            let index = c.resolved_index.map_or("unresolved".to_string(), |i| format!("field {i}"));
+            out.push_str(&format!("  {} <- {} ({})\n", c.output_column, identifier_to_string(&c.identifier), index));
+        }
+    }
+    out
+}
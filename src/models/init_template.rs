@@ -0,0 +1,9 @@
+/// What `dbloada init` scaffolds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InitTemplate {
+    /// The example project with its sample tables, data files and scripts.
+    #[default]
+    Full,
+    /// Only a dbloada.yaml without tables.
+    Minimal,
+}
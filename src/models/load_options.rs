@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadOptions {
+    /// Write the fully resolved project spec to this path before any table is read.
+    pub dump_spec: Option<PathBuf>,
+    /// Relationship names left out of the referential-integrity check and the load ordering.
+    pub skip_relationships: Vec<String>,
+    /// Only read these tables; all tables are read when empty.
+    pub tables: Vec<String>,
+    /// Only check that sources are reachable and columns resolve; no rows are read.
+    pub dry_run: bool,
+    /// Fail before reading anything if the load would write to disk.
+    pub no_side_effects: bool,
+    /// Read at most this many rows of every table; a source's own lower limit is kept.
+    pub limit: Option<usize>,
+    /// On a failing table, return the tables read so far in `LoadError::Partial`.
+    pub partial: bool,
+    /// Check every relationship row by row and fail with all dangling references, then fail with every
+    /// duplicate value in a unique column. Skipped when `limit` is set.
+    pub check_relationships: bool,
+    /// Read at most this many tables at the same time; one per CPU when unset.
+    pub jobs: Option<usize>,
+    /// Read this table from standard input instead of its own source, keeping its encoding and CSV options.
+    pub stdin_table: Option<String>,
+}
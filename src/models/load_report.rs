@@ -0,0 +1,194 @@
+use std::time::Duration;
+use super::table::Table;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableTiming {
+    pub table_name: String,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableBlanks {
+    pub table_name: String,
+    /// Rows with at least one blank cell.
+    pub blank_rows: usize,
+    pub total_rows: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    /// Wall-clock time spent reading each table, in load order.
+    pub table_timings: Vec<TableTiming>,
+    /// Relationships, as `table.relationship`, skipped on request: they are left out of the load
+    /// order, the integrity checks and the foreign keys of database writers.
+    pub skipped_relationships: Vec<String>,
+    /// Blank cell counts for each table, in load order.
+    pub table_blanks: Vec<TableBlanks>,
+}
+
+/// A relationship source value with no matching target value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DanglingReference {
+    pub table: String,
+    pub relationship: String,
+    /// Zero-based data row in the source table.
+    pub row: usize,
+    pub value: String,
+}
+
+/// Lists up to `max` dangling references, noting how many more were found.
+pub fn dangling_references_to_string(references: &[DanglingReference], max: usize) -> String {
+    let mut parts: Vec<String> = references
+        .iter()
+        .take(max)
+        .map(|r| format!("{} row {}: '{}' ({})", r.table, r.row, r.value, r.relationship))
+        .collect();
+    if references.len() > max {
+        parts.push(format!("and {} more", references.len() - max));
+    }
+    parts.join("; ")
+}
+
+/// A value held by more than one row of a unique column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateValue {
+    pub table: String,
+    pub column: String,
+    pub value: String,
+    /// Zero-based data rows holding the value.
+    pub rows: Vec<usize>,
+}
+
+/// Lists up to `max` duplicated values, noting how many more were found.
+pub fn duplicate_values_to_string(duplicates: &[DuplicateValue], max: usize) -> String {
+    let mut parts: Vec<String> = duplicates
+        .iter()
+        .take(max)
+        .map(|d| {
+            let rows: Vec<String> = d.rows.iter().map(|r| r.to_string()).collect();
+            format!("{}.{} '{}' in rows {}", d.table, d.column, d.value, rows.join(", "))
+        })
+        .collect();
+    if duplicates.len() > max {
+        parts.push(format!("and {} more", duplicates.len() - max));
+    }
+    parts.join("; ")
+}
+
+impl LoadReport {
+    pub fn timings_slowest_first(&self) -> Vec<&TableTiming> {
+        let mut timings: Vec<&TableTiming> = self.table_timings.iter().collect();
+        timings.sort_by_key(|t| std::cmp::Reverse(t.duration));
+        timings
+    }
+}
+
+/// Counts the rows having at least one empty or whitespace-only cell.
+pub fn count_blank_rows(table: &Table) -> usize {
+    table
+        .rows
+        .iter()
+        .filter(|row| row.len() < table.num_columns() || row.iter().any(|cell| cell.trim().is_empty()))
+        .count()
+}
+
+pub fn blanks_to_string(report: &LoadReport) -> String {
+    let width = report.table_blanks.iter().map(|b| b.table_name.len()).max().unwrap_or(0);
+    report
+        .table_blanks
+        .iter()
+        .map(|b| format!("{:<width$}  {} of {} rows with blank cells\n", b.table_name, b.blank_rows, b.total_rows))
+        .collect()
+}
+
+pub fn timings_to_string(report: &LoadReport) -> String {
+    let timings = report.timings_slowest_first();
+    let width = timings.iter().map(|t| t.table_name.len()).max().unwrap_or(0);
+    timings
+        .iter()
+        .map(|t| format!("{:<width$}  {:>10.3} ms\n", t.table_name, t.duration.as_secs_f64() * 1000.0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ColumnType;
+
+    fn timing(table_name: &str, millis: u64) -> TableTiming {
+        TableTiming {
+            table_name: table_name.to_string(),
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn dangling_references_to_string_caps_the_list() {
+        let reference = |row: usize, value: &str| DanglingReference {
+            table: "office".to_string(),
+            relationship: "located_in".to_string(),
+            row,
+            value: value.to_string(),
+        };
+        let references = vec![reference(0, "Berlin"), reference(4, "Oslo"), reference(7, "Rome")];
+        assert_eq!(
+            dangling_references_to_string(&references, 2),
+            "office row 0: 'Berlin' (located_in); office row 4: 'Oslo' (located_in); and 1 more"
+        );
+    }
+
+    #[test]
+    fn duplicate_values_to_string_lists_rows() {
+        let duplicate = |value: &str, rows: Vec<usize>| DuplicateValue {
+            table: "country".to_string(),
+            column: "code".to_string(),
+            value: value.to_string(),
+            rows,
+        };
+        assert_eq!(
+            duplicate_values_to_string(&[duplicate("SE", vec![0, 3]), duplicate("NO", vec![1, 2, 5])], 1),
+            "country.code 'SE' in rows 0, 3; and 1 more"
+        );
+    }
+
+    #[test]
+    fn count_blank_rows_counts_rows_with_any_blank_cell() {
+        let row = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let table = Table::new(
+            "office".to_string(),
+            vec!["name".to_string(), "city".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
+            vec![row(&["Star Tower", "London"]), row(&["Annex", ""]), row(&["  ", "Berlin"]), row(&["Depot"])],
+        );
+        assert_eq!(count_blank_rows(&table), 3);
+    }
+
+    #[test]
+    fn blanks_to_string_aligns_table_names() {
+        let report = LoadReport {
+            table_blanks: vec![
+                TableBlanks { table_name: "city".to_string(), blank_rows: 0, total_rows: 2 },
+                TableBlanks { table_name: "office".to_string(), blank_rows: 1, total_rows: 3 },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            blanks_to_string(&report),
+            "city    0 of 2 rows with blank cells\noffice  1 of 3 rows with blank cells\n"
+        );
+    }
+
+    #[test]
+    fn timings_to_string_sorts_slowest_first() {
+        let report = LoadReport {
+            table_timings: vec![timing("city", 5), timing("employee", 1250), timing("country", 1)],
+            ..Default::default()
+        };
+        assert_eq!(
+            timings_to_string(&report),
+            "employee    1250.000 ms\n\
+             city           5.000 ms\n\
+             country        1.000 ms\n"
+        );
+    }
+}
@@ -1,9 +1,35 @@
 pub mod project;
 pub mod table;
+pub mod dialect;
+pub mod connection_string;
+pub mod init_template;
+pub mod project_format;
+pub mod load_options;
+pub mod load_report;
+pub mod column_mapping;
+pub mod table_stats;
+pub mod project_summary;
+pub mod resource_name;
 
 pub use project::{
-    PROJECT_API_VERSION, PROJECT_KIND,
-    Project, ProjectSpec, LoadedProject, TableSpec, SourceSpec, FileSourceSpec, CmdSourceSpec,
-    ColumnSpec, ColumnIdentifier, ColumnType, RelationshipSpec,
+    PROJECT_API_VERSION, PROJECT_KIND, RAW_LINE_COLUMN, AUTO_CHARACTER_ENCODING, DEFAULT_CHARACTER_ENCODING, effective_character_encoding,
+    Project, ProjectSpec, ProjectDefaults, LoadedProject, TableSpec, SourceSpec, FileSourceSpec, CmdSourceSpec, HttpSourceSpec, StdinSourceSpec,
+    ColumnSpec, ColumnIdentifier, ColumnType, RelationshipSpec, OnDelete, RowFilter, FilterOp, CsvOptions, TrimMode, RaggedRowMode,
+    parse_bool,
+};
+pub use table::{
+    Table, TableError, BoolDisplay, TableStringOptions, table_to_html, table_to_string_with_opts, table_to_string_with_types,
+};
+pub use dialect::Dialect;
+pub use connection_string::{ConnectionString, ConnectionStringError, parse_connection_string};
+pub use init_template::InitTemplate;
+pub use project_format::ProjectFormat;
+pub use load_options::LoadOptions;
+pub use table_stats::{ColumnStats, column_stats, describe_table};
+pub use project_summary::project_summary;
+pub use resource_name::{sanitize_resource_name, validate_resource_name};
+pub use column_mapping::{ColumnMapping, TableMapping, mappings_to_json, mappings_to_string};
+pub use load_report::{
+    DanglingReference, DuplicateValue, LoadReport, dangling_references_to_string, duplicate_values_to_string, TableBlanks, TableTiming,
+    blanks_to_string, count_blank_rows, timings_to_string,
 };
-pub use table::{Table, table_to_string};
@@ -1,5 +1,16 @@
 pub const PROJECT_API_VERSION: &str = "project.dbloada.io/v1";
 pub const PROJECT_KIND: &str = "DBLoadaProject";
+/// Column appended to tables with `keep_raw_line`, holding each row's original line.
+pub const RAW_LINE_COLUMN: &str = "__raw";
+/// Encoding of sources that neither pin one nor inherit a project default.
+pub const DEFAULT_CHARACTER_ENCODING: &str = "utf-8";
+/// Encoding label that detects the encoding from the bytes that were read.
+pub const AUTO_CHARACTER_ENCODING: &str = "auto";
+
+/// Picks a source's encoding: its own pinned encoding, else the project default, else utf-8.
+pub fn effective_character_encoding<'a>(source: Option<&'a str>, project_default: Option<&'a str>) -> &'a str {
+    source.or(project_default).unwrap_or(DEFAULT_CHARACTER_ENCODING)
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Project {
@@ -11,12 +22,34 @@ pub struct Project {
 #[derive(Debug, Clone, PartialEq)]
 pub struct ProjectSpec {
     pub tables: Vec<TableSpec>,
+    /// Files, relative to the project directory, whose tables are merged into `tables` on load.
+    pub includes: Vec<String>,
+    /// `spec.defaults` as written in the project file. The tables already hold the resolved values;
+    /// these are kept so that saving writes each default once instead of pinning it on every table.
+    pub defaults: ProjectDefaults,
+}
+
+/// Values for tables, sources and columns that leave them out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectDefaults {
+    pub character_encoding: Option<String>,
+    pub has_header: Option<bool>,
+    pub ignore_header_case: Option<bool>,
+}
+
+impl ProjectSpec {
+    /// The spec with included tables inlined and no includes, as one self-contained file.
+    pub fn flattened(&self) -> ProjectSpec {
+        let tables = self.tables.iter().map(|t| TableSpec { included_from: None, ..t.clone() }).collect();
+        ProjectSpec { tables, includes: vec![], defaults: self.defaults.clone() }
+    }
 }
 
 #[derive(Debug)]
 pub struct LoadedProject {
     pub project: Project,
     pub tables: Vec<super::table::Table>,
+    pub report: super::load_report::LoadReport,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -24,21 +57,38 @@ pub struct TableSpec {
     pub name: String,
     pub description: String,
     pub has_header: bool,
+    /// Append a `__raw` column holding each row's original line.
+    pub keep_raw_line: bool,
     pub source: SourceSpec,
     pub columns: Vec<ColumnSpec>,
     pub relationships: Vec<RelationshipSpec>,
+    /// Only rows matching this are read into the table.
+    pub filter: Option<RowFilter>,
+    /// Columns whose combined value identifies a row; later rows with the same values are dropped.
+    pub dedup_by: Vec<String>,
+    /// The included file the table was read from. Such tables are not written back to the project file.
+    pub included_from: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SourceSpec {
     File(FileSourceSpec),
     Cmd(CmdSourceSpec),
+    Http(HttpSourceSpec),
+    Stdin(StdinSourceSpec),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FileSourceSpec {
     pub filename: String,
     pub character_encoding: String,
+    /// Permit reading from FIFOs and character devices instead of only regular files.
+    pub allow_special_files: bool,
+    /// Treat `filename` as a glob pattern and concatenate every matching file, in sorted order.
+    pub glob: bool,
+    /// Worksheet to read from a spreadsheet source; the first sheet when `None`.
+    pub sheet: Option<String>,
+    pub csv: CsvOptions,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -47,6 +97,170 @@ pub struct CmdSourceSpec {
     pub args: Vec<String>,
     pub stdout: bool,
     pub character_encoding: String,
+    pub csv: CsvOptions,
+    /// Kill the command and fail the read if it runs longer than this.
+    pub timeout_seconds: Option<u64>,
+    /// Extra environment variables for the command. Values may reference variables of the
+    /// dbloada process as `${VAR}`; entries here override inherited variables of the same name.
+    pub env: Vec<(String, String)>,
+    /// In temp file mode, log the command's stderr instead of passing it through to the terminal.
+    pub capture_stderr: bool,
+    /// Run the command again this many times when it fails to start or exits non-zero.
+    pub retries: u32,
+    /// Seconds to wait before each retry.
+    pub retry_delay_seconds: u64,
+    /// Exit codes that count as success.
+    pub allowed_exit_codes: Vec<i32>,
+}
+
+/// A CSV fetched with a GET request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpSourceSpec {
+    pub url: String,
+    pub character_encoding: String,
+    pub csv: CsvOptions,
+    /// Fail the read if the response has not fully arrived after this long.
+    pub timeout_seconds: Option<u64>,
+}
+
+/// Reads CSV from standard input; at most one table per load may do so.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StdinSourceSpec {
+    pub character_encoding: String,
+    pub csv: CsvOptions,
+}
+
+/// Parsing options shared by every source that produces CSV text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvOptions {
+    /// Field delimiter; a comma when unset. Always a single ASCII character.
+    pub delimiter: Option<char>,
+    pub trim: TrimMode,
+    pub on_ragged_row: RaggedRowMode,
+    /// Stop after this many data rows; the header row does not count.
+    pub limit: Option<usize>,
+    /// Keep parsing past bad rows and report up to this many of them at the end.
+    /// `None` or `Some(0)` fails on the first bad row.
+    pub max_errors: Option<usize>,
+    /// Lines starting with this ASCII character are skipped, including before the header.
+    pub comment: Option<char>,
+    /// Physical lines dropped from the start of the source before the header (or first row) is read.
+    pub skip_rows: usize,
+    /// Keep the quotes of a field whose opening quote follows whitespace, as in `"a", "b"`. The CSV
+    /// reader does not treat such quotes as quoting, so by default they are removed from the value.
+    pub keep_quotes: bool,
+}
+
+/// Which parts of a CSV source have surrounding whitespace removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrimMode {
+    None,
+    Headers,
+    Fields,
+    #[default]
+    All,
+}
+
+impl TrimMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrimMode::None => "none",
+            TrimMode::Headers => "headers",
+            TrimMode::Fields => "fields",
+            TrimMode::All => "all",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<TrimMode, String> {
+        match s {
+            "none" => Ok(TrimMode::None),
+            "headers" => Ok(TrimMode::Headers),
+            "fields" => Ok(TrimMode::Fields),
+            "all" => Ok(TrimMode::All),
+            other => Err(format!("unknown trim mode '{other}', expected none, headers, fields or all")),
+        }
+    }
+}
+
+/// What to do with a data row whose field count differs from the header (or the
+/// first row without a header), or that lacks a referenced column index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RaggedRowMode {
+    /// Fail the parse.
+    Error,
+    /// Missing fields are empty and extra fields are ignored.
+    #[default]
+    Pad,
+    /// Extra fields are ignored, but missing fields fail the parse.
+    Truncate,
+}
+
+impl RaggedRowMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RaggedRowMode::Error => "error",
+            RaggedRowMode::Pad => "pad",
+            RaggedRowMode::Truncate => "truncate",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<RaggedRowMode, String> {
+        match s {
+            "error" => Ok(RaggedRowMode::Error),
+            "pad" => Ok(RaggedRowMode::Pad),
+            "truncate" => Ok(RaggedRowMode::Truncate),
+            other => Err(format!("unknown ragged row mode '{other}', expected error, pad or truncate")),
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn delimiter_byte(&self) -> u8 {
+        self.delimiter.map(|c| c as u8).unwrap_or(b',')
+    }
+
+    pub fn comment_byte(&self) -> Option<u8> {
+        self.comment.map(|c| c as u8)
+    }
+}
+
+impl SourceSpec {
+    /// The `type` the source is written with in the project file.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SourceSpec::File(_) => "file",
+            SourceSpec::Cmd(_) => "cmd",
+            SourceSpec::Http(_) => "http",
+            SourceSpec::Stdin(_) => "stdin",
+        }
+    }
+
+    pub fn csv_options(&self) -> &CsvOptions {
+        match self {
+            SourceSpec::File(fs) => &fs.csv,
+            SourceSpec::Cmd(cs) => &cs.csv,
+            SourceSpec::Http(hs) => &hs.csv,
+            SourceSpec::Stdin(ss) => &ss.csv,
+        }
+    }
+
+    pub fn character_encoding(&self) -> &str {
+        match self {
+            SourceSpec::File(fs) => &fs.character_encoding,
+            SourceSpec::Cmd(cs) => &cs.character_encoding,
+            SourceSpec::Http(hs) => &hs.character_encoding,
+            SourceSpec::Stdin(ss) => &ss.character_encoding,
+        }
+    }
+
+    pub fn csv_options_mut(&mut self) -> &mut CsvOptions {
+        match self {
+            SourceSpec::File(fs) => &mut fs.csv,
+            SourceSpec::Cmd(cs) => &mut cs.csv,
+            SourceSpec::Http(hs) => &mut hs.csv,
+            SourceSpec::Stdin(ss) => &mut ss.csv,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -55,17 +269,48 @@ pub struct ColumnSpec {
     pub description: String,
     pub column_identifier: ColumnIdentifier,
     pub column_type: ColumnType,
+    /// Write empty cells as NULL; when false they are written as the empty value of the column type.
+    pub empty_is_null: bool,
+    /// Part of the table's primary key; several columns form a composite key.
+    pub primary_key: bool,
+    /// No two rows may hold the same value; empty cells read as NULL are exempt.
+    pub unique: bool,
+    /// Match a name identifier against the headers without regard to case.
+    pub ignore_header_case: bool,
+    /// Other header names tried, in order, when a name identifier matches no header.
+    pub aliases: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ColumnIdentifier {
     Index(u64),
     Name(String),
+    /// Capture group `group` of `pattern` applied to the value of the column named `source`.
+    /// Rows where the pattern does not match get an empty value.
+    Capture { source: String, pattern: String, group: usize },
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ColumnType {
-    String,
+    String { max_length: Option<u64> },
+    Int64,
+    Bool,
+}
+
+impl ColumnType {
+    /// Numeric columns are right-aligned when tables are printed.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, ColumnType::Int64)
+    }
+}
+
+/// Parses the boolean tokens accepted in `bool` columns, ignoring case.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "1" => Some(true),
+        "false" | "f" | "no" | "n" | "0" => Some(false),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -75,4 +320,114 @@ pub struct RelationshipSpec {
     pub source_column: String,
     pub target_table: String,
     pub target_column: String,
+    /// What the database does with referencing rows when the target row is deleted; only used in DDL.
+    pub on_delete: OnDelete,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OnDelete {
+    #[default]
+    NoAction,
+    Cascade,
+    Restrict,
+    SetNull,
+}
+
+impl OnDelete {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OnDelete::NoAction => "no action",
+            OnDelete::Cascade => "cascade",
+            OnDelete::Restrict => "restrict",
+            OnDelete::SetNull => "set null",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<OnDelete, String> {
+        match s {
+            "no action" => Ok(OnDelete::NoAction),
+            "cascade" => Ok(OnDelete::Cascade),
+            "restrict" => Ok(OnDelete::Restrict),
+            "set null" => Ok(OnDelete::SetNull),
+            other => Err(format!("unknown onDelete '{other}', expected cascade, restrict, set null or no action")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterOp {
+    Eq,
+    Ne,
+}
+
+impl FilterOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterOp::Eq => "==",
+            FilterOp::Ne => "!=",
+        }
+    }
+}
+
+/// A `column op value` comparison against the text of a column, e.g. `status == "active"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowFilter {
+    pub column: String,
+    pub op: FilterOp,
+    pub value: String,
+}
+
+impl RowFilter {
+    /// Parses `column == value` or `column != value`. The value is a single word or a
+    /// double-quoted string, which may be empty and holds no double quotes.
+    pub fn parse(s: &str) -> Result<RowFilter, String> {
+        let (at, op) = [FilterOp::Eq, FilterOp::Ne]
+            .into_iter()
+            .filter_map(|op| s.find(op.as_str()).map(|at| (at, op)))
+            .min_by_key(|&(at, _)| at)
+            .ok_or("expected 'column == value' or 'column != value'")?;
+        let column = s[..at].trim();
+        let value = s[at + 2..].trim();
+        if column.is_empty() || column.contains(char::is_whitespace) {
+            return Err(format!("expected a single column name before '{}'", op.as_str()));
+        }
+        let value = match value.strip_prefix('"') {
+            Some(quoted) => match quoted.strip_suffix('"') {
+                Some(inner) if !inner.contains('"') => inner,
+                _ => return Err(format!("unterminated or malformed quoted value {value}")),
+            },
+            None if value.is_empty() => return Err(format!("expected a value after '{}'", op.as_str())),
+            None if value.contains(char::is_whitespace) || value.contains('"') => {
+                return Err(format!("value '{value}' must be a single word or a double-quoted string"));
+            }
+            None => value,
+        };
+        Ok(RowFilter { column: column.to_string(), op, value: value.to_string() })
+    }
+
+    pub fn matches(&self, value: &str) -> bool {
+        match self.op {
+            FilterOp::Eq => value == self.value,
+            FilterOp::Ne => value != self.value,
+        }
+    }
+}
+
+impl std::fmt::Display for RowFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} \"{}\"", self.column, self.op.as_str(), self.value)
+    }
+}
+
+impl RelationshipSpec {
+    /// A self reference points back at the table that owns the relationship,
+    /// e.g. `employee.manager -> employee.name`.
+    pub fn is_self_reference(&self, owner_table: &str) -> bool {
+        self.target_table == owner_table
+    }
+
+    /// A self reference onto the very same column can only ever match a row to itself.
+    pub fn is_ambiguous_self_reference(&self, owner_table: &str) -> bool {
+        self.is_self_reference(owner_table) && self.source_column == self.target_column
+    }
 }
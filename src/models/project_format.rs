@@ -0,0 +1,26 @@
+use std::path::Path;
+
+/// The file format of a project, chosen by the project file's extension.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProjectFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+impl ProjectFormat {
+    pub fn filename(&self) -> &'static str {
+        match self {
+            ProjectFormat::Yaml => "dbloada.yaml",
+            ProjectFormat::Json => "dbloada.json",
+        }
+    }
+
+    /// `.json` files are JSON; every other file is YAML.
+    pub fn from_path(path: &Path) -> ProjectFormat {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ProjectFormat::Json,
+            _ => ProjectFormat::Yaml,
+        }
+    }
+}
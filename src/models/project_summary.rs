@@ -0,0 +1,70 @@
+use super::project::Project;
+
+/// An overview of a project and its tables, taken from the spec alone.
+pub fn project_summary(project: &Project) -> String {
+    let mut out = format!(
+        "project:    {}\napiVersion: {}\ntables:     {}\n",
+        project.name,
+        project.api_version,
+        project.spec.tables.len(),
+    );
+    if project.spec.tables.is_empty() {
+        return out;
+    }
+
+    let header = ["table", "columns", "source", "relationships"].map(str::to_string);
+    let rows: Vec<[String; 4]> = project
+        .spec
+        .tables
+        .iter()
+        .map(|t| {
+            [
+                t.name.clone(),
+                t.columns.len().to_string(),
+                t.source.type_name().to_string(),
+                t.relationships.len().to_string(),
+            ]
+        })
+        .collect();
+    let mut widths = header.clone().map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    out.push('\n');
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row.iter().zip(widths).map(|(cell, width)| format!("{cell:<width$}")).collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::init::init_impl::example_project;
+    use crate::models::{PROJECT_API_VERSION, ProjectSpec};
+
+    #[test]
+    fn project_summary_lists_every_table() {
+        let summary = project_summary(&example_project("demo"));
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines[0], "project:    demo");
+        assert_eq!(lines[1], format!("apiVersion: {PROJECT_API_VERSION}"));
+        assert_eq!(lines[4].split_whitespace().collect::<Vec<_>>(), ["table", "columns", "source", "relationships"]);
+        assert!(summary.lines().any(|l| l.split_whitespace().eq(["employee", "2", "cmd", "1"])), "summary:\n{summary}");
+    }
+
+    #[test]
+    fn project_summary_without_tables_has_no_table_list() {
+        let project = Project {
+            name: "empty".to_string(),
+            api_version: PROJECT_API_VERSION.to_string(),
+            spec: ProjectSpec { tables: vec![], includes: vec![], defaults: Default::default() },
+        };
+        assert_eq!(project_summary(&project), format!("project:    empty\napiVersion: {PROJECT_API_VERSION}\ntables:     0\n"));
+    }
+}
@@ -0,0 +1,61 @@
+/// Turns `raw`, such as a directory name, into a resource name: lowercase, hyphens for spaces and
+/// underscores, other characters dropped and at most 63 characters.
+pub fn sanitize_resource_name(raw: &str) -> String {
+    let s: String = raw
+        .to_lowercase()
+        .chars()
+        .map(|c| if c == ' ' || c == '_' { '-' } else { c })
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '-')
+        .collect();
+
+    let s = s.trim_matches('-').to_string();
+
+    // collapse consecutive hyphens
+    let mut result = String::with_capacity(s.len());
+    let mut prev_hyphen = false;
+    for c in s.chars() {
+        if c == '-' {
+            if !prev_hyphen {
+                result.push(c);
+            }
+            prev_hyphen = true;
+        } else {
+            result.push(c);
+            prev_hyphen = false;
+        }
+    }
+
+    // truncate to 63 chars, trim trailing hyphens
+    if result.len() > 63 {
+        result.truncate(63);
+    }
+    result.trim_end_matches('-').to_string()
+}
+
+/// Checks that `name` can name a project: 1 to 63 lowercase alphanumerics or '-', starting and
+/// ending with an alphanumeric.
+pub fn validate_resource_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if name.len() > 63 {
+        return Err(format!(
+            "name must be no more than 63 characters, got {}",
+            name.len()
+        ));
+    }
+    if !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(
+            "name must contain only lowercase alphanumeric characters or '-'".to_string(),
+        );
+    }
+    let first = name.chars().next().unwrap();
+    if !first.is_ascii_alphanumeric() {
+        return Err("name must start with an alphanumeric character".to_string());
+    }
+    let last = name.chars().last().unwrap();
+    if !last.is_ascii_alphanumeric() {
+        return Err("name must end with an alphanumeric character".to_string());
+    }
+    Ok(())
+}
@@ -1,15 +1,48 @@
 use std::fmt::Write;
+use thiserror::Error;
+use unicode_width::UnicodeWidthStr;
+use super::project::{ColumnType, TableSpec, parse_bool};
 
-#[derive(Debug)]
+#[derive(Debug, Error, PartialEq)]
+pub enum TableError {
+    #[error("table '{table}' has no column '{column}'")]
+    UnknownColumn { table: String, column: String },
+    #[error("cannot append table '{other}' to '{table}': columns {actual:?} do not match {expected:?}")]
+    ColumnMismatch { table: String, other: String, expected: Vec<String>, actual: Vec<String> },
+}
+
+#[derive(Debug, Clone)]
 pub struct Table {
     pub name: String,
     pub columns: Vec<String>,
+    /// Type of each column as declared in the spec, indexed like `columns`.
+    pub column_types: Vec<ColumnType>,
+    /// Whether an empty cell of each column is NULL, indexed like `columns`.
+    pub nullable: Vec<bool>,
     pub rows: Vec<Vec<String>>,
 }
 
 impl Table {
-    pub fn new(name: String, columns: Vec<String>, rows: Vec<Vec<String>>) -> Self {
-        Table { name, columns, rows }
+    pub fn new(name: String, columns: Vec<String>, column_types: Vec<ColumnType>, rows: Vec<Vec<String>>) -> Self {
+        let nullable = vec![true; columns.len()];
+        Table { name, columns, column_types, nullable, rows }
+    }
+
+    /// A table named and typed after `spec`, holding `rows`.
+    pub fn from_spec(spec: &TableSpec, rows: Vec<Vec<String>>) -> Self {
+        Table {
+            nullable: spec.columns.iter().map(|c| c.empty_is_null).collect(),
+            ..Table::new(
+                spec.name.clone(),
+                spec.columns.iter().map(|c| c.name.clone()).collect(),
+                spec.columns.iter().map(|c| c.column_type.clone()).collect(),
+                rows,
+            )
+        }
+    }
+
+    pub fn column_type(&self, index: usize) -> Option<&ColumnType> {
+        self.column_types.get(index)
     }
 
     pub fn headers(&self) -> &[String] {
@@ -31,16 +64,216 @@ impl Table {
     pub fn cell(&self, row: usize, col: usize) -> Option<&str> {
         self.rows.get(row).and_then(|r| r.get(col)).map(|s| s.as_str())
     }
+
+    /// Index of the first column called `name`.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+
+    pub fn get(&self, row: usize, column: &str) -> Option<&str> {
+        self.cell(row, self.column_index(column)?)
+    }
+
+    /// A copy with the same name and columns but no rows.
+    pub fn clone_empty(&self) -> Table {
+        Table {
+            name: self.name.clone(),
+            columns: self.columns.clone(),
+            column_types: self.column_types.clone(),
+            nullable: self.nullable.clone(),
+            rows: vec![],
+        }
+    }
+
+    /// Returns a copy holding only the rows for which `f` returns true.
+    pub fn filter<F: Fn(&[String]) -> bool>(&self, f: F) -> Table {
+        let rows = self.rows.iter().filter(|row| f(row)).cloned().collect();
+        Table { rows, ..self.clone_empty() }
+    }
+
+    /// Returns a copy holding only the named columns, in the order given.
+    pub fn select(&self, names: &[&str]) -> Result<Table, TableError> {
+        let indices = names
+            .iter()
+            .map(|name| {
+                self.column_index(name).ok_or_else(|| TableError::UnknownColumn {
+                    table: self.name.clone(),
+                    column: name.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| indices.iter().map(|&i| row.get(i).cloned().unwrap_or_default()).collect())
+            .collect();
+        Ok(Table {
+            name: self.name.clone(),
+            columns: names.iter().map(|n| n.to_string()).collect(),
+            column_types: indices.iter().map(|&i| self.column_types[i].clone()).collect(),
+            nullable: indices.iter().map(|&i| self.nullable.get(i).copied().unwrap_or(true)).collect(),
+            rows,
+        })
+    }
+
+    /// Adds the rows of `other`, which must have the same columns in the same order.
+    pub fn append(&mut self, other: Table) -> Result<(), TableError> {
+        if other.columns != self.columns {
+            return Err(TableError::ColumnMismatch {
+                table: self.name.clone(),
+                other: other.name,
+                expected: self.columns.clone(),
+                actual: other.columns,
+            });
+        }
+        self.rows.extend(other.rows);
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&table_to_string(self))
+    }
+}
+
+/// How cells of `bool` columns are shown by `table_to_string_with_types`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoolDisplay {
+    /// The token as it appeared in the source.
+    #[default]
+    Raw,
+    Checkmark,
+    YesNo,
+}
+
+impl BoolDisplay {
+    pub fn render(&self, value: &str) -> String {
+        match (self, parse_bool(value)) {
+            (BoolDisplay::Checkmark, Some(true)) => "✓".to_string(),
+            (BoolDisplay::Checkmark, Some(false)) => "✗".to_string(),
+            (BoolDisplay::YesNo, Some(true)) => "Y".to_string(),
+            (BoolDisplay::YesNo, Some(false)) => "N".to_string(),
+            _ => value.to_string(),
+        }
+    }
+}
+
+/// How `table_to_string_with_opts` renders cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableStringOptions {
+    pub bool_display: BoolDisplay,
+    /// Cut header and cell values wider than this many terminal columns, ending them with `…`.
+    pub max_column_width: Option<usize>,
 }
 
 pub fn table_to_string(table: &Table) -> String {
-    let col_count = table.num_columns();
-    let mut widths: Vec<usize> = table.columns.iter().map(|c| c.len()).collect();
+    render_table(table, &table.column_types, &table.rows, None)
+}
+
+/// Like `table_to_string`, but renders the cells of `bool` columns using
+/// `bool_display`. `column_types` is indexed like the table's columns.
+pub fn table_to_string_with_types(table: &Table, column_types: &[ColumnType], bool_display: BoolDisplay) -> String {
+    table_to_string_with_opts(table, column_types, &TableStringOptions { bool_display, max_column_width: None })
+}
 
+pub fn table_to_string_with_opts(table: &Table, column_types: &[ColumnType], options: &TableStringOptions) -> String {
+    let rows: Vec<Vec<String>> = table
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(i, val)| match column_types.get(i) {
+                    Some(ColumnType::Bool) => options.bool_display.render(val),
+                    _ => val.clone(),
+                })
+                .collect()
+        })
+        .collect();
+    render_table(table, column_types, &rows, options.max_column_width)
+}
+
+/// Cuts `value` to at most `max_width` terminal columns, ending with `…` when anything was cut.
+pub fn truncate_to_width(value: &str, max_width: usize) -> String {
+    if value.width() <= max_width {
+        return value.to_string();
+    }
+    let mut out = String::new();
+    let mut width = 0;
+    for c in value.chars() {
+        let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+        if width + char_width + 1 > max_width {
+            break;
+        }
+        width += char_width;
+        out.push(c);
+    }
+    out.push('…');
+    out
+}
+
+fn html_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders the table as an HTML `<table>` captioned with the table name.
+pub fn table_to_html(table: &Table) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "<table>");
+    let _ = writeln!(out, "  <caption>{}</caption>", html_escape(&table.name));
+    let _ = writeln!(out, "  <thead>");
+    let header: String = table.columns.iter().map(|c| format!("<th>{}</th>", html_escape(c))).collect();
+    let _ = writeln!(out, "    <tr>{}</tr>", header);
+    let _ = writeln!(out, "  </thead>");
+    let _ = writeln!(out, "  <tbody>");
     for row in &table.rows {
+        let cells: String = (0..table.num_columns())
+            .map(|i| format!("<td>{}</td>", html_escape(row.get(i).map(|s| s.as_str()).unwrap_or(""))))
+            .collect();
+        let _ = writeln!(out, "    <tr>{}</tr>", cells);
+    }
+    let _ = writeln!(out, "  </tbody>");
+    let _ = writeln!(out, "</table>");
+    out
+}
+
+/// Pads `value` to `width` terminal columns, on the left for numeric columns.
+fn pad_cell(value: &str, width: usize, column_type: Option<&ColumnType>) -> String {
+    let padding = " ".repeat(width.saturating_sub(value.width()));
+    if column_type.is_some_and(ColumnType::is_numeric) {
+        format!(" {padding}{value} ")
+    } else {
+        format!(" {value}{padding} ")
+    }
+}
+
+fn render_table(table: &Table, column_types: &[ColumnType], rows: &[Vec<String>], max_column_width: Option<usize>) -> String {
+    let fit = |value: &str| match max_column_width {
+        Some(max) => truncate_to_width(value, max),
+        None => value.to_string(),
+    };
+    let headers: Vec<String> = table.columns.iter().map(|c| fit(c)).collect();
+    let rows: Vec<Vec<String>> = rows.iter().map(|row| row.iter().map(|val| fit(val)).collect()).collect();
+
+    let col_count = table.num_columns();
+    let mut widths: Vec<usize> = headers.iter().map(|c| c.width()).collect();
+
+    for row in &rows {
         for (i, val) in row.iter().enumerate() {
             if i < col_count {
-                widths[i] = widths[i].max(val.len());
+                widths[i] = widths[i].max(val.width());
             }
         }
     }
@@ -62,20 +295,17 @@ pub fn table_to_string(table: &Table) -> String {
     let header: String = widths
         .iter()
         .enumerate()
-        .map(|(i, w)| format!(" {:width$} ", table.columns[i], width = w))
+        .map(|(i, &w)| pad_cell(&headers[i], w, column_types.get(i)))
         .collect::<Vec<_>>()
         .join("|");
     let _ = writeln!(out, "|{}|", header);
     let _ = writeln!(out, "{}", separator);
 
-    for row in &table.rows {
+    for row in &rows {
         let line: String = widths
             .iter()
             .enumerate()
-            .map(|(i, w)| {
-                let val = row.get(i).map(|s| s.as_str()).unwrap_or("");
-                format!(" {:width$} ", val, width = w)
-            })
+            .map(|(i, &w)| pad_cell(row.get(i).map(|s| s.as_str()).unwrap_or(""), w, column_types.get(i)))
             .collect::<Vec<_>>()
             .join("|");
         let _ = writeln!(out, "|{}|", line);
@@ -94,6 +324,7 @@ mod tests {
         let table = Table::new(
             "test".to_string(),
             vec!["a".to_string(), "b".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
             vec![vec!["1".to_string(), "2".to_string()]],
         );
         assert_eq!(table.name, "test");
@@ -107,6 +338,7 @@ mod tests {
         let table = Table::new(
             "t".to_string(),
             vec!["x".to_string()],
+            vec![ColumnType::String { max_length: None }; 1],
             vec![vec!["v0".to_string()], vec!["v1".to_string()]],
         );
         assert_eq!(table.row(0), Some(vec!["v0".to_string()].as_slice()));
@@ -119,6 +351,7 @@ mod tests {
         let table = Table::new(
             "t".to_string(),
             vec!["a".to_string(), "b".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
             vec![vec!["r0c0".to_string(), "r0c1".to_string()]],
         );
         assert_eq!(table.cell(0, 0), Some("r0c0"));
@@ -127,11 +360,106 @@ mod tests {
         assert_eq!(table.cell(0, 2), None);
     }
 
+    fn people() -> Table {
+        Table::new(
+            "people".to_string(),
+            vec!["name".to_string(), "age".to_string(), "city".to_string()],
+            vec![ColumnType::String { max_length: None }; 3],
+            vec![
+                vec!["Alice".to_string(), "30".to_string(), "London".to_string()],
+                vec!["Bob".to_string(), "25".to_string(), "Berlin".to_string()],
+            ],
+        )
+    }
+
+    #[test]
+    fn select_keeps_only_named_columns() {
+        let selected = people().select(&["name", "city"]).unwrap();
+        assert_eq!(selected.name, "people");
+        assert_eq!(selected.headers(), &["name", "city"]);
+        assert_eq!(selected.row(1), Some(vec!["Bob".to_string(), "Berlin".to_string()].as_slice()));
+    }
+
+    #[test]
+    fn select_reorders_columns() {
+        let mut table = people();
+        table.column_types[1] = ColumnType::Int64;
+        let selected = table.select(&["city", "age", "name"]).unwrap();
+        assert_eq!(selected.headers(), &["city", "age", "name"]);
+        assert_eq!(selected.column_type(1), Some(&ColumnType::Int64));
+        assert_eq!(selected.row(0), Some(vec!["London".to_string(), "30".to_string(), "Alice".to_string()].as_slice()));
+    }
+
+    #[test]
+    fn select_rejects_unknown_column() {
+        let err = people().select(&["name", "email"]).unwrap_err();
+        assert_eq!(err, TableError::UnknownColumn { table: "people".to_string(), column: "email".to_string() });
+        assert_eq!(err.to_string(), "table 'people' has no column 'email'");
+    }
+
+    #[test]
+    fn append_extends_rows() {
+        let mut table = people();
+        let before = table.num_rows();
+        table.append(people()).unwrap();
+        assert_eq!(table.num_rows(), before * 2);
+        assert_eq!(table.row(before), people().row(0));
+    }
+
+    #[test]
+    fn append_rejects_mismatched_columns() {
+        let mut table = people();
+        let other = people().select(&["age", "name"]).unwrap();
+        let err = table.append(other).unwrap_err();
+        assert!(matches!(err, TableError::ColumnMismatch { ref actual, .. } if actual == &["age", "name"]));
+        assert!(err.to_string().contains("do not match"), "error was: {err}");
+        assert_eq!(table.num_rows(), people().num_rows());
+    }
+
+    #[test]
+    fn get_looks_up_cell_by_column_name() {
+        let table = people();
+        assert_eq!(table.get(1, "city"), Some("Berlin"));
+        assert_eq!(table.get(0, "email"), None);
+        assert_eq!(table.column_index("email"), None);
+        assert_eq!(table.get(2, "name"), None);
+    }
+
+    #[test]
+    fn get_uses_first_of_duplicate_column_names() {
+        let table = Table::new(
+            "t".to_string(),
+            vec!["id".to_string(), "code".to_string(), "code".to_string()],
+            vec![ColumnType::String { max_length: None }; 3],
+            vec![vec!["1".to_string(), "first".to_string(), "second".to_string()]],
+        );
+        assert_eq!(table.column_index("code"), Some(1));
+        assert_eq!(table.get(0, "code"), Some("first"));
+    }
+
+    #[test]
+    fn display_matches_table_to_string() {
+        let table = people();
+        assert_eq!(table.to_string(), table_to_string(&table));
+    }
+
+    #[test]
+    fn filter_keeps_matching_rows() {
+        let table = people();
+        let city = table.column_index("city").unwrap();
+        let filtered = table.filter(|row| row[city] == "Berlin");
+        assert_eq!(filtered.headers(), table.headers());
+        assert_eq!(filtered.num_rows(), 1);
+        assert_eq!(filtered.cell(0, 0), Some("Bob"));
+        assert_eq!(table.filter(|_| false).num_rows(), 0);
+    }
+
     #[test]
     fn table_to_string_includes_summary() {
         let table = Table::new(
             "users".to_string(),
             vec!["name".to_string(), "age".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
             vec![vec!["Alice".to_string(), "30".to_string()]],
         );
         let output = table_to_string(&table);
@@ -143,6 +471,7 @@ mod tests {
         let table = Table::new(
             "t".to_string(),
             vec!["a".to_string()],
+            vec![ColumnType::String { max_length: None }; 1],
             vec![vec!["x".to_string()]],
         );
         let output = table_to_string(&table);
@@ -160,6 +489,7 @@ mod tests {
         let table = Table::new(
             "t".to_string(),
             vec!["name".to_string(), "id".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
             vec![
                 vec!["Alice".to_string(), "1".to_string()],
                 vec!["Bob".to_string(), "22".to_string()],
@@ -170,11 +500,86 @@ mod tests {
         assert!(output.contains("| Bob   | 22 |"));
     }
 
+    #[test]
+    fn table_to_string_aligns_wide_and_combining_characters() {
+        let table = Table::new(
+            "t".to_string(),
+            vec!["city".to_string(), "note".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
+            vec![
+                vec!["東京".to_string(), "cafe\u{301}".to_string()],
+                vec!["Zürich".to_string(), "x".to_string()],
+            ],
+        );
+        let output = table_to_string(&table);
+        let lines: Vec<&str> = output.lines().skip(1).collect();
+        let boundaries = |line: &str| -> Vec<usize> {
+            let mut col = 0;
+            let mut found = Vec::new();
+            for c in line.chars() {
+                if c == '|' || c == '+' {
+                    found.push(col);
+                }
+                col += unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+            }
+            found
+        };
+        for line in &lines {
+            assert_eq!(boundaries(line), boundaries(lines[0]), "output was:\n{output}");
+        }
+        assert!(output.contains("| 東京   | cafe\u{301} |"), "output was:\n{output}");
+    }
+
+    #[test]
+    fn truncate_to_width_counts_terminal_columns() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("abcdefgh", 5), "abcd…");
+        // each CJK character is two columns wide, so only one fits before the ellipsis
+        assert_eq!(truncate_to_width("東京都庁", 4), "東…");
+        assert_eq!(truncate_to_width("cafe\u{301}sss", 5), "cafe\u{301}…");
+    }
+
+    #[test]
+    fn table_to_string_with_opts_truncates_wide_cells_and_headers() {
+        let table = Table::new(
+            "t".to_string(),
+            vec!["a_very_long_header".to_string(), "id".to_string()],
+            vec![ColumnType::String { max_length: None }, ColumnType::Int64],
+            vec![vec!["x".repeat(5000), "1".to_string()]],
+        );
+        let options = TableStringOptions { max_column_width: Some(8), ..Default::default() };
+        let output = table_to_string_with_opts(&table, &table.column_types, &options);
+        assert!(output.contains("| a_very_… | id |"), "output was:\n{output}");
+        assert!(output.contains(&format!("| {}… |  1 |", "x".repeat(7))), "output was:\n{output}");
+        assert!(output.lines().all(|line| line.len() < 100));
+
+        assert!(table_to_string(&table).contains(&"x".repeat(5000)));
+    }
+
+    #[test]
+    fn table_to_string_right_aligns_numeric_columns() {
+        let table = Table::new(
+            "t".to_string(),
+            vec!["name".to_string(), "num".to_string()],
+            vec![ColumnType::String { max_length: None }, ColumnType::Int64],
+            vec![
+                vec!["Alice".to_string(), "1".to_string()],
+                vec!["Bob".to_string(), "22".to_string()],
+            ],
+        );
+        let output = table_to_string(&table);
+        assert!(output.contains("| name  | num |"), "output was:\n{output}");
+        assert!(output.contains("| Alice |   1 |"), "output was:\n{output}");
+        assert!(output.contains("| Bob   |  22 |"), "output was:\n{output}");
+        assert!(output.contains("+-------+-----+"), "output was:\n{output}");
+    }
+
     #[test]
     fn table_to_string_empty_table() {
         let table = Table::new(
             "empty".to_string(),
             vec!["col".to_string()],
+            vec![ColumnType::String { max_length: None }; 1],
             vec![],
         );
         let output = table_to_string(&table);
@@ -183,4 +588,50 @@ mod tests {
         // summary, separator, header, separator, separator (no data rows)
         assert_eq!(lines.len(), 5);
     }
+
+    #[test]
+    fn table_to_string_with_types_renders_bool_checkmarks() {
+        let table = Table::new(
+            "t".to_string(),
+            vec!["name".to_string(), "active".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
+            vec![
+                vec!["true".to_string(), "true".to_string()],
+                vec!["false".to_string(), "false".to_string()],
+            ],
+        );
+        let types = vec![ColumnType::String { max_length: None }, ColumnType::Bool];
+        let output = table_to_string_with_types(&table, &types, BoolDisplay::Checkmark);
+        assert!(output.contains("| true  | ✓      |"), "output was:\n{output}");
+        assert!(output.contains("| false | ✗      |"), "output was:\n{output}");
+    }
+
+    #[test]
+    fn table_to_html_escapes_cells_and_has_one_header_row() {
+        let table = Table::new(
+            "cmp".to_string(),
+            vec!["expr".to_string(), "note".to_string()],
+            vec![ColumnType::String { max_length: None }; 2],
+            vec![
+                vec!["a < b".to_string(), "x & y".to_string()],
+                vec!["c".to_string(), "d".to_string()],
+            ],
+        );
+        let html = table_to_html(&table);
+        assert!(html.contains("<td>a &lt; b</td><td>x &amp; y</td>"), "html was:\n{html}");
+        assert!(html.contains("<caption>cmp</caption>"));
+        let thead = &html[html.find("<thead>").unwrap()..html.find("</thead>").unwrap()];
+        let tbody = &html[html.find("<tbody>").unwrap()..html.find("</tbody>").unwrap()];
+        assert_eq!(thead.matches("<tr>").count(), 1);
+        assert!(thead.contains("<th>expr</th><th>note</th>"));
+        assert_eq!(tbody.matches("<tr>").count(), 2);
+    }
+
+    #[test]
+    fn bool_display_render() {
+        assert_eq!(BoolDisplay::YesNo.render("1"), "Y");
+        assert_eq!(BoolDisplay::YesNo.render("no"), "N");
+        assert_eq!(BoolDisplay::Checkmark.render("maybe"), "maybe");
+        assert_eq!(BoolDisplay::Raw.render("TRUE"), "TRUE");
+    }
 }
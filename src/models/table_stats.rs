@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use super::project::ColumnType;
+use super::table::Table;
+
+/// Summary of the values of one column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub column: String,
+    pub rows: usize,
+    /// Cells that are empty or whitespace only.
+    pub empty: usize,
+    pub distinct: usize,
+    /// Only for numeric columns, over the cells that parse as numbers.
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub mean: Option<f64>,
+}
+
+pub fn column_stats(table: &Table) -> Vec<ColumnStats> {
+    (0..table.num_columns())
+        .map(|i| {
+            let cells: Vec<&str> = table.rows.iter().map(|row| row.get(i).map(|s| s.as_str()).unwrap_or("")).collect();
+            let numbers: Vec<i64> = match table.column_type(i) {
+                Some(column_type) if column_type.is_numeric() => {
+                    cells.iter().filter_map(|c| c.trim().parse::<i64>().ok()).collect()
+                }
+                _ => vec![],
+            };
+            ColumnStats {
+                column: table.columns[i].clone(),
+                rows: cells.len(),
+                empty: cells.iter().filter(|c| c.trim().is_empty()).count(),
+                distinct: cells.iter().collect::<HashSet<_>>().len(),
+                min: numbers.iter().min().copied(),
+                max: numbers.iter().max().copied(),
+                mean: (!numbers.is_empty()).then(|| numbers.iter().map(|&n| n as f64).sum::<f64>() / numbers.len() as f64),
+            }
+        })
+        .collect()
+}
+
+/// The stats of every column of `table` as a table with one row per column, for printing.
+pub fn describe_table(table: &Table) -> Table {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let rows = column_stats(table)
+        .into_iter()
+        .map(|s| {
+            vec![
+                s.column,
+                s.rows.to_string(),
+                s.empty.to_string(),
+                s.distinct.to_string(),
+                optional(s.min.map(|n| n.to_string())),
+                optional(s.max.map(|n| n.to_string())),
+                optional(s.mean.map(|m| format!("{m:.2}"))),
+            ]
+        })
+        .collect();
+    let columns = ["column", "rows", "empty", "distinct", "min", "max", "mean"];
+    let mut column_types = vec![ColumnType::Int64; columns.len()];
+    column_types[0] = ColumnType::String { max_length: None };
+    Table::new(table.name.clone(), columns.iter().map(|c| c.to_string()).collect(), column_types, rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cities() -> Table {
+        Table::new(
+            "city".to_string(),
+            vec!["name".to_string(), "population".to_string()],
+            vec![ColumnType::String { max_length: None }, ColumnType::Int64],
+            vec![
+                vec!["London".to_string(), "9000000".to_string()],
+                vec!["Berlin".to_string(), "3000000".to_string()],
+                vec!["London".to_string(), "".to_string()],
+            ],
+        )
+    }
+
+    #[test]
+    fn column_stats_counts_empty_and_distinct_cells() {
+        let stats = column_stats(&cities());
+        assert_eq!(stats[0].rows, 3);
+        assert_eq!(stats[0].empty, 0);
+        assert_eq!(stats[0].distinct, 2);
+        assert_eq!(stats[0].min, None);
+        assert_eq!(stats[1].empty, 1);
+        assert_eq!(stats[1].distinct, 3);
+    }
+
+    #[test]
+    fn column_stats_summarizes_numeric_columns() {
+        let stats = column_stats(&cities());
+        assert_eq!(stats[1].min, Some(3000000));
+        assert_eq!(stats[1].max, Some(9000000));
+        assert_eq!(stats[1].mean, Some(6000000.0));
+    }
+
+    #[test]
+    fn describe_table_has_a_row_per_column() {
+        let described = describe_table(&cities());
+        assert_eq!(described.name, "city");
+        assert_eq!(described.num_rows(), 2);
+        assert_eq!(described.get(1, "mean"), Some("6000000.00"));
+        assert_eq!(described.get(0, "mean"), Some(""));
+    }
+}
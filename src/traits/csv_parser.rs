@@ -1,3 +1,4 @@
+use std::io::Read;
 use async_trait::async_trait;
 use thiserror::Error;
 use crate::models::{Table, TableSpec};
@@ -8,7 +9,32 @@ pub enum CsvParserError {
     ParseError { table_name: String, message: String },
 }
 
+/// Receives each row, ordered like the table's columns, as soon as it is parsed.
+pub type RowCallback<'a> = dyn FnMut(Vec<String>) + Send + 'a;
+
 #[async_trait]
 pub trait CsvParser: Send + Sync {
     async fn parse(&self, content: &str, table: &TableSpec) -> Result<Table, CsvParserError>;
+    /// Parses like `parse` but hands every row to `on_row` instead of collecting them.
+    /// Returns the number of rows parsed.
+    async fn parse_rows(
+        &self,
+        content: &str,
+        table: &TableSpec,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, CsvParserError>;
+    /// Resolves the column identifiers without reading any record and returns a table with no rows.
+    async fn parse_header(&self, content: &str, table: &TableSpec) -> Result<Table, CsvParserError>;
+    /// The field index each column reads, in column order, resolved against the header.
+    async fn column_indices(&self, content: &str, table: &TableSpec) -> Result<Vec<usize>, CsvParserError>;
+    /// Like `parse_rows`, but reads UTF-8 CSV from `input` as it parses, so the whole
+    /// input is never held in memory. Not supported for tables that keep the raw line.
+    async fn parse_rows_from_reader(
+        &self,
+        input: Box<dyn Read + Send>,
+        table: &TableSpec,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, CsvParserError>;
+    /// Like `parse_header`, reading no further into `input` than the header.
+    async fn parse_header_from_reader(&self, input: Box<dyn Read + Send>, table: &TableSpec) -> Result<Table, CsvParserError>;
 }
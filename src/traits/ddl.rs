@@ -0,0 +1,49 @@
+use std::path::Path;
+use async_trait::async_trait;
+use thiserror::Error;
+use crate::models::Dialect;
+use super::file_system::FileSystemError;
+use super::project_io::ProjectIOError;
+
+#[derive(Debug, Error)]
+pub enum DdlError {
+    #[error("relationship '{relationship}' on table '{table}' references unknown table '{target_table}'")]
+    UnknownTargetTable {
+        relationship: String,
+        table: String,
+        target_table: String,
+    },
+    #[error("relationship '{relationship}' references unknown column '{column}' on table '{table}'")]
+    UnknownColumn {
+        relationship: String,
+        table: String,
+        column: String,
+    },
+    #[error("relationship '{relationship}' on table '{table}' sets '{column}' to NULL on delete, but the column is not nullable (emptyIsNull is false)")]
+    SetNullOnNotNullColumn {
+        relationship: String,
+        table: String,
+        column: String,
+    },
+    #[error("relationships form a cycle between tables: {}", .tables.join(" -> "))]
+    RelationshipCycle { tables: Vec<String> },
+    #[error("failed to order tables: {0}")]
+    TableOrder(String),
+    #[error("directory not found: {0}")]
+    DirectoryNotFound(String),
+    #[error("project file not found: {0}")]
+    ProjectFileNotFound(String),
+    #[error(transparent)]
+    IOError(#[from] ProjectIOError),
+    #[error("failed to write DDL: {0}")]
+    FileError(#[from] FileSystemError),
+}
+
+#[async_trait]
+pub trait Ddl: Send + Sync {
+    /// Writes the CREATE TABLE statements of every table in the project to `out`.
+    /// Returns the number of tables written.
+    async fn write_ddl(&self, path: &Path, out: &Path, dialect: Dialect) -> Result<usize, DdlError>;
+    /// Renders the project's tables and relationships as a Mermaid ER diagram.
+    async fn mermaid(&self, path: &Path) -> Result<String, DdlError>;
+}
@@ -2,11 +2,51 @@ use std::path::Path;
 use async_trait::async_trait;
 use super::init::InitError;
 use super::load::LoadError;
-use crate::models::LoadedProject;
+use super::freeze::FreezeError;
+use super::validate::ValidateError;
+use super::fmt::FmtError;
+use super::ddl::DdlError;
+use super::table_writer::{TableWriter, TableWriterError};
+use crate::models::{
+    Dialect, InitTemplate, LoadOptions, LoadedProject, Project, ProjectFormat, TableMapping,
+};
 
 #[async_trait]
 pub trait Engine: Send + Sync {
     async fn init(&self);
-    async fn init_project_dir(&self, path: &Path, name: Option<&str>, force: bool) -> Result<(), InitError>;
-    async fn load_project(&self, path: &Path) -> Result<LoadedProject, LoadError>;
+    async fn init_project_dir(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        force: bool,
+        overwrite_project: bool,
+        template: InitTemplate,
+        format: ProjectFormat,
+    ) -> Result<(), InitError>;
+    async fn add_table(
+        &self,
+        path: &Path,
+        name: &str,
+        filename: &str,
+        has_header: bool,
+        columns: &[String],
+        force: bool,
+    ) -> Result<(), InitError>;
+    fn example_project_yaml(&self) -> Result<String, InitError>;
+    async fn load_project(&self, path: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError>;
+    async fn load_bundle(&self, bundle: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError>;
+    async fn stream_table(
+        &self,
+        path: &Path,
+        table_name: &str,
+        on_row: &mut (dyn for<'r> FnMut(&'r [String]) + Send),
+    ) -> Result<usize, LoadError>;
+    async fn explain_project(&self, path: &Path) -> Result<Vec<TableMapping>, LoadError>;
+    async fn project_info(&self, path: &Path) -> Result<Project, LoadError>;
+    async fn write_tables(&self, loaded_project: &LoadedProject, writer: &dyn TableWriter) -> Result<(), TableWriterError>;
+    async fn validate_project(&self, path: &Path) -> Result<Vec<String>, ValidateError>;
+    async fn freeze_project(&self, path: &Path) -> Result<Vec<String>, FreezeError>;
+    async fn format_project(&self, path: &Path, verify: bool) -> Result<bool, FmtError>;
+    async fn write_ddl(&self, path: &Path, out: &Path, dialect: Dialect) -> Result<usize, DdlError>;
+    async fn mermaid_diagram(&self, path: &Path) -> Result<String, DdlError>;
 }
@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
+#[allow(clippy::enum_variant_names)]
 pub enum FileSystemError {
     #[error("failed to read file: {path}")]
     ReadError {
@@ -26,5 +27,13 @@ pub trait FileSystem: Send + Sync {
     async fn save(&self, content: &str, path: &std::path::Path) -> Result<(), FileSystemError>;
     async fn load(&self, path: &std::path::Path) -> Result<String, FileSystemError>;
     async fn load_bytes(&self, path: &std::path::Path) -> Result<Vec<u8>, FileSystemError>;
+    /// Opens `path` for reading it piece by piece instead of loading it whole.
+    async fn open_read(&self, path: &std::path::Path) -> Result<Box<dyn std::io::Read + Send>, FileSystemError>;
     async fn ensure_dir(&self, path: &std::path::Path) -> Result<(), FileSystemError>;
+    /// True for FIFOs, sockets and devices, i.e. anything that is neither a regular file nor a directory.
+    async fn is_special_file(&self, path: &std::path::Path) -> Result<bool, FileSystemError>;
+    /// True when anything, of any kind, is at `path`.
+    async fn exists(&self, path: &std::path::Path) -> Result<bool, FileSystemError>;
+    /// The absolute path with `..` and symlinks resolved. Fails when nothing is at `path`.
+    async fn canonicalize(&self, path: &std::path::Path) -> Result<PathBuf, FileSystemError>;
 }
@@ -0,0 +1,26 @@
+use std::path::Path;
+use async_trait::async_trait;
+use thiserror::Error;
+use super::file_system::FileSystemError;
+use super::project_serialization::ProjectSerializationError;
+
+#[derive(Debug, Error)]
+pub enum FmtError {
+    #[error("directory not found: {0}")]
+    DirectoryNotFound(String),
+    #[error("project file not found: {0}")]
+    ProjectFileNotFound(String),
+    #[error("project does not survive a serialization round trip:\n{diff}")]
+    RoundTripMismatch { diff: String },
+    #[error(transparent)]
+    FileError(#[from] FileSystemError),
+    #[error(transparent)]
+    SerializationError(#[from] ProjectSerializationError),
+}
+
+#[async_trait]
+pub trait Fmt: Send + Sync {
+    /// Rewrites the project file in canonical form and returns whether its content changed.
+    /// With `verify`, the re-serialized project must deserialize back to the same model.
+    async fn fmt(&self, path: &Path, verify: bool) -> Result<bool, FmtError>;
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+use async_trait::async_trait;
+use thiserror::Error;
+use super::file_system::FileSystemError;
+use super::project_io::ProjectIOError;
+use super::table_reader::TableReaderError;
+
+#[derive(Debug, Error)]
+pub enum FreezeError {
+    #[error("directory not found: {0}")]
+    DirectoryNotFound(String),
+    #[error("project file not found: {0}")]
+    ProjectFileNotFound(String),
+    #[error("failed to write CSV for table '{table_name}': {message}")]
+    CsvWriteError { table_name: String, message: String },
+    #[error("table '{table}' is defined in included file '{file}'; freeze only rewrites the main project file")]
+    IncludedTable { table: String, file: String },
+    #[error(transparent)]
+    IOError(#[from] ProjectIOError),
+    #[error(transparent)]
+    FileError(#[from] FileSystemError),
+    #[error(transparent)]
+    TableReaderError(#[from] TableReaderError),
+}
+
+#[async_trait]
+pub trait Freeze: Send + Sync {
+    /// Runs every cmd source once, writes its output to `data/<table>.csv` and
+    /// rewrites the project to use file sources. Returns the names of the frozen tables.
+    async fn freeze(&self, path: &Path) -> Result<Vec<String>, FreezeError>;
+}
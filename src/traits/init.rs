@@ -3,6 +3,7 @@ use async_trait::async_trait;
 use thiserror::Error;
 use super::project_io::ProjectIOError;
 use super::file_system::FileSystemError;
+use crate::models::{InitTemplate, ProjectFormat};
 
 #[derive(Debug, Error)]
 pub enum InitError {
@@ -12,15 +13,48 @@ pub enum InitError {
     InvalidDirectoryName(String),
     #[error("invalid resource name '{name}': {reason}")]
     InvalidResourceName { name: String, reason: String },
-    #[error("failed to write dbloada.yaml: {0}")]
+    #[error("failed to write the project file: {0}")]
     IOError(#[from] ProjectIOError),
     #[error("file operation failed: {0}")]
     FileError(#[from] FileSystemError),
     #[error("directory is not empty: {0} (use --force to override)")]
     DirectoryNotEmpty(String),
+    #[error("project file already exists: {0} (use --overwrite-project to replace it)")]
+    ProjectFileExists(String),
+    #[error("project file not found: {0}")]
+    ProjectFileNotFound(String),
+    #[error("table '{0}' already exists (use --force to replace it)")]
+    TableExists(String),
+    #[error("table '{table}' is defined in included file '{file}'; change it there")]
+    IncludedTable { table: String, file: String },
+    #[error("the project would be invalid: {}", .0.join("; "))]
+    InvalidProject(Vec<String>),
 }
 
 #[async_trait]
 pub trait Init: Send + Sync {
-    async fn init(&self, path: &Path, name: Option<&str>, force: bool) -> Result<(), InitError>;
+    /// `force` allows a non-empty directory; an existing project file is only replaced when `overwrite_project` is set.
+    /// The project is written as dbloada.yaml or dbloada.json depending on `format`.
+    async fn init(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        force: bool,
+        overwrite_project: bool,
+        template: InitTemplate,
+        format: ProjectFormat,
+    ) -> Result<(), InitError>;
+    /// Appends a table reading the CSV file `filename` to the project in `path`, with `columns` matched by header
+    /// name or, without a header, by position. A table of the same name is only replaced when `force` is set.
+    async fn add_table(
+        &self,
+        path: &Path,
+        name: &str,
+        filename: &str,
+        has_header: bool,
+        columns: &[String],
+        force: bool,
+    ) -> Result<(), InitError>;
+    /// The commented example project printed by `dbloada example`, as YAML.
+    fn example_project_yaml(&self) -> Result<String, InitError>;
 }
@@ -1,9 +1,13 @@
 use std::path::Path;
 use async_trait::async_trait;
 use thiserror::Error;
-use crate::models::LoadedProject;
+use crate::models::{
+    DanglingReference, DuplicateValue, LoadOptions, LoadedProject, Project, Table, TableMapping,
+    dangling_references_to_string, duplicate_values_to_string,
+};
 use super::project_io::ProjectIOError;
 use super::TableReaderError;
+use super::csv_parser::RowCallback;
 
 #[derive(Debug, Error)]
 pub enum LoadError {
@@ -11,6 +15,26 @@ pub enum LoadError {
     DirectoryNotFound(String),
     #[error("project file not found: {0}")]
     ProjectFileNotFound(String),
+    #[error("relationship '{relationship}' on table '{table}' references its own source column")]
+    AmbiguousSelfReference { table: String, relationship: String },
+    #[error("table '{0}' not found in project")]
+    UnknownTable(String),
+    #[error("only one table can read standard input, but {} do: {}", .0.len(), .0.join(", "))]
+    MultipleStdinTables(Vec<String>),
+    #[error("relationships form a cycle between tables: {}", .tables.join(" -> "))]
+    RelationshipCycle { tables: Vec<String> },
+    #[error("{} dangling reference(s): {}", .0.len(), dangling_references_to_string(.0, 10))]
+    DanglingReferences(Vec<DanglingReference>),
+    #[error("{} duplicate value(s) in unique columns: {}", .0.len(), duplicate_values_to_string(.0, 10))]
+    DuplicateValues(Vec<DuplicateValue>),
+    #[error("{} source file(s) not found: {}", .0.len(), .0.join("; "))]
+    MissingSourceFiles(Vec<String>),
+    #[error("load would have side effects: {}", .0.join("; "))]
+    SideEffects(Vec<String>),
+    #[error("{error} ({} table(s) read before the failure)", .tables.len())]
+    Partial { tables: Vec<Table>, error: Box<LoadError> },
+    #[error("bundle '{path}': {message}")]
+    Bundle { path: String, message: String },
     #[error(transparent)]
     IOError(#[from] ProjectIOError),
     #[error(transparent)]
@@ -19,5 +43,13 @@ pub enum LoadError {
 
 #[async_trait]
 pub trait Load: Send + Sync {
-    async fn load(&self, path: &Path) -> Result<LoadedProject, LoadError>;
+    async fn load(&self, path: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError>;
+    /// Extracts a `.tar.gz` project bundle to a temporary directory, loads it from there and removes it again.
+    async fn load_bundle(&self, bundle: &Path, options: &LoadOptions) -> Result<LoadedProject, LoadError>;
+    /// Reads a single table and hands its rows to `on_row` one by one instead of collecting them.
+    async fn stream_table(&self, path: &Path, table_name: &str, on_row: &mut RowCallback<'_>) -> Result<usize, LoadError>;
+    /// Tells for every table which source field each column reads, reading only headers.
+    async fn column_mappings(&self, path: &Path) -> Result<Vec<TableMapping>, LoadError>;
+    /// Reads and checks the project file without reading any table.
+    async fn read_project(&self, path: &Path) -> Result<Project, LoadError>;
 }
@@ -7,6 +7,11 @@ pub mod project_io;
 pub mod load;
 pub mod csv_parser;
 pub mod table_reader;
+pub mod freeze;
+pub mod ddl;
+pub mod table_writer;
+pub mod validate;
+pub mod fmt;
 
 pub use logger::Logger;
 pub use engine::Engine;
@@ -15,5 +20,10 @@ pub use file_system::{FileSystem, FileSystemError};
 pub use project_serialization::{ProjectSerialization, ProjectSerializationError};
 pub use project_io::{ProjectIO, ProjectIOError};
 pub use load::{Load, LoadError};
-pub use csv_parser::{CsvParser, CsvParserError};
+pub use csv_parser::{CsvParser, CsvParserError, RowCallback};
 pub use table_reader::{TableReader, TableReaderError};
+pub use freeze::{Freeze, FreezeError};
+pub use ddl::{Ddl, DdlError};
+pub use table_writer::{TableWriter, TableWriterError};
+pub use validate::{Validate, ValidateError};
+pub use fmt::{Fmt, FmtError};
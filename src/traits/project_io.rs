@@ -11,6 +11,16 @@ pub enum ProjectIOError {
     FileError(#[from] FileSystemError),
     #[error(transparent)]
     SerializationError(#[from] ProjectSerializationError),
+    #[error("included file '{file}': {source}")]
+    IncludeError { file: String, source: ProjectSerializationError },
+    #[error("include cycle: {0}")]
+    IncludeCycle(String),
+    #[error("includes nested deeper than {limit} levels: {chain}")]
+    IncludeDepthExceeded { limit: usize, chain: String },
+    #[error("a project may include at most {0} files")]
+    TooManyIncludes(usize),
+    #[error("table '{table}' in included file '{file}' is already defined in '{defined_in}'")]
+    DuplicateTable { table: String, file: String, defined_in: String },
 }
 
 #[async_trait]
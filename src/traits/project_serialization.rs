@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use thiserror::Error;
-use crate::models::{Project};
+use crate::models::{Project, ProjectSpec};
 
 #[derive(Debug, Error)]
 pub enum ProjectSerializationError {
@@ -10,10 +10,14 @@ pub enum ProjectSerializationError {
     DeserializeError(String),
     #[error("unexpected kind: expected '{expected}', got '{actual}'")]
     UnexpectedKind { expected: String, actual: String },
+    #[error("unsupported apiVersion: expected '{expected}', got '{actual}'")]
+    UnsupportedApiVersion { expected: String, actual: String },
 }
 
 #[async_trait]
 pub trait ProjectSerialization: Send + Sync {
     async fn serialize(&self, project: &Project) -> Result<String, ProjectSerializationError>;
     async fn deserialize(&self, content: &str) -> Result<Project, ProjectSerializationError>;
+    /// Parses a file listed in a project's `includes`.
+    async fn deserialize_include(&self, content: &str) -> Result<ProjectSpec, ProjectSerializationError>;
 }
@@ -1,9 +1,9 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use async_trait::async_trait;
 use thiserror::Error;
 use crate::models::{Table, TableSpec};
 use super::file_system::FileSystemError;
-use super::csv_parser::CsvParserError;
+use super::csv_parser::{CsvParserError, RowCallback};
 
 #[derive(Debug, Error)]
 pub enum TableReaderError {
@@ -21,7 +21,43 @@ pub enum TableReaderError {
 pub trait TableReader: Send + Sync {
     fn name(&self) -> &str;
     fn can_read(&self, table: &TableSpec) -> bool;
+    /// File name suffixes this reader picks up; empty for readers not chosen by file name.
+    fn extensions(&self) -> &[&str] {
+        &[]
+    }
     async fn read_table(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError>;
+
+    /// Hands every row to `on_row` instead of returning a `Table`. Readers that can
+    /// parse incrementally override this; the default reads the whole table first.
+    async fn read_rows(
+        &self,
+        table: &TableSpec,
+        project_dir: &Path,
+        on_row: &mut RowCallback<'_>,
+    ) -> Result<usize, TableReaderError> {
+        let result = self.read_table(table, project_dir).await?;
+        let row_count = result.num_rows();
+        result.rows.into_iter().for_each(on_row);
+        Ok(row_count)
+    }
+
+    /// Checks that the source is reachable and its columns resolve, returning a table
+    /// with no rows. Readers that can stop after the header override this.
+    async fn dry_read(&self, table: &TableSpec, project_dir: &Path) -> Result<Table, TableReaderError> {
+        Ok(self.read_table(table, project_dir).await?.clone_empty())
+    }
+
+    /// The source field index each column reads, when the reader reads fields by position.
+    /// Readers that do not (or cannot tell without running a command) return `None`.
+    async fn column_indices(&self, _table: &TableSpec, _project_dir: &Path) -> Result<Option<Vec<usize>>, TableReaderError> {
+        Ok(None)
+    }
+
+    /// The file the source names when nothing is there, so a load can fail before reading any table.
+    /// Readers that do not read a single named file return `None`.
+    async fn missing_file(&self, _table: &TableSpec, _project_dir: &Path) -> Result<Option<PathBuf>, TableReaderError> {
+        Ok(None)
+    }
 }
 
 pub async fn read(
@@ -36,3 +72,56 @@ pub async fn read(
     }
     Err(TableReaderError::NoReaderFound(table.name.clone()))
 }
+
+pub async fn dry_read(
+    readers: &[Box<dyn TableReader>],
+    table: &TableSpec,
+    project_dir: &Path,
+) -> Result<Table, TableReaderError> {
+    for reader in readers {
+        if reader.can_read(table) {
+            return reader.dry_read(table, project_dir).await;
+        }
+    }
+    Err(TableReaderError::NoReaderFound(table.name.clone()))
+}
+
+pub async fn column_indices(
+    readers: &[Box<dyn TableReader>],
+    table: &TableSpec,
+    project_dir: &Path,
+) -> Result<Option<Vec<usize>>, TableReaderError> {
+    for reader in readers {
+        if reader.can_read(table) {
+            return reader.column_indices(table, project_dir).await;
+        }
+    }
+    Err(TableReaderError::NoReaderFound(table.name.clone()))
+}
+
+pub async fn missing_file(
+    readers: &[Box<dyn TableReader>],
+    table: &TableSpec,
+    project_dir: &Path,
+) -> Result<Option<PathBuf>, TableReaderError> {
+    for reader in readers {
+        if reader.can_read(table) {
+            return reader.missing_file(table, project_dir).await;
+        }
+    }
+    Err(TableReaderError::NoReaderFound(table.name.clone()))
+}
+
+pub async fn read_rows(
+    readers: &[Box<dyn TableReader>],
+    table: &TableSpec,
+    project_dir: &Path,
+    on_row: &mut RowCallback<'_>,
+) -> Result<usize, TableReaderError> {
+    for reader in readers {
+        if reader.can_read(table) {
+            return reader.read_rows(table, project_dir, on_row).await;
+        }
+    }
+    Err(TableReaderError::NoReaderFound(table.name.clone()))
+}
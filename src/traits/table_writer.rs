@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use thiserror::Error;
+use crate::models::{LoadedProject, Table, TableSpec};
+
+#[derive(Debug, Error)]
+pub enum TableWriterError {
+    #[error("failed to write table '{table_name}': {message}")]
+    WriteError { table_name: String, message: String },
+    #[error("failed to connect to database: {0}")]
+    ConnectError(String),
+    #[error("failed to commit the written tables: {0}")]
+    CommitError(String),
+}
+
+#[async_trait]
+pub trait TableWriter: Send + Sync {
+    fn name(&self) -> &str;
+    /// Called once before any table of `loaded_project` is written.
+    async fn start(&self, _loaded_project: &LoadedProject) -> Result<(), TableWriterError> {
+        Ok(())
+    }
+    async fn write(&self, table: &Table, spec: &TableSpec) -> Result<(), TableWriterError>;
+    /// Called once after every table of `loaded_project` was written, to add what spans tables.
+    async fn finish(&self, _loaded_project: &LoadedProject) -> Result<(), TableWriterError> {
+        Ok(())
+    }
+}
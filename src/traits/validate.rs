@@ -0,0 +1,20 @@
+use std::path::Path;
+use async_trait::async_trait;
+use thiserror::Error;
+use super::project_io::ProjectIOError;
+
+#[derive(Debug, Error)]
+pub enum ValidateError {
+    #[error("directory not found: {0}")]
+    DirectoryNotFound(String),
+    #[error("project file not found: {0}")]
+    ProjectFileNotFound(String),
+    #[error(transparent)]
+    IOError(#[from] ProjectIOError),
+}
+
+#[async_trait]
+pub trait Validate: Send + Sync {
+    /// Checks the project spec without reading any table sources and returns every problem found.
+    async fn validate(&self, path: &Path) -> Result<Vec<String>, ValidateError>;
+}